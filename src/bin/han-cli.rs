@@ -0,0 +1,128 @@
+//! Print the telegrams of a power meter as they arrive.
+//!
+//! ```text
+//! han-cli [--json] [--dsmr2] [PORT]
+//! ```
+//!
+//! Reads from the serial port `PORT`, opened at 115200 8N1 as for the
+//! Swedish and Norwegian HAN port and DSMR 4 and later, or at 9600 7E1
+//! without checksums for DSMR 2.2 and 3 with `--dsmr2`. Reads captured
+//! telegrams from standard input if no port is given.
+
+use std::io::{self, BufRead, BufReader};
+use std::process::ExitCode;
+
+use han::json::JsonOptions;
+use han::serial::{SerialSettings, StripParity};
+use han::state::{Measurement, State};
+use han::{Direction, IoReader, Line, Power};
+
+const USAGE: &str = "usage: han-cli [--json] [--dsmr2] [PORT]";
+
+fn main() -> ExitCode {
+    let mut json = false;
+    let mut dsmr2 = false;
+    let mut path = None;
+    for arg in std::env::args().skip(1) {
+        match arg.as_str() {
+            "--json" => json = true,
+            "--dsmr2" => dsmr2 = true,
+            "-h" | "--help" => {
+                println!("{USAGE}");
+                return ExitCode::SUCCESS;
+            }
+            _ if path.is_none() && !arg.starts_with('-') => path = Some(arg),
+            _ => {
+                eprintln!("{USAGE}");
+                return ExitCode::FAILURE;
+            }
+        }
+    }
+
+    let input: Box<dyn BufRead> = match &path {
+        Some(path) if dsmr2 => match SerialSettings::DSMR2.open(path) {
+            Ok(port) => Box::new(BufReader::new(StripParity(port))),
+            Err(e) => {
+                eprintln!("{path}: {e}");
+                return ExitCode::FAILURE;
+            }
+        },
+        Some(path) => match SerialSettings::SWEDISH_HAN.open(path) {
+            Ok(port) => Box::new(BufReader::new(port)),
+            Err(e) => {
+                eprintln!("{path}: {e}");
+                return ExitCode::FAILURE;
+            }
+        },
+        None => Box::new(io::stdin().lock()),
+    };
+    let reader = if dsmr2 {
+        IoReader::new(input).without_checksum()
+    } else {
+        IoReader::new(input)
+    };
+
+    match run(reader, json) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("{e}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn run(reader: IoReader<impl BufRead>, json: bool) -> io::Result<()> {
+    let mut state = State::default();
+    for readout in reader {
+        let readout = readout?;
+        let telegram = match readout.to_telegram() {
+            Ok(telegram) => telegram,
+            Err(e) => {
+                eprintln!("skipping telegram: {e}");
+                continue;
+            }
+        };
+        for obj in telegram.objects_with_context() {
+            match obj {
+                Ok(obj) => state.insert(obj),
+                Err(e) => eprintln!("skipping object: {e}"),
+            }
+        }
+
+        if json {
+            println!("{}", state.to_json_with(&JsonOptions::new()));
+        } else {
+            print_table(telegram.identification, &state);
+        }
+    }
+    Ok(())
+}
+
+fn print_table(identification: &str, state: &State) {
+    let value = |v: Option<f64>| v.map_or_else(|| "-".to_owned(), |v| v.to_string());
+    let net = |m: &Measurement| {
+        let from = m.get(Power::Active, Direction::FromGrid)?;
+        let to = m.get(Power::Active, Direction::ToGrid).unwrap_or(0.);
+        Some(from - to)
+    };
+
+    match state.datetime {
+        Some(time) => println!("{identification} at {time}"),
+        None => println!("{identification}"),
+    }
+    println!(
+        "  energy {:>12} kWh from grid {:>12} kWh to grid",
+        value(state.energy.get(Power::Active, Direction::FromGrid)),
+        value(state.energy.get(Power::Active, Direction::ToGrid)),
+    );
+    println!("  power  {:>12} kW", value(net(&state.power)));
+    for (line, name) in [(Line::L1, "L1"), (Line::L2, "L2"), (Line::L3, "L3")] {
+        let l = state.line(line);
+        println!(
+            "  {name}     {:>12} kW {:>8} V {:>8} A",
+            value(net(&l.power)),
+            value(l.voltage),
+            value(l.current),
+        );
+    }
+}
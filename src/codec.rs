@@ -0,0 +1,167 @@
+use tokio_util::bytes::{Buf, BytesMut};
+use tokio_util::codec::Decoder;
+
+use crate::{Readout, DEFAULT_BUFFER_SIZE};
+
+/// A [`Decoder`] framing the raw UART output of a power meter into
+/// [`Readout`]s, for use with [`FramedRead`](tokio_util::codec::FramedRead).
+///
+/// Like the readers, bytes preceding a `/` are skipped and telegrams that do
+/// not fit in `N` bytes are discarded.
+///
+/// ```
+/// use tokio_util::codec::Decoder;
+/// use tokio_util::bytes::BytesMut;
+///
+/// let mut codec = han::TelegramCodec::new();
+/// let mut src = BytesMut::from(&include_bytes!("../test/ell.txt")[..]);
+///
+/// let readout = codec.decode(&mut src)?.unwrap();
+/// assert_eq!(readout.to_telegram().unwrap().checksum, 0x9ab5);
+/// # Ok::<(), std::io::Error>(())
+/// ```
+#[derive(Debug)]
+pub struct TelegramCodec<const N: usize = DEFAULT_BUFFER_SIZE> {
+    /// Whether telegrams end with a checksum.
+    checksum: bool,
+}
+
+impl TelegramCodec {
+    /// Construct a new codec.
+    pub fn new() -> Self {
+        Self::new_sized()
+    }
+}
+
+impl Default for TelegramCodec {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<const N: usize> TelegramCodec<N> {
+    /// Construct a new codec with a buffer of `N` bytes per readout.
+    pub fn new_sized() -> Self {
+        Self { checksum: true }
+    }
+
+    /// Decode telegrams that end with a bare `!` instead of a checksum, as
+    /// sent by older DSMR 2.2 and 3.0 meters.
+    ///
+    /// See [`Reader::without_checksum`](crate::Reader::without_checksum).
+    pub fn without_checksum(mut self) -> Self {
+        self.checksum = false;
+        self
+    }
+}
+
+impl<const N: usize> Decoder for TelegramCodec<N> {
+    type Item = Readout<N>;
+    type Error = std::io::Error;
+
+    fn decode(&mut self, src: &mut BytesMut) -> Result<Option<Self::Item>, Self::Error> {
+        loop {
+            let Some(start) = src.iter().position(|&b| b == b'/') else {
+                src.clear();
+                return Ok(None);
+            };
+            src.advance(start);
+
+            // end of telegram 4 bytes after the '!', if checksummed
+            let checksum_len = if self.checksum { 4 } else { 0 };
+            let end = src
+                .iter()
+                .position(|&b| b == b'!')
+                .map(|i| i + 1 + checksum_len);
+
+            match end {
+                Some(end) if end <= N => {
+                    if src.len() < end {
+                        src.reserve(end - src.len());
+                        return Ok(None);
+                    }
+
+                    let mut buffer = [0; N];
+                    buffer[..end].copy_from_slice(&src[..end]);
+                    src.advance(end);
+                    return Ok(Some(Readout {
+                        buffer,
+                        crc: None,
+                        verify: self.checksum,
+                    }));
+                }
+                None if src.len() < N => return Ok(None),
+                // buffer overflow
+                _ => src.advance(N.min(src.len())),
+            }
+        }
+    }
+
+    fn decode_eof(&mut self, src: &mut BytesMut) -> Result<Option<Self::Item>, Self::Error> {
+        let readout = self.decode(src)?;
+        if readout.is_none() {
+            // an incomplete telegram at EOF is discarded
+            src.clear();
+        }
+        Ok(readout)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tokio_util::bytes::BytesMut;
+    use tokio_util::codec::Decoder;
+
+    use super::TelegramCodec;
+
+    #[test]
+    fn chunked() {
+        let bytes = include_bytes!("../test/ell.txt");
+        let stream = [&bytes[300..], &bytes[..], &bytes[..]].concat();
+
+        for chunk in [1, 7, 64, 4096] {
+            let mut codec = TelegramCodec::new();
+            let mut src = BytesMut::new();
+            let mut checksums = std::vec::Vec::new();
+
+            for part in stream.chunks(chunk) {
+                src.extend_from_slice(part);
+                while let Some(readout) = codec.decode(&mut src).unwrap() {
+                    checksums.push(readout.checksum().unwrap());
+                }
+            }
+
+            assert!(codec.decode_eof(&mut src).unwrap().is_none());
+            assert!(src.is_empty());
+            assert_eq!(checksums, [0x9ab5, 0x9ab5], "chunk size {}", chunk);
+        }
+    }
+
+    #[test]
+    fn without_checksum() {
+        let bytes = include_bytes!("../test/ell.txt");
+        let legacy = [&bytes[..bytes.len() - 6], b"\r\n"].concat();
+        let mut codec = TelegramCodec::new().without_checksum();
+        let mut src = BytesMut::from(&[&legacy[..], &legacy[..]].concat()[..]);
+
+        for _ in 0..2 {
+            let readout = codec.decode(&mut src).unwrap().unwrap();
+            assert_eq!(readout.to_telegram().unwrap().objects().count(), 27);
+        }
+        assert!(codec.decode_eof(&mut src).unwrap().is_none());
+    }
+
+    #[test]
+    fn oversized() {
+        let bytes = include_bytes!("../test/oversized.txt");
+        let mut codec = TelegramCodec::new();
+        let mut src = BytesMut::from(&bytes[..]);
+
+        let readout = codec.decode(&mut src).unwrap().unwrap();
+        assert_eq!(readout.checksum().unwrap(), 0x9ab5);
+
+        let mut codec = TelegramCodec::<256>::new_sized();
+        let mut src = BytesMut::from(&include_bytes!("../test/ell.txt")[..]);
+        assert!(codec.decode_eof(&mut src).unwrap().is_none());
+    }
+}
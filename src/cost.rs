@@ -0,0 +1,295 @@
+//! Energy cost from a price schedule and the energy registers of successive
+//! [`State`]s.
+//!
+//! ```
+//! use han::cost::{Costs, Prices};
+//! use han::{Direction, Object, Power, WattHours, state::State};
+//! use time::macros::datetime;
+//!
+//! let state = |dt, wh| {
+//!     let mut state = State::default();
+//!     state.insert(Object::DateTime(dt));
+//!     state.insert(Object::Energy(Direction::FromGrid, WattHours(wh).into()));
+//!     state
+//! };
+//!
+//! let mut costs = Costs::new(Prices::Flat(1.5));
+//! costs.push(&state(datetime!(2024-01-01 12:00 +1), 1_000_000));
+//! assert_eq!(costs.push(&state(datetime!(2024-01-01 13:00 +1), 1_002_000)), Some(3.0));
+//! assert_eq!(costs.today(), 3.0);
+//! ```
+
+use core::ops::Range;
+
+use time::{Duration, OffsetDateTime};
+
+use crate::state::State;
+use crate::{Direction, Power};
+
+/// The price of active energy received from the grid, per kWh.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Prices<'a> {
+    /// The same price around the clock.
+    Flat(f64),
+    /// A day and a night price, with the day price applying during
+    /// `day_hours` (e.g. `6..22`) of local time.
+    DayNight {
+        /// The price during the day.
+        day: f64,
+        /// The price during the night.
+        night: f64,
+        /// The hours of the day price.
+        day_hours: Range<u8>,
+    },
+    /// Hourly prices from `start`, such as Nord Pool spot prices.
+    Hourly {
+        /// The start of the first hour.
+        start: OffsetDateTime,
+        /// The price of each hour.
+        prices: &'a [f64],
+    },
+}
+
+impl Prices<'_> {
+    /// The price at `time`, or `None` if outside the hourly prices.
+    pub fn at(&self, time: OffsetDateTime) -> Option<f64> {
+        match self {
+            Prices::Flat(price) => Some(*price),
+            Prices::DayNight {
+                day,
+                night,
+                day_hours,
+            } => Some(if day_hours.contains(&time.hour()) {
+                *day
+            } else {
+                *night
+            }),
+            Prices::Hourly { start, prices } => {
+                let hours = (time - *start).whole_seconds().div_euclid(3600);
+                prices.get(usize::try_from(hours).ok()?).copied()
+            }
+        }
+    }
+
+    /// The cost of the active energy received from the grid between two
+    /// timestamped states. The energy is assumed to be received evenly over
+    /// the interval, which is priced hour by hour.
+    pub fn cost(&self, earlier: &State, later: &State) -> Option<f64> {
+        Some(self.parts(earlier, later)?.map(|(_, cost)| cost).sum())
+    }
+
+    /// The start and cost of each part of the interval between two states,
+    /// split at hour boundaries. `None` unless every part has a price.
+    fn parts<'s>(
+        &'s self,
+        earlier: &State,
+        later: &State,
+    ) -> Option<impl Iterator<Item = (OffsetDateTime, f64)> + 's> {
+        let (start, end) = (earlier.datetime?, later.datetime?);
+        let energy = later.energy_since(earlier, Power::Active, Direction::FromGrid, None)?;
+        if end < start || hours(start, end).any(|(t, _)| self.at(t).is_none()) {
+            return None;
+        }
+
+        let total = (end - start).as_seconds_f64();
+        Some(hours(start, end).map(move |(from, to)| {
+            let share = if total > 0. {
+                (to - from).as_seconds_f64() / total
+            } else {
+                1.
+            };
+            (from, energy * share * self.at(from).unwrap_or_default())
+        }))
+    }
+}
+
+/// The interval from `start` to `end` split at hour boundaries.
+fn hours(
+    start: OffsetDateTime,
+    end: OffsetDateTime,
+) -> impl Iterator<Item = (OffsetDateTime, OffsetDateTime)> {
+    let next_hour = |t: OffsetDateTime| {
+        let into_hour = Duration::minutes(t.minute().into())
+            + Duration::seconds(t.second().into())
+            + Duration::nanoseconds(t.nanosecond().into());
+        t - into_hour + Duration::HOUR
+    };
+    core::iter::successors(Some(start), move |&t| {
+        Some(next_hour(t)).filter(|&t| t < end)
+    })
+    .map(move |t| (t, next_hour(t).min(end)))
+}
+
+/// Reset the `day` and `month` totals if `to` is in another day or month
+/// than `from`.
+fn roll(day: &mut f64, month: &mut f64, from: OffsetDateTime, to: OffsetDateTime) {
+    if to.date() != from.date() {
+        *day = 0.;
+    }
+    if (to.year(), to.month()) != (from.year(), from.month()) {
+        *month = 0.;
+    }
+}
+
+/// Cost accumulated per day and month of local time from successive states.
+#[derive(Debug, Clone)]
+pub struct Costs<'a> {
+    prices: Prices<'a>,
+    last: Option<State>,
+    day: f64,
+    month: f64,
+}
+
+impl<'a> Costs<'a> {
+    /// Start accumulating at `prices`.
+    pub fn new(prices: Prices<'a>) -> Self {
+        Self {
+            prices,
+            last: None,
+            day: 0.,
+            month: 0.,
+        }
+    }
+
+    /// Account for a state, returning the cost since the previous one.
+    ///
+    /// The cost is booked to the days and months it was incurred in, so
+    /// [`today`](Self::today) and [`this_month`](Self::this_month) start
+    /// from zero at midnight. States without a timestamp are ignored.
+    pub fn push(&mut self, state: &State) -> Option<f64> {
+        let time = state.datetime?;
+        let last = self.last.replace(*state)?;
+        let mut current = last.datetime?;
+
+        let Self {
+            prices, day, month, ..
+        } = self;
+        let Some(parts) = prices.parts(&last, state) else {
+            roll(day, month, current, time);
+            return None;
+        };
+        let mut cost = 0.;
+        for (start, part) in parts {
+            roll(day, month, current, start);
+            current = start;
+            *day += part;
+            *month += part;
+            cost += part;
+        }
+        roll(day, month, current, time);
+        Some(cost)
+    }
+
+    /// The cost so far today.
+    pub fn today(&self) -> f64 {
+        self.day
+    }
+
+    /// The cost so far this month.
+    pub fn this_month(&self) -> f64 {
+        self.month
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use time::macros::datetime;
+    use time::OffsetDateTime;
+
+    use crate::state::State;
+    use crate::{Direction, Object, WattHours};
+
+    use super::{Costs, Prices};
+
+    fn state(dt: OffsetDateTime, wh: u64) -> State {
+        let mut state = State::default();
+        state.insert(Object::DateTime(dt));
+        state.insert(Object::Energy(Direction::FromGrid, WattHours(wh).into()));
+        state
+    }
+
+    #[test]
+    fn prices() {
+        let day_night = Prices::DayNight {
+            day: 2.,
+            night: 1.,
+            day_hours: 6..22,
+        };
+        assert_eq!(day_night.at(datetime!(2024-01-01 05:59 +1)), Some(1.));
+        assert_eq!(day_night.at(datetime!(2024-01-01 06:00 +1)), Some(2.));
+        assert_eq!(day_night.at(datetime!(2024-01-01 22:00 +1)), Some(1.));
+
+        let hourly = Prices::Hourly {
+            start: datetime!(2024-01-01 00:00 +1),
+            prices: &[0.5, 0.75],
+        };
+        assert_eq!(hourly.at(datetime!(2024-01-01 01:30 +1)), Some(0.75));
+        assert_eq!(hourly.at(datetime!(2023-12-31 23:30 +1)), None);
+        assert_eq!(hourly.at(datetime!(2024-01-01 02:00 +1)), None);
+    }
+
+    #[test]
+    fn rollover() {
+        let mut costs = Costs::new(Prices::Flat(1.));
+        assert_eq!(costs.push(&state(datetime!(2024-01-31 23:00 +1), 0)), None);
+        assert_eq!(costs.push(&State::default()), None);
+        assert_eq!(
+            costs.push(&state(datetime!(2024-01-31 23:30 +1), 1000)),
+            Some(1.)
+        );
+        assert_eq!((costs.today(), costs.this_month()), (1., 1.));
+
+        // the last half hour of January is booked to January
+        costs.push(&state(datetime!(2024-02-01 00:00 +1), 3000));
+        assert_eq!((costs.today(), costs.this_month()), (0., 0.));
+        costs.push(&state(datetime!(2024-02-01 01:00 +1), 4000));
+        assert_eq!((costs.today(), costs.this_month()), (1., 1.));
+        costs.push(&state(datetime!(2024-02-01 23:00 +1), 4000));
+        assert_eq!((costs.today(), costs.this_month()), (1., 1.));
+
+        // split at midnight
+        assert_eq!(
+            costs.push(&state(datetime!(2024-02-02 01:00 +1), 6000)),
+            Some(2.)
+        );
+        assert_eq!((costs.today(), costs.this_month()), (1., 3.));
+    }
+
+    #[test]
+    fn hourly() {
+        let prices = Prices::Hourly {
+            start: datetime!(2024-01-01 00:00 +1),
+            prices: &[1., 2., 4.],
+        };
+        let cost = |a, b| prices.cost(&state(a, 0), &state(b, 3000));
+
+        assert_eq!(
+            cost(
+                datetime!(2024-01-01 00:30 +1),
+                datetime!(2024-01-01 02:00 +1)
+            ),
+            Some(1. + 4.)
+        );
+        assert_eq!(
+            cost(
+                datetime!(2024-01-01 01:00 +1),
+                datetime!(2024-01-01 01:00 +1)
+            ),
+            Some(6.)
+        );
+        assert_eq!(
+            cost(
+                datetime!(2024-01-01 02:00 +1),
+                datetime!(2024-01-01 03:30 +1)
+            ),
+            None
+        );
+        assert_eq!(
+            cost(
+                datetime!(2024-01-01 02:00 +1),
+                datetime!(2024-01-01 01:00 +1)
+            ),
+            None
+        );
+    }
+}
@@ -0,0 +1,207 @@
+use aes_gcm::aead::consts::U12;
+use aes_gcm::aes::Aes128;
+use aes_gcm::{AeadInOut, AesGcm, KeyInit, Nonce, Tag};
+
+use crate::{Error, Result, Telegram};
+
+/// Tag of a DLMS general-glo-ciphering frame.
+const GENERAL_GLO_CIPHERING: u8 = 0xdb;
+/// Length of the GCM authentication tag.
+const TAG_LEN: usize = 12;
+/// Length of the security control byte and the frame counter.
+const SECURITY_HEADER_LEN: usize = 5;
+
+type Aes128Gcm = AesGcm<Aes128, U12, U12>;
+
+/// The fields of an encrypted frame.
+struct Frame {
+    system_title: [u8; 8],
+    /// Offset of the security control byte.
+    start: usize,
+    /// Length of the security header, ciphertext and tag.
+    len: usize,
+}
+
+impl Frame {
+    fn parse(frame: &[u8]) -> Result<Option<Self>> {
+        let [tag, title_len, rest @ ..] = frame else {
+            return Ok(None);
+        };
+
+        if *tag != GENERAL_GLO_CIPHERING || usize::from(*title_len) != 8 {
+            return Err(Error::InvalidFormat);
+        }
+
+        let Some((system_title, rest)) = rest.split_first_chunk::<8>() else {
+            return Ok(None);
+        };
+
+        // BER-encoded length
+        let (len, len_size) = match rest {
+            [n @ 0..=0x7f, ..] => (usize::from(*n), 1),
+            [0x81, n, ..] => (usize::from(*n), 2),
+            [0x82, hi, lo, ..] => (usize::from(u16::from_be_bytes([*hi, *lo])), 3),
+            [] | [0x81] | [0x82] | [0x82, _] => return Ok(None),
+            _ => return Err(Error::InvalidFormat),
+        };
+
+        if len < SECURITY_HEADER_LEN + TAG_LEN {
+            return Err(Error::InvalidFormat);
+        }
+
+        Ok(Some(Self {
+            system_title: *system_title,
+            start: 2 + 8 + len_size,
+            len,
+        }))
+    }
+}
+
+/// Decryption of P1 telegrams wrapped in DLMS general-glo-ciphering frames
+/// (AES-128-GCM), as sent by e.g. Luxembourgish "Smarty" and Austrian
+/// meters.
+///
+/// The plaintext is an ordinary telegram, which can be parsed with
+/// [`Telegram::parse`] or directly through [`Decryptor::decrypt_telegram`].
+pub struct Decryptor {
+    cipher: Aes128Gcm,
+    auth_key: [u8; 16],
+}
+
+impl Decryptor {
+    /// Construct a new decryptor from the global unicast encryption key
+    /// (GUEK) and the authentication key (AK) supplied by the grid operator.
+    pub fn new(key: &[u8; 16], auth_key: &[u8; 16]) -> Self {
+        Self {
+            cipher: Aes128Gcm::new(&(*key).into()),
+            auth_key: *auth_key,
+        }
+    }
+
+    /// Total length of the frame starting at the beginning of `buf`, or
+    /// `None` if more bytes are needed to tell.
+    ///
+    /// This can be used to find the end of a frame in a byte stream.
+    pub fn frame_len(buf: &[u8]) -> Result<Option<usize>> {
+        Ok(Frame::parse(buf)?.map(|frame| frame.start + frame.len))
+    }
+
+    /// Decrypt a complete frame in place, returning the plaintext.
+    ///
+    /// Returns [`Error::Decryption`] if the frame fails authentication, e.g.
+    /// because of a wrong key.
+    pub fn decrypt<'a>(&self, frame: &'a mut [u8]) -> Result<&'a mut [u8]> {
+        let Frame {
+            system_title,
+            start,
+            len,
+        } = Frame::parse(frame)?.ok_or(Error::InvalidFormat)?;
+        let frame = frame
+            .get_mut(start..start + len)
+            .ok_or(Error::InvalidFormat)?;
+
+        let (header, rest) = frame.split_at_mut(SECURITY_HEADER_LEN);
+        let (ciphertext, tag) = rest.split_at_mut(len - SECURITY_HEADER_LEN - TAG_LEN);
+
+        let mut nonce = [0; 12];
+        nonce[..8].copy_from_slice(&system_title);
+        nonce[8..].copy_from_slice(&header[1..]);
+
+        let mut aad = [0; 17];
+        aad[0] = header[0];
+        aad[1..].copy_from_slice(&self.auth_key);
+
+        let tag = Tag::<U12>::try_from(&*tag).map_err(|_| Error::InvalidFormat)?;
+        self.cipher
+            .decrypt_inout_detached(&Nonce::from(nonce), &aad, ciphertext.into(), &tag)
+            .map_err(|_| Error::Decryption)?;
+
+        Ok(ciphertext)
+    }
+
+    /// Decrypt a complete frame in place and parse the plaintext as a
+    /// [`Telegram`].
+    pub fn decrypt_telegram<'a>(&self, frame: &'a mut [u8]) -> Result<Telegram<'a>> {
+        let plaintext = self.decrypt(frame)?;
+        let s = core::str::from_utf8(plaintext).map_err(|_| Error::InvalidFormat)?;
+        Telegram::parse(s)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::vec::Vec;
+
+    use aes_gcm::{AeadInOut, KeyInit, Nonce};
+
+    use crate::Error;
+
+    use super::{Aes128Gcm, Decryptor};
+
+    const KEY: [u8; 16] = *b"0123456789abcdef";
+    const AUTH_KEY: [u8; 16] = [
+        0x00, 0x11, 0x22, 0x33, 0x44, 0x55, 0x66, 0x77, 0x88, 0x99, 0xaa, 0xbb, 0xcc, 0xdd, 0xee,
+        0xff,
+    ];
+
+    fn encrypt(plaintext: &[u8]) -> Vec<u8> {
+        let system_title = *b"SAG\x05\x00\x01\x02\x03";
+        let counter = 0x0000_2a01u32.to_be_bytes();
+
+        let mut nonce = [0; 12];
+        nonce[..8].copy_from_slice(&system_title);
+        nonce[8..].copy_from_slice(&counter);
+        let aad = [&[0x30][..], &AUTH_KEY].concat();
+
+        let mut ciphertext = plaintext.to_vec();
+        let tag = Aes128Gcm::new(&KEY.into())
+            .encrypt_inout_detached(&Nonce::from(nonce), &aad, ciphertext.as_mut_slice().into())
+            .unwrap();
+
+        let len = u16::try_from(5 + ciphertext.len() + tag.len()).unwrap();
+        [
+            &[0xdb, 0x08][..],
+            &system_title,
+            &[0x82],
+            &len.to_be_bytes(),
+            &[0x30],
+            &counter,
+            &ciphertext,
+            &tag,
+        ]
+        .concat()
+    }
+
+    #[test]
+    fn decrypt() {
+        let plaintext = include_bytes!("../test/ell.txt");
+        let mut frame = encrypt(plaintext);
+        let decryptor = Decryptor::new(&KEY, &AUTH_KEY);
+
+        assert_eq!(Decryptor::frame_len(&frame[..5]).unwrap(), None);
+        assert_eq!(Decryptor::frame_len(&frame).unwrap(), Some(frame.len()));
+
+        let mut copy = frame.clone();
+        let telegram = decryptor.decrypt_telegram(&mut copy).unwrap();
+        assert_eq!(telegram.checksum, 0x9ab5);
+        assert_eq!(decryptor.decrypt(&mut frame).unwrap(), plaintext);
+    }
+
+    #[test]
+    fn wrong_key() {
+        let mut frame = encrypt(include_bytes!("../test/ell.txt"));
+        let decryptor = Decryptor::new(b"fedcba9876543210", &AUTH_KEY);
+        assert!(matches!(
+            decryptor.decrypt(&mut frame),
+            Err(Error::Decryption)
+        ));
+
+        let mut truncated = encrypt(b"/ELL5\r\n\r\n!");
+        truncated.pop();
+        let decryptor = Decryptor::new(&KEY, &AUTH_KEY);
+        assert!(matches!(
+            decryptor.decrypt(&mut truncated),
+            Err(Error::InvalidFormat)
+        ));
+    }
+}
@@ -0,0 +1,152 @@
+use crate::{Error, ObisFull, Object, ParseOptions, Result};
+
+/// Decodes objects that the built-in parser does not support, such as
+/// vendor-specific ones, for [`Telegram::objects_with`](crate::Telegram::objects_with).
+///
+/// Decoders are tried before the built-in parser, so they can also work
+/// around firmware quirks of supported objects. They are combined with
+/// [`ObjectDecoder::or`], and closures taking the reference and the values
+/// of a line are decoders too.
+///
+/// ```
+/// use han::{Decoded, Obis, ObisFull, ObjectDecoder, Telegram};
+///
+/// /// The reactive power of a made-up vendor, as a bare integer (var).
+/// fn vendor_power(obis: ObisFull, values: &str) -> Option<Result<u32, han::Error>> {
+///     if obis.obis != Obis(1, 0, 128, 7, 0) {
+///         return None;
+///     }
+///     let value = values.trim_start_matches('(').trim_end_matches(')');
+///     Some(value.parse().map_err(|_| han::Error::InvalidFormat))
+/// }
+///
+/// let telegram = Telegram::parse_without_checksum("/XYZ5\r\n\r\n1-0:128.7.0(42)\r\n!")?;
+/// let mut objects = telegram.objects_with(vendor_power);
+/// assert!(matches!(objects.next(), Some(Ok(Decoded::Custom(42)))));
+/// # Ok::<(), han::Error>(())
+/// ```
+pub trait ObjectDecoder {
+    /// The decoded objects.
+    type Output;
+
+    /// Decode the values of a line, e.g. `(1)(2)`, with the given reference.
+    ///
+    /// Returns `None` if the reference is not handled by this decoder.
+    fn decode(&self, obis: ObisFull, values: &str) -> Option<Result<Self::Output>>;
+
+    /// Try this decoder first, and then `other`.
+    fn or<D>(self, other: D) -> Or<Self, D>
+    where
+        Self: Sized,
+        D: ObjectDecoder<Output = Self::Output>,
+    {
+        Or(self, other)
+    }
+}
+
+impl<F, T> ObjectDecoder for F
+where
+    F: Fn(ObisFull, &str) -> Option<Result<T>>,
+{
+    type Output = T;
+
+    fn decode(&self, obis: ObisFull, values: &str) -> Option<Result<T>> {
+        self(obis, values)
+    }
+}
+
+/// Two decoders tried in order, created by [`ObjectDecoder::or`].
+#[derive(Debug, Clone, Copy)]
+pub struct Or<A, B>(A, B);
+
+impl<A, B> ObjectDecoder for Or<A, B>
+where
+    A: ObjectDecoder,
+    B: ObjectDecoder<Output = A::Output>,
+{
+    type Output = A::Output;
+
+    fn decode(&self, obis: ObisFull, values: &str) -> Option<Result<Self::Output>> {
+        self.0
+            .decode(obis, values)
+            .or_else(|| self.1.decode(obis, values))
+    }
+}
+
+/// An object decoded by either the built-in parser or an [`ObjectDecoder`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt-03", derive(defmt::Format))]
+pub enum Decoded<T> {
+    /// An object supported by the built-in parser.
+    Object(Object),
+    /// An object decoded by the [`ObjectDecoder`].
+    Custom(T),
+}
+
+/// Decode a line with `decoder`, falling back to the built-in parser.
+pub(crate) fn decode<D: ObjectDecoder>(
+    decoder: &D,
+    line: &str,
+    options: &ParseOptions,
+) -> Result<Decoded<D::Output>> {
+    let (obis, _) = line.split_once('(').ok_or(Error::InvalidFormat)?;
+
+    if let Ok(full) = obis.parse() {
+        if let Some(custom) = decoder.decode(full, &line[obis.len()..]) {
+            return custom.map(Decoded::Custom);
+        }
+    }
+
+    Object::parse_with(line, options).map(Decoded::Object)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Direction, Error, Obis, ObisFull, Object, ParseOptions, Result, WattHours};
+
+    use super::{decode, Decoded, ObjectDecoder};
+
+    fn count(obis: ObisFull, values: &str) -> Option<Result<usize>> {
+        (obis.obis == Obis(0, 0, 96, 99, 0)).then_some(Ok(values.len()))
+    }
+
+    #[test]
+    fn fallback() {
+        assert_eq!(
+            decode(&count, "0-0:96.99.0(ABCDEF)", &ParseOptions::new()).unwrap(),
+            Decoded::Custom(8)
+        );
+        assert_eq!(
+            decode(&count, "1-0:1.8.0(00006136.930*kWh)", &ParseOptions::new()).unwrap(),
+            Decoded::Object(Object::Energy(
+                Direction::FromGrid,
+                WattHours(6136930).into()
+            ))
+        );
+        assert!(matches!(
+            decode(&count, "0-0:96.98.0(ABCDEF)", &ParseOptions::new()),
+            Err(Error::UnrecognizedReference)
+        ));
+    }
+
+    #[test]
+    fn or() {
+        let quirk = |obis: ObisFull, _: &str| (obis.obis == Obis(1, 0, 1, 8, 0)).then_some(Ok(0));
+        let decoder = quirk.or(count);
+
+        // the first decoder takes precedence, also over the built-in parser
+        assert_eq!(
+            decode(
+                &decoder,
+                "1-0:1.8.0(00006136.930*kWh)",
+                &ParseOptions::new()
+            )
+            .unwrap(),
+            Decoded::Custom(0)
+        );
+        assert_eq!(
+            decode(&decoder, "0-0:96.99.0(AB)", &ParseOptions::new()).unwrap(),
+            Decoded::Custom(4)
+        );
+    }
+}
@@ -0,0 +1,170 @@
+use crate::state::{Field, GenericState, Value};
+use crate::{Error, Obis, ObisFull, Result, Telegram};
+
+/// A set of [`Obis`] references that telegrams, or the states built from
+/// them, are expected to include, to detect misconfigured meters.
+///
+/// ```
+/// use han::{ObjectSet, Telegram};
+///
+/// let telegram = Telegram::parse(include_str!("../test/ell.txt"))?;
+/// ObjectSet::SWEDISH.check(&telegram)?;
+/// ObjectSet::SWEDISH.check_state(&telegram.to_state()?)?;
+/// # Ok::<(), han::Error>(())
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ObjectSet<'a> {
+    references: &'a [Obis],
+}
+
+impl ObjectSet<'static> {
+    /// The objects mandated by the Swedish specification: the clock, the
+    /// total energy and power in all four quadrants and the power, voltage
+    /// and current of each line.
+    pub const SWEDISH: Self = Self::new(&[
+        Obis(0, 0, 1, 0, 0),
+        Obis(1, 0, 1, 8, 0),
+        Obis(1, 0, 2, 8, 0),
+        Obis(1, 0, 3, 8, 0),
+        Obis(1, 0, 4, 8, 0),
+        Obis(1, 0, 1, 7, 0),
+        Obis(1, 0, 2, 7, 0),
+        Obis(1, 0, 3, 7, 0),
+        Obis(1, 0, 4, 7, 0),
+        Obis(1, 0, 21, 7, 0),
+        Obis(1, 0, 22, 7, 0),
+        Obis(1, 0, 41, 7, 0),
+        Obis(1, 0, 42, 7, 0),
+        Obis(1, 0, 61, 7, 0),
+        Obis(1, 0, 62, 7, 0),
+        Obis(1, 0, 23, 7, 0),
+        Obis(1, 0, 24, 7, 0),
+        Obis(1, 0, 43, 7, 0),
+        Obis(1, 0, 44, 7, 0),
+        Obis(1, 0, 63, 7, 0),
+        Obis(1, 0, 64, 7, 0),
+        Obis(1, 0, 32, 7, 0),
+        Obis(1, 0, 52, 7, 0),
+        Obis(1, 0, 72, 7, 0),
+        Obis(1, 0, 31, 7, 0),
+        Obis(1, 0, 51, 7, 0),
+        Obis(1, 0, 71, 7, 0),
+    ]);
+}
+
+impl<'a> ObjectSet<'a> {
+    /// A set of the given references.
+    pub const fn new(references: &'a [Obis]) -> Self {
+        Self { references }
+    }
+
+    /// The expected references.
+    pub fn references(&self) -> &'a [Obis] {
+        self.references
+    }
+
+    /// The expected references that are missing from a telegram, whether or
+    /// not they could be parsed. References with an F group other than 255,
+    /// such as historical values, are not counted.
+    ///
+    /// ```
+    /// use han::{Obis, ObjectSet, Telegram};
+    ///
+    /// let telegram = Telegram::parse(include_str!("../test/ell.txt"))?;
+    /// let set = ObjectSet::new(&[Obis(1, 0, 1, 8, 0), Obis(0, 0, 96, 1, 0)]);
+    /// assert!(set.missing(&telegram).eq([Obis(0, 0, 96, 1, 0)]));
+    /// # Ok::<(), han::Error>(())
+    /// ```
+    pub fn missing<'t>(&self, telegram: &Telegram<'t>) -> impl Iterator<Item = Obis> + 'a
+    where
+        't: 'a,
+    {
+        let lines = telegram.lines();
+        self.references.iter().copied().filter(move |&obis| {
+            !lines.clone().any(|line| {
+                line.split_once('(')
+                    .and_then(|(full, _)| full.parse::<ObisFull>().ok())
+                    .is_some_and(|full| full.obis == obis && full.is_current())
+            })
+        })
+    }
+
+    /// Fail with [`Error::MissingReference`] on the first expected reference
+    /// that is missing from a telegram.
+    pub fn check(&self, telegram: &Telegram) -> Result<()> {
+        match self.missing(telegram).next() {
+            Some(obis) => Err(Error::MissingReference(obis)),
+            None => Ok(()),
+        }
+    }
+
+    /// The expected references whose values are not set in a state, such as
+    /// one merged from several partial telegrams. References without a
+    /// [`Field`] of their own, apart from the clock, are always missing.
+    ///
+    /// ```
+    /// use han::{Decivolts, Line, Obis, Object, ObjectSet, state::State};
+    ///
+    /// let mut state = State::default();
+    /// state.insert(Object::Voltage(Line::L1, Decivolts(2301)));
+    /// let set = ObjectSet::new(&[Obis(1, 0, 32, 7, 0), Obis(1, 0, 52, 7, 0)]);
+    /// assert!(set.missing_in_state(&state).eq([Obis(1, 0, 52, 7, 0)]));
+    /// ```
+    pub fn missing_in_state<'s, T: Value>(
+        &self,
+        state: &'s GenericState<T>,
+    ) -> impl Iterator<Item = Obis> + 's
+    where
+        'a: 's,
+    {
+        self.references
+            .iter()
+            .copied()
+            .filter(move |&obis| match obis {
+                Obis(0, 0, 1, 0, 0) => state.datetime.is_none(),
+                obis => Field::of_obis(obis).and_then(|f| state.field(f)).is_none(),
+            })
+    }
+
+    /// Fail with [`Error::MissingReference`] on the first expected reference
+    /// whose value is not set in a state.
+    pub fn check_state<T: Value>(&self, state: &GenericState<T>) -> Result<()> {
+        match self.missing_in_state(state).next() {
+            Some(obis) => Err(Error::MissingReference(obis)),
+            None => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Error, Obis, Telegram};
+
+    use super::ObjectSet;
+
+    #[test]
+    fn swedish() {
+        let telegram = Telegram::parse(include_str!("../test/ell.txt")).unwrap();
+        assert_eq!(ObjectSet::SWEDISH.missing(&telegram).count(), 0);
+
+        let telegram = Telegram::parse(include_str!("../test/dsmr5.txt")).unwrap();
+        assert!(matches!(
+            ObjectSet::SWEDISH.check(&telegram),
+            Err(Error::MissingReference(Obis(1, 0, 1, 8, 0)))
+        ));
+    }
+
+    #[test]
+    fn state() {
+        let full = Telegram::parse(include_str!("../test/ell.txt")).unwrap();
+        let state = full.to_state().unwrap();
+        assert_eq!(ObjectSet::SWEDISH.missing_in_state(&state).count(), 0);
+
+        let telegram = Telegram::parse(include_str!("../test/dsmr5.txt")).unwrap();
+        let state = telegram.to_state().unwrap();
+        assert!(matches!(
+            ObjectSet::SWEDISH.check_state(&state),
+            Err(Error::MissingReference(Obis(1, 0, 1, 8, 0)))
+        ));
+    }
+}
@@ -0,0 +1,188 @@
+use core::cmp::Ordering;
+use core::fmt::Display;
+use core::str::FromStr;
+
+use crate::state::Value;
+use crate::Error;
+
+/// A fixed-point decimal of `value` × 10<sup>-`scale`</sup>, keeping the
+/// number of decimals written by the meter without needing an FPU.
+///
+/// Values with different scales compare by their numeric value, so
+/// `1.0 == 1.00`, but [`Display`] keeps the scale.
+///
+/// ```
+/// use han::Fixed;
+///
+/// let v = "00006136.930".parse::<Fixed>()?;
+/// assert_eq!(v, Fixed::new(6136930, 3));
+/// assert_eq!(v, Fixed::new(613693, 2));
+/// assert_eq!(v.to_string(), "6136.930");
+/// # Ok::<(), han::Error>(())
+/// ```
+#[derive(Debug, Default, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "defmt-03", derive(defmt::Format))]
+pub struct Fixed {
+    /// The unscaled value.
+    pub value: i64,
+    /// The number of decimals.
+    pub scale: u8,
+}
+
+impl Fixed {
+    /// Construct `value` × 10<sup>-`scale`</sup>.
+    pub const fn new(value: i64, scale: u8) -> Self {
+        Self { value, scale }
+    }
+
+    /// The value rescaled to `scale` decimals, truncating any excess
+    /// precision. Returns `None` on overflow.
+    ///
+    /// ```
+    /// use han::Fixed;
+    ///
+    /// assert_eq!(Fixed::new(1205, 2).rescale(1), Some(120));
+    /// assert_eq!(Fixed::new(12, 1).rescale(3), Some(1200));
+    /// ```
+    pub fn rescale(self, scale: u8) -> Option<i64> {
+        let diff = u32::from(self.scale.abs_diff(scale));
+        let factor = 10i64.checked_pow(diff)?;
+        if scale >= self.scale {
+            self.value.checked_mul(factor)
+        } else {
+            Some(self.value / factor)
+        }
+    }
+
+    /// Both values with the same scale, for comparisons.
+    fn widen(self, other: Self) -> (i128, i128) {
+        let scale = self.scale.max(other.scale);
+        let widen = |v: Self| {
+            i128::from(v.value).saturating_mul(10i128.saturating_pow((scale - v.scale).into()))
+        };
+        (widen(self), widen(other))
+    }
+}
+
+impl PartialEq for Fixed {
+    fn eq(&self, other: &Self) -> bool {
+        let (a, b) = self.widen(*other);
+        a == b
+    }
+}
+
+impl Eq for Fixed {}
+
+impl PartialOrd for Fixed {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Fixed {
+    fn cmp(&self, other: &Self) -> Ordering {
+        let (a, b) = self.widen(*other);
+        a.cmp(&b)
+    }
+}
+
+impl Display for Fixed {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        if self.value < 0 {
+            f.write_str("-")?;
+        }
+        let v = self.value.unsigned_abs();
+        match 10u64.checked_pow(self.scale.into()) {
+            Some(1) => write!(f, "{}", v),
+            Some(factor) => write!(
+                f,
+                "{}.{:0scale$}",
+                v / factor,
+                v % factor,
+                scale = usize::from(self.scale)
+            ),
+            // no u64 has this many decimals
+            None => write!(f, "0.{:0scale$}", v, scale = usize::from(self.scale)),
+        }
+    }
+}
+
+impl FromStr for Fixed {
+    type Err = Error;
+
+    /// Parse a decimal such as `-0012.340`, keeping the number of decimals.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (negative, s) = match s.strip_prefix('-') {
+            Some(s) => (true, s),
+            None => (false, s),
+        };
+        let (i, f) = match s.split_once('.') {
+            Some((_, "")) => return Err(Error::InvalidFormat),
+            Some(parts) => parts,
+            None => (s, ""),
+        };
+        let is_digits = |s: &str| s.bytes().all(|b| b.is_ascii_digit());
+        if i.is_empty() || !is_digits(i) || !is_digits(f) {
+            return Err(Error::InvalidFormat);
+        }
+
+        let scale = u8::try_from(f.len()).map_err(|_| Error::InvalidFormat)?;
+        let value = i
+            .bytes()
+            .chain(f.bytes())
+            .try_fold(0i64, |v, b| {
+                v.checked_mul(10)?.checked_add(i64::from(b - b'0'))
+            })
+            .ok_or(Error::InvalidFormat)?;
+
+        Ok(Self::new(if negative { -value } else { value }, scale))
+    }
+}
+
+impl Value for Fixed {
+    fn from_scaled(v: i64, scale: u32) -> Self {
+        Self::new(v, scale.try_into().unwrap_or(u8::MAX))
+    }
+
+    fn parse_exact(s: &str) -> Option<Self> {
+        s.parse().ok()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::string::ToString;
+
+    use crate::Error;
+
+    use super::Fixed;
+
+    #[test]
+    fn parse() {
+        assert_eq!("001.21".parse::<Fixed>().unwrap(), Fixed::new(121, 2));
+        assert_eq!("-0.5".parse::<Fixed>().unwrap(), Fixed::new(-5, 1));
+        assert_eq!("00042".parse::<Fixed>().unwrap(), Fixed::new(42, 0));
+
+        for s in ["", ".5", "1.", "1.2.3", "1*kW", "-", "99999999999999999999"] {
+            assert!(
+                matches!(s.parse::<Fixed>(), Err(Error::InvalidFormat)),
+                "{s}"
+            );
+        }
+    }
+
+    #[test]
+    fn round_trip() {
+        for s in ["6136.930", "1.21", "-0.250", "50.00", "42", "0.001"] {
+            assert_eq!(s.parse::<Fixed>().unwrap().to_string(), s);
+        }
+    }
+
+    #[test]
+    fn compare() {
+        assert_eq!(Fixed::new(10, 1), Fixed::new(100, 2));
+        assert!(Fixed::new(121, 2) > Fixed::new(12, 1));
+        assert!(Fixed::new(-1, 0) < Fixed::new(1, 3));
+    }
+}
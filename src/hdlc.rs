@@ -0,0 +1,544 @@
+//! DLMS/COSEM data notifications framed by HDLC, as pushed by Norwegian HAN
+//! meters instead of the ASCII telegrams handled by [`Reader`](crate::Reader).
+//!
+//! [`Notification::objects`] decodes OBIS-tagged lists generically, while the
+//! lists of specific vendors are decoded by [`aidon`], [`kaifa`] and
+//! [`kamstrup`].
+//!
+//! ```
+//! use han::hdlc::{Frame, Notification};
+//!
+//! fn handle(frame: &[u8]) -> Result<(), han::Error> {
+//!     let frame = Frame::parse(frame)?;
+//!     let notification = Notification::parse(frame.information)?;
+//!
+//!     for obj in notification.objects() {
+//!         println!("{:?}", obj?);
+//!     }
+//!
+//!     Ok(())
+//! }
+//! ```
+
+#[cfg(feature = "time")]
+use time::{Date, Month, PrimitiveDateTime, Time, UtcOffset};
+
+#[cfg(feature = "time")]
+use crate::obis::{CEST, CET};
+use crate::{Error, Obis, Object, Result, Timestamp};
+
+pub mod aidon;
+pub mod kaifa;
+pub mod kamstrup;
+
+/// The flag delimiting HDLC frames.
+pub const FLAG: u8 = 0x7e;
+
+/// The LLC header preceding the APDU in frames sent by the meter.
+const LLC_HEADER: [u8; 3] = [0xe6, 0xe7, 0x00];
+
+/// Tag of a data-notification APDU.
+const DATA_NOTIFICATION: u8 = 0x0f;
+
+/// HDLC frame check sequence, stored little-endian.
+fn fcs(bytes: &[u8]) -> u16 {
+    crc16::State::<crc16::X_25>::calculate(bytes)
+}
+
+/// Check the little-endian checksum following `bytes`.
+fn check_fcs(bytes: &[u8], received: &[u8]) -> Result<()> {
+    match received {
+        [lo, hi] if u16::from_le_bytes([*lo, *hi]) == fcs(bytes) => Ok(()),
+        _ => Err(Error::Checksum),
+    }
+}
+
+/// Skip an HDLC address, which ends with a byte whose least significant bit
+/// is set.
+fn skip_address(bytes: &[u8]) -> Result<&[u8]> {
+    let end = bytes
+        .iter()
+        .position(|b| b & 1 == 1)
+        .ok_or(Error::InvalidFormat)?;
+    Ok(&bytes[end + 1..])
+}
+
+/// An HDLC frame (type 3).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Frame<'a> {
+    /// Whether the information continues in the next frame.
+    pub segmented: bool,
+    /// The information field, normally the LLC header followed by an APDU.
+    pub information: &'a [u8],
+}
+
+impl<'a> Frame<'a> {
+    /// Total length, including both flags, of the frame starting at the
+    /// beginning of `buf`, or `None` if more bytes are needed to tell.
+    ///
+    /// This can be used to find the end of a frame in a byte stream.
+    pub fn frame_len(buf: &[u8]) -> Result<Option<usize>> {
+        match buf {
+            [FLAG, format, len, ..] => {
+                if format & 0xf0 != 0xa0 {
+                    return Err(Error::InvalidFormat);
+                }
+                let len = usize::from(format & 0x07) << 8 | usize::from(*len);
+                Ok(Some(len + 2))
+            }
+            [FLAG] | [FLAG, _] | [] => Ok(None),
+            _ => Err(Error::InvalidFormat),
+        }
+    }
+
+    /// Parse a complete frame, from the opening flag through the closing
+    /// flag, verifying both the header and frame checksums.
+    pub fn parse(frame: &'a [u8]) -> Result<Self> {
+        let len = Self::frame_len(frame)?.ok_or(Error::InvalidFormat)?;
+        if frame.len() != len || frame[len - 1] != FLAG || len < 9 {
+            return Err(Error::InvalidFormat);
+        }
+
+        let inner = &frame[1..len - 1];
+        let (checked, fcs) = inner.split_at(inner.len() - 2);
+        check_fcs(checked, fcs)?;
+
+        let segmented = inner[0] & 0x08 != 0;
+        let rest = skip_address(&checked[2..])?; // destination
+        let rest = skip_address(rest)?; // source
+        let rest = rest.get(1..).ok_or(Error::InvalidFormat)?; // control
+
+        if rest.is_empty() {
+            return Ok(Frame {
+                segmented,
+                information: rest,
+            });
+        }
+
+        // header check sequence
+        let header_len = checked.len() - rest.len();
+        let (hcs, information) = rest.split_at(2.min(rest.len()));
+        check_fcs(&checked[..header_len], hcs)?;
+
+        Ok(Frame {
+            segmented,
+            information,
+        })
+    }
+}
+
+/// A single element of COSEM data, as yielded by [`Data`].
+///
+/// Arrays and structures are flattened: their elements follow them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Element<'a> {
+    /// No data.
+    Null,
+    /// An array of the given number of elements.
+    Array(usize),
+    /// A structure of the given number of elements.
+    Structure(usize),
+    /// A boolean.
+    Bool(bool),
+    /// A signed integer of any width.
+    Integer(i64),
+    /// An unsigned integer of any width.
+    Unsigned(u64),
+    /// An enumerated value, e.g. a unit.
+    Enum(u8),
+    /// A string of bytes, e.g. an OBIS code or a timestamp.
+    OctetString(&'a [u8]),
+    /// An ASCII string.
+    VisibleString(&'a [u8]),
+}
+
+/// An iterator over A-XDR encoded COSEM data.
+#[derive(Debug, Clone)]
+pub struct Data<'a> {
+    bytes: &'a [u8],
+}
+
+impl<'a> Data<'a> {
+    /// Iterate over the elements of encoded data.
+    pub fn new(bytes: &'a [u8]) -> Self {
+        Self { bytes }
+    }
+
+    fn take_bytes(&mut self, n: usize) -> Result<&'a [u8]> {
+        if self.bytes.len() < n {
+            return Err(Error::InvalidFormat);
+        }
+        let (taken, rest) = self.bytes.split_at(n);
+        self.bytes = rest;
+        Ok(taken)
+    }
+
+    fn take_array<const N: usize>(&mut self) -> Result<[u8; N]> {
+        Ok(self.take_bytes(N)?.try_into().unwrap())
+    }
+
+    /// Variable-length length, as used for arrays and strings.
+    fn take_len(&mut self) -> Result<usize> {
+        match self.take_array::<1>()? {
+            [n @ 0..=0x7f] => Ok(usize::from(n)),
+            [0x81] => Ok(usize::from(self.take_array::<1>()?[0])),
+            [0x82] => Ok(usize::from(u16::from_be_bytes(self.take_array()?))),
+            _ => Err(Error::InvalidFormat),
+        }
+    }
+
+    fn element(&mut self) -> Result<Element<'a>> {
+        let [tag] = self.take_array()?;
+        let element = match tag {
+            0x00 => Element::Null,
+            0x01 => Element::Array(self.take_len()?),
+            0x02 => Element::Structure(self.take_len()?),
+            0x03 => Element::Bool(self.take_array::<1>()? != [0]),
+            0x05 => Element::Integer(i32::from_be_bytes(self.take_array()?).into()),
+            0x06 => Element::Unsigned(u32::from_be_bytes(self.take_array()?).into()),
+            0x09 => {
+                let len = self.take_len()?;
+                Element::OctetString(self.take_bytes(len)?)
+            }
+            0x0a => {
+                let len = self.take_len()?;
+                Element::VisibleString(self.take_bytes(len)?)
+            }
+            0x0f => Element::Integer(i8::from_be_bytes(self.take_array()?).into()),
+            0x10 => Element::Integer(i16::from_be_bytes(self.take_array()?).into()),
+            0x11 => Element::Unsigned(u8::from_be_bytes(self.take_array()?).into()),
+            0x12 => Element::Unsigned(u16::from_be_bytes(self.take_array()?).into()),
+            0x14 => Element::Integer(i64::from_be_bytes(self.take_array()?)),
+            0x15 => Element::Unsigned(u64::from_be_bytes(self.take_array()?)),
+            0x16 => Element::Enum(self.take_array::<1>()?[0]),
+            _ => return Err(Error::InvalidFormat),
+        };
+
+        Ok(element)
+    }
+}
+
+impl<'a> Iterator for Data<'a> {
+    type Item = Result<Element<'a>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.bytes.is_empty() {
+            return None;
+        }
+
+        let element = self.element();
+        if element.is_err() {
+            self.bytes = &[];
+        }
+        Some(element)
+    }
+}
+
+/// Parse a 12-byte COSEM date-time.
+///
+/// If the deviation from UTC is unspecified, as is common, the time is
+/// assumed to be local Central European time, with the daylight saving bit
+/// of the clock status selecting CEST.
+#[cfg(feature = "time")]
+pub fn parse_datetime(bytes: &[u8]) -> Result<Timestamp> {
+    let &[y0, y1, month, day, _weekday, hour, minute, second, hundredths, d0, d1, status] = bytes
+    else {
+        return Err(Error::InvalidFormat);
+    };
+
+    let month = Month::try_from(month).map_err(|_| Error::InvalidFormat)?;
+    let date = Date::from_calendar_date(i32::from(u16::from_be_bytes([y0, y1])), month, day)
+        .map_err(|_| Error::InvalidFormat)?;
+    let hundredths = if hundredths == 0xff { 0 } else { hundredths };
+    let time = Time::from_hms_milli(hour, minute, second, u16::from(hundredths) * 10)
+        .map_err(|_| Error::InvalidFormat)?;
+
+    let offset = match i16::from_be_bytes([d0, d1]) {
+        i16::MIN => {
+            if status & 0x80 != 0 {
+                CEST
+            } else {
+                CET
+            }
+        }
+        // minutes from local time to UTC
+        deviation => UtcOffset::from_whole_seconds(-i32::from(deviation) * 60)
+            .map_err(|_| Error::InvalidFormat)?,
+    };
+
+    Ok(PrimitiveDateTime::new(date, time).assume_offset(offset))
+}
+
+/// Parse a 12-byte COSEM date-time as local time, with the daylight saving
+/// bit of the clock status as the DST indicator. Any deviation from UTC and
+/// the hundredths of a second are discarded.
+#[cfg(not(feature = "time"))]
+pub fn parse_datetime(bytes: &[u8]) -> Result<Timestamp> {
+    let &[y0, y1, month, day, _weekday, hour, min, sec, _hundredths, _d0, _d1, status] = bytes
+    else {
+        return Err(Error::InvalidFormat);
+    };
+
+    crate::RawTimestamp {
+        year: u16::from_be_bytes([y0, y1]),
+        month,
+        day,
+        hour,
+        min,
+        sec,
+        dst: Some(status & 0x80 != 0),
+    }
+    .validate()
+}
+
+/// The kind of list pushed by Norwegian meters, which determines the values
+/// included.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Kind {
+    /// Active power from the grid only.
+    List1,
+    /// Meter identity, power, current and voltage.
+    List2,
+    /// The values of [`Kind::List2`], the clock and the energy registers.
+    List3,
+}
+
+/// A DLMS data-notification, carrying a list of values pushed by the meter.
+#[derive(Debug, Clone)]
+pub struct Notification<'a> {
+    /// Identifier of the notification.
+    pub invoke_id: u32,
+    /// Time at which the notification was sent, if included.
+    pub datetime: Option<Timestamp>,
+    body: &'a [u8],
+}
+
+impl<'a> Notification<'a> {
+    /// Parse a data-notification APDU, optionally preceded by the LLC header
+    /// as in [`Frame::information`].
+    pub fn parse(apdu: &'a [u8]) -> Result<Self> {
+        let apdu = apdu.strip_prefix(&LLC_HEADER).unwrap_or(apdu);
+        let mut data = match apdu {
+            [DATA_NOTIFICATION, rest @ ..] => Data::new(rest),
+            _ => return Err(Error::InvalidFormat),
+        };
+
+        let invoke_id = u32::from_be_bytes(data.take_array()?);
+        let datetime = match data.take_array()? {
+            [0x00] => None,
+            [0x0c] => Some(parse_datetime(data.take_bytes(12)?)?),
+            // tagged as an octet string by e.g. Kaifa
+            [0x09] => match data.take_array()? {
+                [0x0c] => Some(parse_datetime(data.take_bytes(12)?)?),
+                _ => return Err(Error::InvalidFormat),
+            },
+            _ => return Err(Error::InvalidFormat),
+        };
+
+        Ok(Self {
+            invoke_id,
+            datetime,
+            body: data.bytes,
+        })
+    }
+
+    /// The elements of the notification body.
+    pub fn data(&self) -> Data<'a> {
+        Data::new(self.body)
+    }
+
+    /// The objects of the notification body.
+    ///
+    /// Every 6-byte octet string is taken as an OBIS code, followed by its
+    /// value and optionally a structure of scaler and unit. This covers the
+    /// OBIS-tagged lists of most meters. Values of references not covered by
+    /// [`Object`] are reported as [`Error::UnrecognizedReference`].
+    pub fn objects(&self) -> impl Iterator<Item = Result<Object>> + 'a {
+        Objects { data: self.data() }
+    }
+}
+
+struct Objects<'a> {
+    data: Data<'a>,
+}
+
+impl Objects<'_> {
+    fn object(&mut self, obis: Obis) -> Result<Object> {
+        let value = self.data.next().ok_or(Error::InvalidFormat)??;
+
+        // optional scaler and unit
+        let mut ahead = self.data.clone();
+        let scaler = match (ahead.next(), ahead.next(), ahead.next()) {
+            (
+                Some(Ok(Element::Structure(2))),
+                Some(Ok(Element::Integer(scaler))),
+                Some(Ok(Element::Enum(_))),
+            ) => {
+                self.data = ahead;
+                i8::try_from(scaler).map_err(|_| Error::InvalidFormat)?
+            }
+            _ => 0,
+        };
+
+        match (obis, value) {
+            (Obis(0, 0, 1, 0, 0), Element::OctetString(dt)) => {
+                Ok(Object::DateTime(parse_datetime(dt)?))
+            }
+            (_, Element::Integer(v)) => Object::from_scaled(obis, v, scaler),
+            (_, Element::Unsigned(v)) => {
+                let v = i64::try_from(v).map_err(|_| Error::InvalidFormat)?;
+                Object::from_scaled(obis, v, scaler)
+            }
+            _ => Err(Error::UnrecognizedReference),
+        }
+    }
+}
+
+impl Iterator for Objects<'_> {
+    type Item = Result<Object>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            match self.data.next()? {
+                Ok(Element::OctetString(&[a, b, c, d, e, _])) => {
+                    return Some(self.object(Obis(a, b, c, d, e)));
+                }
+                Ok(_) => continue,
+                Err(e) => return Some(Err(e)),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use std::vec::Vec;
+
+    /// Wrap `information` in an HDLC frame with correct checksums.
+    pub(crate) fn frame(information: &[u8]) -> Vec<u8> {
+        let len = 2 + 1 + 2 + 1 + 2 + information.len() + 2;
+        let mut inner = std::vec![0xa0 | (len >> 8) as u8, len as u8, 0x41, 0x08, 0x83, 0x13];
+        let hcs = super::fcs(&inner);
+        inner.extend_from_slice(&hcs.to_le_bytes());
+        inner.extend_from_slice(information);
+        let fcs = super::fcs(&inner);
+        inner.extend_from_slice(&fcs.to_le_bytes());
+
+        [&[super::FLAG][..], &inner, &[super::FLAG]].concat()
+    }
+
+    /// An Aidon-style list with the active power from the grid and the
+    /// voltage and current of L1.
+    pub(crate) fn aidon_frame() -> Vec<u8> {
+        frame(&[
+            0xe6, 0xe7, 0x00, 0x0f, 0x40, 0x00, 0x00, 0x00, 0x00, // notification header
+            0x01, 0x03, // array of 3
+            0x02, 0x03, 0x09, 0x06, 0x01, 0x00, 0x01, 0x07, 0x00, 0xff, // 1-0:1.7.0
+            0x06, 0x00, 0x00, 0x04, 0x62, // 1122
+            0x02, 0x02, 0x0f, 0x00, 0x16, 0x1b, // W
+            0x02, 0x03, 0x09, 0x06, 0x01, 0x00, 0x20, 0x07, 0x00, 0xff, // 1-0:32.7.0
+            0x12, 0x08, 0xfd, // 2301
+            0x02, 0x02, 0x0f, 0xff, 0x16, 0x23, // 0.1 V
+            0x02, 0x03, 0x09, 0x06, 0x01, 0x00, 0x1f, 0x07, 0x00, 0xff, // 1-0:31.7.0
+            0x10, 0x00, 0x2d, // 45
+            0x02, 0x02, 0x0f, 0xff, 0x16, 0x21, // 0.1 A
+        ])
+    }
+
+    /// Parse the notification in `frame` into a manufacturer-specific list
+    /// with `parse`, returning the list along with its objects, all of which
+    /// must be recognized.
+    ///
+    /// The `.bin` lists in `test/` are not captured from meters. They are
+    /// built from the list layouts published by each manufacturer and share
+    /// the same readings, so they only show that the lists decode alike and
+    /// cannot catch a scaler or ordering mistake made in reading the layouts.
+    /// Real frames, such as the examples in the Aidon HAN interface
+    /// description, are still to be added with their source noted.
+    pub(crate) fn objects<'a, L, I>(
+        frame: &'a [u8],
+        parse: fn(&super::Notification<'a>) -> crate::Result<L>,
+        objects: fn(&L) -> I,
+    ) -> (L, Vec<crate::Object>)
+    where
+        I: Iterator<Item = crate::Result<crate::Object>>,
+    {
+        let frame = super::Frame::parse(frame).unwrap();
+        let notification = super::Notification::parse(frame.information).unwrap();
+        let list = parse(&notification).unwrap();
+        let objects = objects(&list).collect::<crate::Result<Vec<_>>>().unwrap();
+        (list, objects)
+    }
+
+    #[test]
+    fn fcs() {
+        assert_eq!(super::fcs(b"123456789"), 0x906e);
+    }
+
+    #[test]
+    fn frame_len() {
+        let frame = aidon_frame();
+        assert_eq!(super::Frame::frame_len(&frame[..2]).unwrap(), None);
+        assert_eq!(super::Frame::frame_len(&frame).unwrap(), Some(frame.len()));
+        assert!(super::Frame::frame_len(&[0x7e, 0x00, 0x00]).is_err());
+    }
+
+    #[test]
+    fn aidon() {
+        use crate::{Deciamps, Decivolts, Direction, Line, Object, Watts};
+
+        let frame = aidon_frame();
+        let frame = super::Frame::parse(&frame).unwrap();
+        assert!(!frame.segmented);
+
+        let notification = super::Notification::parse(frame.information).unwrap();
+        assert_eq!(notification.invoke_id, 0x4000_0000);
+        assert_eq!(notification.datetime, None);
+
+        let objects = notification
+            .objects()
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        assert_eq!(
+            objects,
+            [
+                Object::TotalPower(Direction::FromGrid, Watts(1122).into()),
+                Object::Voltage(Line::L1, Decivolts(2301)),
+                Object::Current(Line::L1, Deciamps(45)),
+            ]
+        );
+    }
+
+    #[test]
+    fn corrupted() {
+        let mut frame = aidon_frame();
+        frame[20] ^= 1;
+        assert!(matches!(
+            super::Frame::parse(&frame),
+            Err(crate::Error::Checksum)
+        ));
+
+        let frame = aidon_frame();
+        assert!(super::Frame::parse(&frame[..frame.len() - 1]).is_err());
+    }
+
+    #[cfg(feature = "time")]
+    #[test]
+    fn datetime() {
+        use time::macros::datetime;
+
+        // deviation unspecified, DST
+        let dt = [0x07, 0xe8, 3, 31, 7, 3, 30, 0, 0xff, 0x80, 0x00, 0x80];
+        assert_eq!(
+            super::parse_datetime(&dt).unwrap(),
+            datetime!(2024-03-31 03:30:00 +2)
+        );
+
+        // deviation of -60 minutes, i.e. CET
+        let dt = [0x07, 0xe8, 1, 15, 1, 12, 0, 0, 0x00, 0xff, 0xc4, 0x00];
+        assert_eq!(
+            super::parse_datetime(&dt).unwrap(),
+            datetime!(2024-01-15 12:00:00 +1)
+        );
+    }
+}
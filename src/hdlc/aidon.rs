@@ -0,0 +1,331 @@
+//! The lists pushed by Aidon meters, common in Norway and Sweden:
+//!
+//! - List 1, with the active power from the grid, every 2.5 seconds;
+//! - List 2, additionally with the list version, the meter identity and the
+//!   power, current and voltage per line, every 10 seconds;
+//! - List 3, additionally with the clock and the energy registers, every hour.
+//!
+//! Unlike [`Notification::objects`], the decoding is strict: every value must
+//! carry the scaler and unit that the list version prescribes, and other
+//! units are reported as [`Error::UnitMismatch`](crate::Error::UnitMismatch).
+//!
+//! ```
+//! use han::hdlc::{aidon, Frame, Notification};
+//!
+//! fn handle(frame: &[u8]) -> Result<(), han::Error> {
+//!     let frame = Frame::parse(frame)?;
+//!     let notification = Notification::parse(frame.information)?;
+//!     let list = aidon::List::parse(&notification)?;
+//!
+//!     println!("{:?} from meter {:?}", list.kind, list.meter_id);
+//!     for obj in list.objects() {
+//!         println!("{:?}", obj?);
+//!     }
+//!
+//!     Ok(())
+//! }
+//! ```
+
+use super::{parse_datetime, Data, Element, Kind, Notification};
+use crate::{Error, Obis, Object, Power, Result, Unit};
+
+/// OBIS list version identifier, e.g. `AIDON_V0001`.
+const VERSION: Obis = Obis(1, 1, 0, 2, 129);
+/// Meter ID (GIAI GS1, 16 digits).
+const METER_ID: Obis = Obis(0, 0, 96, 1, 0);
+/// Meter type, e.g. `6525`.
+const METER_TYPE: Obis = Obis(0, 0, 96, 1, 7);
+/// Clock and date in the meter.
+const CLOCK: Obis = Obis(0, 0, 1, 0, 0);
+
+/// The prefix of the list version identifier of Aidon meters.
+const VERSION_PREFIX: &str = "AIDON_";
+
+/// A single element of a list: a structure of an OBIS code, a value and, for
+/// numeric values, the scaler and unit.
+#[derive(Debug, Clone, Copy)]
+struct Entry<'a> {
+    obis: Obis,
+    value: Element<'a>,
+    scaler_unit: Option<(i8, u8)>,
+}
+
+#[derive(Debug, Clone)]
+struct Entries<'a> {
+    data: Data<'a>,
+    remaining: usize,
+}
+
+impl<'a> Entries<'a> {
+    fn new(mut data: Data<'a>) -> Result<Self> {
+        match data.next() {
+            Some(Ok(Element::Array(remaining))) => Ok(Self { data, remaining }),
+            Some(Err(e)) => Err(e),
+            _ => Err(Error::InvalidFormat),
+        }
+    }
+
+    fn element(&mut self) -> Result<Element<'a>> {
+        self.data.next().ok_or(Error::InvalidFormat)?
+    }
+
+    fn entry(&mut self) -> Result<Entry<'a>> {
+        let len = match self.element()? {
+            Element::Structure(len @ 2..=3) => len,
+            _ => return Err(Error::InvalidFormat),
+        };
+        let obis = match self.element()? {
+            Element::OctetString(&[a, b, c, d, e, _]) => Obis(a, b, c, d, e),
+            _ => return Err(Error::InvalidFormat),
+        };
+        let value = self.element()?;
+
+        let scaler_unit = if len == 3 {
+            match (self.element()?, self.element()?, self.element()?) {
+                (Element::Structure(2), Element::Integer(scaler), Element::Enum(unit)) => {
+                    let scaler = i8::try_from(scaler).map_err(|_| Error::InvalidFormat)?;
+                    Some((scaler, unit))
+                }
+                _ => return Err(Error::InvalidFormat),
+            }
+        } else {
+            None
+        };
+
+        Ok(Entry {
+            obis,
+            value,
+            scaler_unit,
+        })
+    }
+}
+
+impl<'a> Iterator for Entries<'a> {
+    type Item = Result<Entry<'a>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.remaining == 0 {
+            return None;
+        }
+
+        self.remaining -= 1;
+        let entry = self.entry();
+        if entry.is_err() {
+            self.remaining = 0;
+        }
+        Some(entry)
+    }
+}
+
+/// The base unit in which the value of `obj` is sent.
+fn base_unit(obj: &Object) -> Option<Unit> {
+    match *obj {
+        Object::Energy(_, e) | Object::EnergyTariff(_, _, e) => Some(match e.kind() {
+            Power::Active => Unit::Wh,
+            Power::Reactive => Unit::VArh,
+            Power::Apparent => Unit::VAh,
+        }),
+        Object::AverageDemand(_) => Some(Unit::W),
+        Object::TotalPower(_, p) | Object::Power(_, _, p) => Some(match p.kind() {
+            Power::Active => Unit::W,
+            Power::Reactive => Unit::VAr,
+            Power::Apparent => Unit::VA,
+        }),
+        Object::Voltage(..) => Some(Unit::V),
+        Object::Current(..) | Object::NeutralCurrent(_) => Some(Unit::A),
+        Object::Frequency(_) => Some(Unit::Hz),
+        _ => None,
+    }
+}
+
+/// Decode an entry, skipping the identification strings.
+fn object(entry: Entry) -> Result<Option<Object>> {
+    let value = match (entry.obis, entry.value) {
+        (VERSION | METER_ID | METER_TYPE, Element::VisibleString(_)) => return Ok(None),
+        (CLOCK, Element::OctetString(dt)) => {
+            return Ok(Some(Object::DateTime(parse_datetime(dt)?)))
+        }
+        (_, Element::Integer(v)) => v,
+        (_, Element::Unsigned(v)) => i64::try_from(v).map_err(|_| Error::InvalidFormat)?,
+        _ => return Err(Error::InvalidFormat),
+    };
+
+    let (scaler, unit) = entry.scaler_unit.ok_or(Error::InvalidFormat)?;
+    let obj = Object::from_scaled(entry.obis, value, scaler)?;
+
+    match base_unit(&obj) {
+        Some(expected) if Unit::from_cosem(unit) != Some(expected) => Err(Error::UnitMismatch),
+        _ => Ok(Some(obj)),
+    }
+}
+
+fn as_str(s: &[u8]) -> Result<&str> {
+    core::str::from_utf8(s).map_err(|_| Error::InvalidFormat)
+}
+
+/// A list pushed by an Aidon meter.
+#[derive(Debug, Clone)]
+pub struct List<'a> {
+    /// The kind of list.
+    pub kind: Kind,
+    /// The OBIS list version identifier, e.g. `AIDON_V0001`, included in
+    /// lists 2 and 3.
+    pub version: Option<&'a str>,
+    /// The meter ID, included in lists 2 and 3.
+    pub meter_id: Option<&'a str>,
+    /// The meter type, included in lists 2 and 3.
+    pub meter_type: Option<&'a str>,
+    entries: Entries<'a>,
+}
+
+impl<'a> List<'a> {
+    /// Decode the body of a data notification as an Aidon list.
+    ///
+    /// Returns [`Error::InvalidFormat`] if the body is not structured like an
+    /// Aidon list, or if its version identifier belongs to another vendor.
+    pub fn parse(notification: &Notification<'a>) -> Result<Self> {
+        let entries = Entries::new(notification.data())?;
+        let mut version = None;
+        let mut meter_id = None;
+        let mut meter_type = None;
+        let mut clock = false;
+        let mut len = 0;
+
+        for entry in entries.clone() {
+            let entry = entry?;
+            len += 1;
+
+            match (entry.obis, entry.value) {
+                (VERSION, Element::VisibleString(s)) => version = Some(as_str(s)?),
+                (METER_ID, Element::VisibleString(s)) => meter_id = Some(as_str(s)?),
+                (METER_TYPE, Element::VisibleString(s)) => meter_type = Some(as_str(s)?),
+                (CLOCK, _) => clock = true,
+                _ => {}
+            }
+        }
+
+        let kind = match version {
+            Some(v) if !v.starts_with(VERSION_PREFIX) => return Err(Error::InvalidFormat),
+            Some(_) if clock => Kind::List3,
+            Some(_) => Kind::List2,
+            None if len == 1 => Kind::List1,
+            None => return Err(Error::InvalidFormat),
+        };
+
+        Ok(Self {
+            kind,
+            version,
+            meter_id,
+            meter_type,
+            entries,
+        })
+    }
+
+    /// The objects of the list, in the order sent by the meter.
+    pub fn objects(&self) -> impl Iterator<Item = Result<Object>> + 'a {
+        self.entries
+            .clone()
+            .filter_map(|entry| entry.and_then(object).transpose())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #[cfg(feature = "time")]
+    use time::macros::datetime;
+
+    use crate::hdlc::{Frame, Notification};
+    use crate::{Deciamps, Decivolts, Direction, Error, Line, Object, VoltAmperesReactive, Watts};
+    #[cfg(feature = "time")]
+    use crate::{VoltAmpereReactiveHours, WattHours};
+
+    use super::List;
+    use crate::hdlc::tests::objects;
+    use crate::hdlc::Kind;
+
+    #[test]
+    fn list1() {
+        let bytes = include_bytes!("../../test/aidon-list1.bin");
+        let (list, objects) = objects(bytes, List::parse, List::objects);
+        assert_eq!(list.kind, Kind::List1);
+        assert_eq!(
+            objects,
+            [Object::TotalPower(Direction::FromGrid, Watts(2631).into())]
+        );
+    }
+
+    #[test]
+    fn list2() {
+        let bytes = include_bytes!("../../test/aidon-list2.bin");
+        let frame = Frame::parse(bytes).unwrap();
+        let notification = Notification::parse(frame.information).unwrap();
+        let list = List::parse(&notification).unwrap();
+        assert_eq!(list.version, Some("AIDON_V0001"));
+        assert_eq!(list.meter_id, Some("7359992890941742"));
+        assert_eq!(list.meter_type, Some("6525"));
+
+        let (list, objects) = objects(bytes, List::parse, List::objects);
+        assert_eq!(list.kind, Kind::List2);
+        assert_eq!(objects.len(), 10);
+        assert_eq!(
+            objects[3],
+            Object::TotalPower(Direction::ToGrid, VoltAmperesReactive(412).into())
+        );
+        assert_eq!(objects[4], Object::Current(Line::L1, Deciamps(91)));
+        assert_eq!(objects[9], Object::Voltage(Line::L3, Decivolts(2324)));
+    }
+
+    #[cfg(feature = "time")]
+    #[test]
+    fn list3() {
+        let bytes = include_bytes!("../../test/aidon-list3.bin");
+        let (list, objects) = objects(bytes, List::parse, List::objects);
+        assert_eq!(list.kind, Kind::List3);
+        assert_eq!(objects.len(), 15);
+        assert_eq!(
+            objects[10],
+            Object::DateTime(datetime!(2024-10-27 15:00:00 +1))
+        );
+        // energy is sent in units of 10 Wh
+        assert_eq!(
+            objects[11],
+            Object::Energy(Direction::FromGrid, WattHours(105108920).into())
+        );
+        assert_eq!(
+            objects[14],
+            Object::Energy(Direction::ToGrid, VoltAmpereReactiveHours(2193540).into())
+        );
+    }
+
+    #[test]
+    fn unit_mismatch() {
+        let mut bytes = include_bytes!("../../test/aidon-list1.bin").to_vec();
+        // W -> Wh
+        let unit = bytes.len() - 4;
+        bytes[unit] = 30;
+        let bytes = crate::hdlc::tests::frame(&bytes[9..bytes.len() - 3]);
+
+        let frame = Frame::parse(&bytes).unwrap();
+        let notification = Notification::parse(frame.information).unwrap();
+        let list = List::parse(&notification).unwrap();
+        assert!(matches!(
+            list.objects().next(),
+            Some(Err(Error::UnitMismatch))
+        ));
+    }
+
+    #[test]
+    fn other_vendor() {
+        let mut bytes = include_bytes!("../../test/aidon-list2.bin").to_vec();
+        let version = bytes.windows(6).position(|w| w == b"AIDON_").unwrap();
+        bytes[version..version + 6].copy_from_slice(b"KFM_00");
+        let bytes = crate::hdlc::tests::frame(&bytes[9..bytes.len() - 3]);
+
+        let frame = Frame::parse(&bytes).unwrap();
+        let notification = Notification::parse(frame.information).unwrap();
+        assert!(matches!(
+            List::parse(&notification),
+            Err(Error::InvalidFormat)
+        ));
+    }
+}
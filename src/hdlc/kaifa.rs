@@ -0,0 +1,289 @@
+//! The lists pushed by Kaifa MA304 meters.
+//!
+//! Unlike most meters, Kaifa sends the values of lists 1, 2 and 3 without
+//! OBIS codes. Their meaning is given by their position, which in turn
+//! depends on the number of values and thus on whether the meter is single-
+//! or three-phase. The objects are the same as those decoded from other
+//! meters.
+//!
+//! ```
+//! use han::hdlc::{kaifa, Frame, Notification};
+//!
+//! fn handle(frame: &[u8]) -> Result<(), han::Error> {
+//!     let frame = Frame::parse(frame)?;
+//!     let notification = Notification::parse(frame.information)?;
+//!     let list = kaifa::List::parse(&notification)?;
+//!
+//!     println!("{:?} from meter {:?}", list.kind, list.meter_id);
+//!     for obj in list.objects() {
+//!         println!("{:?}", obj?);
+//!     }
+//!
+//!     Ok(())
+//! }
+//! ```
+
+use super::{parse_datetime, Data, Element, Kind, Notification};
+use crate::{Error, Obis, Object, Result};
+
+/// The prefix of the list version identifier of Kaifa meters, e.g.
+/// `Kfm_001`.
+const VERSION_PREFIX: &str = "Kfm_";
+
+/// The meaning of a value, given by its position.
+#[derive(Debug, Clone, Copy)]
+enum Field {
+    Version,
+    MeterId,
+    MeterType,
+    Clock,
+    /// A numeric value with its reference and scaler.
+    Value(Obis, i8),
+}
+
+const fn value(c: u8, d: u8, scaler: i8) -> Field {
+    Field::Value(Obis(1, 0, c, d, 0), scaler)
+}
+
+const ACTIVE_FROM_GRID: Field = value(1, 7, 0);
+const POWER: [Field; 4] = [
+    ACTIVE_FROM_GRID,
+    value(2, 7, 0),
+    value(3, 7, 0),
+    value(4, 7, 0),
+];
+const ENERGY: [Field; 4] = [
+    value(1, 8, 0),
+    value(2, 8, 0),
+    value(3, 8, 0),
+    value(4, 8, 0),
+];
+/// Currents are sent in mA.
+const CURRENT: [Field; 3] = [value(31, 7, -3), value(51, 7, -3), value(71, 7, -3)];
+/// Voltages are sent in dV.
+const VOLTAGE: [Field; 3] = [value(32, 7, -1), value(52, 7, -1), value(72, 7, -1)];
+
+const IDENTIFICATION: [Field; 3] = [Field::Version, Field::MeterId, Field::MeterType];
+
+const LIST_1: [Field; 1] = [ACTIVE_FROM_GRID];
+const LIST_2_SINGLE_PHASE: [Field; 9] =
+    concat([&IDENTIFICATION, &POWER, &[CURRENT[0], VOLTAGE[0]]]);
+const LIST_2_THREE_PHASE: [Field; 13] = concat([&IDENTIFICATION, &POWER, &CURRENT, &VOLTAGE]);
+const LIST_3_SINGLE_PHASE: [Field; 14] = concat([&LIST_2_SINGLE_PHASE, &[Field::Clock], &ENERGY]);
+const LIST_3_THREE_PHASE: [Field; 18] = concat([&LIST_2_THREE_PHASE, &[Field::Clock], &ENERGY]);
+
+/// Concatenate field lists at compile time.
+const fn concat<const N: usize, const M: usize>(parts: [&[Field]; M]) -> [Field; N] {
+    let mut fields = [Field::Version; N];
+    let mut i = 0;
+    let mut part = 0;
+
+    while part < M {
+        let mut j = 0;
+        while j < parts[part].len() {
+            fields[i] = parts[part][j];
+            i += 1;
+            j += 1;
+        }
+        part += 1;
+    }
+
+    assert!(i == N);
+    fields
+}
+
+/// The kind and fields of a list with `len` values.
+fn layout(len: usize) -> Option<(Kind, &'static [Field])> {
+    match len {
+        1 => Some((Kind::List1, &LIST_1)),
+        9 => Some((Kind::List2, &LIST_2_SINGLE_PHASE)),
+        13 => Some((Kind::List2, &LIST_2_THREE_PHASE)),
+        14 => Some((Kind::List3, &LIST_3_SINGLE_PHASE)),
+        18 => Some((Kind::List3, &LIST_3_THREE_PHASE)),
+        _ => None,
+    }
+}
+
+/// Decode a value, skipping the identification strings.
+fn object(field: Field, element: Element) -> Result<Option<Object>> {
+    match (field, element) {
+        (Field::Version | Field::MeterId | Field::MeterType, Element::VisibleString(_)) => Ok(None),
+        (Field::Clock, Element::OctetString(dt)) => Ok(Some(Object::DateTime(parse_datetime(dt)?))),
+        (Field::Value(obis, scaler), Element::Unsigned(v)) => {
+            let v = i64::try_from(v).map_err(|_| Error::InvalidFormat)?;
+            Object::from_scaled(obis, v, scaler).map(Some)
+        }
+        (Field::Value(obis, scaler), Element::Integer(v)) => {
+            Object::from_scaled(obis, v, scaler).map(Some)
+        }
+        _ => Err(Error::InvalidFormat),
+    }
+}
+
+fn as_str(s: &[u8]) -> Result<&str> {
+    core::str::from_utf8(s).map_err(|_| Error::InvalidFormat)
+}
+
+/// A list pushed by a Kaifa meter.
+#[derive(Debug, Clone)]
+pub struct List<'a> {
+    /// The kind of list.
+    pub kind: Kind,
+    /// The OBIS list version identifier, e.g. `Kfm_001`, included in lists 2
+    /// and 3.
+    pub version: Option<&'a str>,
+    /// The meter ID, included in lists 2 and 3.
+    pub meter_id: Option<&'a str>,
+    /// The meter type, e.g. `MA304H3E`, included in lists 2 and 3.
+    pub meter_type: Option<&'a str>,
+    fields: &'static [Field],
+    data: Data<'a>,
+}
+
+impl<'a> List<'a> {
+    /// Decode the body of a data notification as a Kaifa list.
+    ///
+    /// Returns [`Error::InvalidFormat`] if the number of values does not
+    /// match any list, or if the version identifier belongs to another
+    /// vendor.
+    pub fn parse(notification: &Notification<'a>) -> Result<Self> {
+        let mut data = notification.data();
+        let len = match data.next() {
+            Some(Ok(Element::Structure(len) | Element::Array(len))) => len,
+            Some(Err(e)) => return Err(e),
+            _ => return Err(Error::InvalidFormat),
+        };
+        let (kind, fields) = layout(len).ok_or(Error::InvalidFormat)?;
+
+        let mut list = Self {
+            kind,
+            version: None,
+            meter_id: None,
+            meter_type: None,
+            fields,
+            data: data.clone(),
+        };
+
+        for &field in fields {
+            let element = data.next().ok_or(Error::InvalidFormat)??;
+            match (field, element) {
+                (Field::Version, Element::VisibleString(s)) => {
+                    list.version = Some(as_str(s)?);
+                }
+                (Field::MeterId, Element::VisibleString(s)) => {
+                    list.meter_id = Some(as_str(s)?);
+                }
+                (Field::MeterType, Element::VisibleString(s)) => {
+                    list.meter_type = Some(as_str(s)?);
+                }
+                (Field::Clock, Element::OctetString(_))
+                | (Field::Value(..), Element::Integer(_) | Element::Unsigned(_)) => {}
+                _ => return Err(Error::InvalidFormat),
+            }
+        }
+
+        if list.version.is_some_and(|v| !v.starts_with(VERSION_PREFIX)) {
+            return Err(Error::InvalidFormat);
+        }
+
+        Ok(list)
+    }
+
+    /// The objects of the list, in the order sent by the meter.
+    pub fn objects(&self) -> impl Iterator<Item = Result<Object>> + 'a {
+        let mut data = self.data.clone();
+        self.fields.iter().filter_map(move |&field| {
+            let element = match data.next() {
+                Some(element) => element,
+                None => return Some(Err(Error::InvalidFormat)),
+            };
+            element.and_then(|e| object(field, e)).transpose()
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #[cfg(feature = "time")]
+    use time::macros::datetime;
+
+    use crate::hdlc::{Frame, Kind, Notification};
+    use crate::{state::State, Deciamps, Decivolts};
+    #[cfg(feature = "time")]
+    use crate::{Direction, Watts};
+    use crate::{Error, Line, Object};
+
+    use super::List;
+    use crate::hdlc::aidon;
+    use crate::hdlc::tests::objects;
+
+    #[cfg(feature = "time")]
+    #[test]
+    fn list1() {
+        let bytes = include_bytes!("../../test/kaifa-list1.bin");
+        let frame = Frame::parse(bytes).unwrap();
+        let notification = Notification::parse(frame.information).unwrap();
+        assert_eq!(
+            notification.datetime,
+            Some(datetime!(2024-10-27 14:59:10 +1))
+        );
+
+        let (list, objects) = objects(bytes, List::parse, List::objects);
+        assert_eq!(list.kind, Kind::List1);
+        assert_eq!(
+            objects,
+            [Object::TotalPower(Direction::FromGrid, Watts(2631).into())]
+        );
+    }
+
+    #[test]
+    fn list2() {
+        let bytes = include_bytes!("../../test/kaifa-list2.bin");
+        let frame = Frame::parse(bytes).unwrap();
+        let notification = Notification::parse(frame.information).unwrap();
+        let list = List::parse(&notification).unwrap();
+        assert_eq!(list.kind, Kind::List2);
+        assert_eq!(list.version, Some("Kfm_001"));
+        assert_eq!(list.meter_id, Some("6970631401234567"));
+        assert_eq!(list.meter_type, Some("MA304H3E"));
+
+        let (_, objects) = objects(bytes, List::parse, List::objects);
+        assert_eq!(objects.len(), 10);
+        // currents are sent in mA
+        assert_eq!(objects[4], Object::Current(Line::L1, Deciamps(91)));
+        assert_eq!(objects[9], Object::Voltage(Line::L3, Decivolts(2324)));
+    }
+
+    #[test]
+    fn same_as_aidon() {
+        let bytes = include_bytes!("../../test/kaifa-list3.bin");
+        let (list, kaifa) = objects(bytes, List::parse, List::objects);
+        assert_eq!(list.kind, Kind::List3);
+        assert_eq!(kaifa.len(), 15);
+
+        let bytes = include_bytes!("../../test/aidon-list3.bin");
+        let (_, aidon) = objects(bytes, aidon::List::parse, aidon::List::objects);
+        assert_eq!(kaifa, aidon);
+
+        let mut state = State::default();
+        for obj in kaifa {
+            state.insert(obj);
+        }
+        let mut expected = State::default();
+        for obj in aidon {
+            expected.insert(obj);
+        }
+        assert_eq!(state, expected);
+    }
+
+    #[test]
+    fn unknown_layout() {
+        let bytes = include_bytes!("../../test/aidon-list2.bin");
+        let frame = Frame::parse(bytes).unwrap();
+        let notification = Notification::parse(frame.information).unwrap();
+        assert!(matches!(
+            List::parse(&notification),
+            Err(Error::InvalidFormat)
+        ));
+    }
+}
@@ -0,0 +1,253 @@
+//! The lists pushed by Kamstrup OMNIPOWER meters.
+//!
+//! The values follow their OBIS codes directly, without the structures used
+//! by e.g. Aidon, and without scalers or units. The list sent every 10
+//! seconds includes the meter identity, power, current and voltage; the
+//! hourly list additionally includes the clock and the energy registers.
+//!
+//! ```
+//! use han::hdlc::{kamstrup, Frame, Notification};
+//!
+//! fn handle(frame: &[u8]) -> Result<(), han::Error> {
+//!     let frame = Frame::parse(frame)?;
+//!     let notification = Notification::parse(frame.information)?;
+//!     let list = kamstrup::List::parse(&notification)?;
+//!
+//!     println!("{:?} from meter {:?}", list.kind, list.meter_id);
+//!     for obj in list.objects() {
+//!         println!("{:?}", obj?);
+//!     }
+//!
+//!     Ok(())
+//! }
+//! ```
+
+use super::{parse_datetime, Data, Element, Kind, Notification};
+use crate::{Error, Obis, Object, Result};
+
+/// Meter ID (GIAI GS1, 16 digits).
+const METER_ID: Obis = Obis(1, 1, 0, 0, 5);
+/// Meter type.
+const METER_TYPE: Obis = Obis(1, 1, 96, 1, 1);
+/// Clock and date in the meter.
+const CLOCK: Obis = Obis(0, 1, 1, 0, 0);
+
+/// The prefix of the list version identifier of Kamstrup meters, e.g.
+/// `Kamstrup_V0001`.
+const VERSION_PREFIX: &str = "Kamstrup_";
+
+/// The implied scaler of a numeric value.
+fn scaler(obis: Obis) -> Option<i8> {
+    match obis {
+        // W and var
+        Obis(1, _, 1..=4, 7, 0) => Some(0),
+        // 10 Wh and 10 varh
+        Obis(1, _, 1..=4, 8, 0) => Some(1),
+        // 10 mA
+        Obis(1, _, 31 | 51 | 71, 7, 0) => Some(-2),
+        // V
+        Obis(1, _, 32 | 52 | 72, 7, 0) => Some(0),
+        _ => None,
+    }
+}
+
+#[derive(Debug, Clone)]
+struct Pairs<'a> {
+    data: Data<'a>,
+    remaining: usize,
+}
+
+impl<'a> Iterator for Pairs<'a> {
+    type Item = Result<(Obis, Element<'a>)>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.remaining == 0 {
+            return None;
+        }
+        self.remaining -= 1;
+
+        let pair = match (self.data.next(), self.data.next()) {
+            (Some(Ok(Element::OctetString(&[a, b, c, d, e, _]))), Some(Ok(value))) => {
+                Ok((Obis(a, b, c, d, e), value))
+            }
+            (Some(Err(e)), _) | (_, Some(Err(e))) => Err(e),
+            _ => Err(Error::InvalidFormat),
+        };
+        if pair.is_err() {
+            self.remaining = 0;
+        }
+        Some(pair)
+    }
+}
+
+/// Decode a pair, skipping the identification strings.
+fn object(obis: Obis, value: Element) -> Result<Option<Object>> {
+    let value = match (obis, value) {
+        (METER_ID | METER_TYPE, Element::VisibleString(_)) => return Ok(None),
+        (CLOCK, Element::OctetString(dt)) => {
+            return Ok(Some(Object::DateTime(parse_datetime(dt)?)))
+        }
+        (_, Element::Integer(v)) => v,
+        (_, Element::Unsigned(v)) => i64::try_from(v).map_err(|_| Error::InvalidFormat)?,
+        _ => return Err(Error::InvalidFormat),
+    };
+
+    let scaler = scaler(obis).ok_or(Error::UnrecognizedReference)?;
+    Object::from_scaled(obis, value, scaler).map(Some)
+}
+
+fn as_str(s: &[u8]) -> Result<&str> {
+    core::str::from_utf8(s).map_err(|_| Error::InvalidFormat)
+}
+
+/// A list pushed by a Kamstrup meter.
+#[derive(Debug, Clone)]
+pub struct List<'a> {
+    /// The kind of list: [`Kind::List2`] every 10 seconds and
+    /// [`Kind::List3`] every hour.
+    pub kind: Kind,
+    /// The OBIS list version identifier, e.g. `Kamstrup_V0001`.
+    pub version: &'a str,
+    /// The meter ID.
+    pub meter_id: Option<&'a str>,
+    /// The meter type.
+    pub meter_type: Option<&'a str>,
+    pairs: Pairs<'a>,
+}
+
+impl<'a> List<'a> {
+    /// Decode the body of a data notification as a Kamstrup list.
+    ///
+    /// Returns [`Error::InvalidFormat`] if the body is not structured like a
+    /// Kamstrup list, or if its version identifier belongs to another vendor.
+    pub fn parse(notification: &Notification<'a>) -> Result<Self> {
+        let mut data = notification.data();
+        let (len, version) = match (data.next(), data.next()) {
+            (Some(Ok(Element::Structure(len))), Some(Ok(Element::VisibleString(v)))) => {
+                (len, as_str(v)?)
+            }
+            _ => return Err(Error::InvalidFormat),
+        };
+
+        // the version is followed by pairs of OBIS codes and values
+        if !version.starts_with(VERSION_PREFIX) || len % 2 != 1 {
+            return Err(Error::InvalidFormat);
+        }
+
+        let pairs = Pairs {
+            data,
+            remaining: len / 2,
+        };
+        let mut list = Self {
+            kind: Kind::List2,
+            version,
+            meter_id: None,
+            meter_type: None,
+            pairs: pairs.clone(),
+        };
+
+        for pair in pairs {
+            match pair? {
+                (METER_ID, Element::VisibleString(s)) => list.meter_id = Some(as_str(s)?),
+                (METER_TYPE, Element::VisibleString(s)) => list.meter_type = Some(as_str(s)?),
+                (CLOCK, _) => list.kind = Kind::List3,
+                _ => {}
+            }
+        }
+
+        Ok(list)
+    }
+
+    /// The objects of the list, in the order sent by the meter.
+    ///
+    /// Values of references not covered by [`Object`] are reported as
+    /// [`Error::UnrecognizedReference`].
+    pub fn objects(&self) -> impl Iterator<Item = Result<Object>> + 'a {
+        self.pairs.clone().filter_map(|pair| {
+            pair.and_then(|(obis, value)| object(obis, value))
+                .transpose()
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #[cfg(feature = "time")]
+    use time::macros::datetime;
+
+    #[cfg(feature = "time")]
+    use crate::hdlc::Kind;
+    use crate::hdlc::{Frame, Notification};
+    use crate::Error;
+    #[cfg(feature = "time")]
+    use crate::{Deciamps, Decivolts, Direction, Line, Object, WattHours, Watts};
+
+    use super::List;
+    #[cfg(feature = "time")]
+    use crate::hdlc::tests::objects;
+
+    #[cfg(feature = "time")]
+    #[test]
+    fn ten_seconds() {
+        let bytes = include_bytes!("../../test/kamstrup-list2.bin");
+        let frame = Frame::parse(bytes).unwrap();
+        let notification = Notification::parse(frame.information).unwrap();
+        assert_eq!(
+            notification.datetime,
+            Some(datetime!(2024-10-27 14:59:10 +1))
+        );
+
+        let list = List::parse(&notification).unwrap();
+        assert_eq!(list.version, "Kamstrup_V0001");
+        assert_eq!(list.meter_id, Some("5706567274389702"));
+        assert_eq!(list.meter_type, Some("6841121BN243101040"));
+
+        let (list, objects) = objects(bytes, List::parse, List::objects);
+        assert_eq!(list.kind, Kind::List2);
+        assert_eq!(objects.len(), 10);
+        assert_eq!(
+            objects[0],
+            Object::TotalPower(Direction::FromGrid, Watts(2631).into())
+        );
+        // currents are sent in units of 10 mA, voltages in V
+        assert_eq!(objects[4], Object::Current(Line::L1, Deciamps(91)));
+        assert_eq!(objects[9], Object::Voltage(Line::L3, Decivolts(2320)));
+    }
+
+    #[cfg(feature = "time")]
+    #[test]
+    fn hourly() {
+        let bytes = include_bytes!("../../test/kamstrup-list3.bin");
+        let (list, objects) = objects(bytes, List::parse, List::objects);
+        assert_eq!(list.kind, Kind::List3);
+        assert_eq!(objects.len(), 15);
+        assert_eq!(
+            objects[10],
+            Object::DateTime(datetime!(2024-10-27 15:00:05 +1))
+        );
+        // energy is sent in units of 10 Wh
+        assert_eq!(
+            objects[11],
+            Object::Energy(Direction::FromGrid, WattHours(105108920).into())
+        );
+    }
+
+    #[test]
+    fn other_vendor() {
+        let bytes = include_bytes!("../../test/aidon-list2.bin");
+        let frame = Frame::parse(bytes).unwrap();
+        let notification = Notification::parse(frame.information).unwrap();
+        assert!(matches!(
+            List::parse(&notification),
+            Err(Error::InvalidFormat)
+        ));
+
+        let bytes = include_bytes!("../../test/kaifa-list2.bin");
+        let frame = Frame::parse(bytes).unwrap();
+        let notification = Notification::parse(frame.information).unwrap();
+        assert!(matches!(
+            List::parse(&notification),
+            Err(Error::InvalidFormat)
+        ));
+    }
+}
@@ -0,0 +1,260 @@
+//! [InfluxDB line protocol] output, for piping telemetry into InfluxDB or
+//! Telegraf.
+//!
+//! [InfluxDB line protocol]: https://docs.influxdata.com/influxdb/v2/reference/syntax/line-protocol/
+//!
+//! ```
+//! use han::{influx::LineProtocolWriter, Telegram};
+//!
+//! let telegram = Telegram::parse(include_str!("../test/ell.txt"))?;
+//! let mut w = LineProtocolWriter::new(String::new(), "han").meter("253833635_A");
+//! w.state(&telegram.to_state()?).unwrap();
+//! let s = w.into_inner();
+//!
+//! assert!(s.starts_with("han,meter=253833635_A energy_from_grid_active=6136.936,"));
+//! assert!(s.contains("\nhan,meter=253833635_A,line=L1 power_from_grid_active=0.523,"));
+//! # Ok::<(), han::Error>(())
+//! ```
+
+use core::fmt::{self, Display, Write};
+
+use crate::state::{Measurement, State};
+use crate::{Direction, Line, Object, Power, Timestamp};
+
+/// Writes [`State`]s or [`Object`]s as InfluxDB line protocol to a
+/// [`fmt::Write`], tagged with the meter and line.
+pub struct LineProtocolWriter<'a, W> {
+    w: W,
+    measurement: &'a str,
+    meter: Option<&'a str>,
+}
+
+impl<'a, W: Write> LineProtocolWriter<'a, W> {
+    /// Write points of `measurement`.
+    pub fn new(w: W, measurement: &'a str) -> Self {
+        Self {
+            w,
+            measurement,
+            meter: None,
+        }
+    }
+
+    /// Tag the points with a meter identification.
+    pub fn meter(mut self, meter: &'a str) -> Self {
+        self.meter = Some(meter);
+        self
+    }
+
+    /// Write a state as one point for the totals and one per line, with
+    /// values in kWh, kW, V, A and Hz. Unset values are left out, and so are
+    /// points without any values.
+    pub fn state(&mut self, state: &State) -> fmt::Result {
+        let mut p = self.point(None);
+        p.measurement("energy", &state.energy)?;
+        p.measurement("power", &state.power)?;
+        p.field("power_factor", state.power_factor)?;
+        p.field("neutral_current", state.neutral_current)?;
+        p.field("frequency", state.frequency)?;
+        p.field("demand_average", state.demand.average)?;
+        p.field("demand_max", state.demand.max)?;
+        p.finish(state.datetime)?;
+
+        for line in [Line::L1, Line::L2, Line::L3] {
+            let l = state.line(line);
+            let mut p = self.point(Some(line));
+            p.measurement("power", &l.power)?;
+            p.field("voltage", l.voltage)?;
+            p.field("current", l.current)?;
+            p.field("power_factor", l.power_factor)?;
+            p.finish(state.datetime)?;
+        }
+        Ok(())
+    }
+
+    /// Write objects as one point with a field per [`Obis`](crate::Obis)
+    /// reference, holding the integer value in the unit of the object (e.g.
+    /// decivolts) or the text. The point is timestamped by the
+    /// [`Object::DateTime`], if any.
+    ///
+    /// ```
+    /// use han::{influx::LineProtocolWriter, Decivolts, Line, Object};
+    ///
+    /// let mut w = LineProtocolWriter::new(String::new(), "han");
+    /// w.objects([Object::Voltage(Line::L1, Decivolts(2301))])?;
+    /// assert_eq!(w.into_inner(), "han 1-0:32.7.0=2301i\n");
+    /// # Ok::<(), core::fmt::Error>(())
+    /// ```
+    pub fn objects(&mut self, objects: impl IntoIterator<Item = Object>) -> fmt::Result {
+        let mut p = self.point(None);
+        let mut time = None;
+        for obj in objects {
+            match obj {
+                Object::DateTime(dt) => time = Some(dt),
+                Object::EquipmentId(text)
+                | Object::TextMessage(text)
+                | Object::MBusEquipmentId(_, text) => {
+                    if let Some(s) = text.as_str() {
+                        p.field(obj.obis(), Some(Quoted(s)))?;
+                    }
+                }
+                obj => p.field(obj.obis(), obj.scaled_value().map(Integer))?,
+            }
+        }
+        p.finish(time)
+    }
+
+    /// The underlying writer.
+    pub fn into_inner(self) -> W {
+        self.w
+    }
+
+    fn point(&mut self, line: Option<Line>) -> Point<'_, 'a, W> {
+        Point {
+            writer: self,
+            line,
+            empty: true,
+        }
+    }
+}
+
+/// A point whose measurement and tags are written along with the first
+/// field.
+struct Point<'p, 'a, W> {
+    writer: &'p mut LineProtocolWriter<'a, W>,
+    line: Option<Line>,
+    empty: bool,
+}
+
+impl<W: Write> Point<'_, '_, W> {
+    fn field(&mut self, key: impl Display, value: Option<impl Display>) -> fmt::Result {
+        let Some(value) = value else {
+            return Ok(());
+        };
+        let w = &mut self.writer.w;
+
+        if self.empty {
+            escape(w, self.writer.measurement, &[',', ' '])?;
+            if let Some(meter) = self.writer.meter {
+                w.write_str(",meter=")?;
+                escape(w, meter, &[',', '=', ' '])?;
+            }
+            if let Some(line) = self.line {
+                write!(w, ",line={:?}", line)?;
+            }
+            w.write_char(' ')?;
+            self.empty = false;
+        } else {
+            w.write_char(',')?;
+        }
+        write!(w, "{}={}", key, value)
+    }
+
+    /// Write the fields of a measurement as e.g. `power_from_grid_active`.
+    fn measurement(&mut self, name: &str, m: &Measurement) -> fmt::Result {
+        for dir in [Direction::FromGrid, Direction::ToGrid] {
+            for power in [Power::Active, Power::Reactive, Power::Apparent] {
+                let key = FieldKey(name, dir, power);
+                self.field(key, m.get(power, dir))?;
+            }
+        }
+        Ok(())
+    }
+
+    fn finish(self, time: Option<Timestamp>) -> fmt::Result {
+        if self.empty {
+            return Ok(());
+        }
+        let w = &mut self.writer.w;
+        #[cfg(feature = "time")]
+        if let Some(time) = time {
+            write!(w, " {}", time.unix_timestamp_nanos())?;
+        }
+        // without `time`, InfluxDB timestamps the point when written
+        #[cfg(not(feature = "time"))]
+        let _ = time;
+        w.write_char('\n')
+    }
+}
+
+struct FieldKey<'a>(&'a str, Direction, Power);
+
+impl Display for FieldKey<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}_{}_{}", self.0, self.1.name(), self.2.name())
+    }
+}
+
+/// An integer field value.
+struct Integer(i64);
+
+impl Display for Integer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}i", self.0)
+    }
+}
+
+/// A string field value.
+struct Quoted<'a>(&'a str);
+
+impl Display for Quoted<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_char('"')?;
+        escape(f, self.0, &['"', '\\'])?;
+        f.write_char('"')
+    }
+}
+
+fn escape(w: &mut impl Write, s: &str, special: &[char]) -> fmt::Result {
+    for c in s.chars() {
+        if special.contains(&c) {
+            w.write_char('\\')?;
+        }
+        w.write_char(c)?;
+    }
+    Ok(())
+}
+
+#[cfg(all(test, feature = "time"))]
+mod tests {
+    use std::string::String;
+
+    use time::macros::datetime;
+
+    use crate::state::State;
+    use crate::{Direction, Object, Text, Watts};
+
+    use super::LineProtocolWriter;
+
+    #[test]
+    fn state() {
+        let mut state = State::default();
+        state.insert(Object::DateTime(datetime!(2024-01-01 12:00 +1)));
+        state.insert(Object::TotalPower(Direction::ToGrid, Watts(1500).into()));
+
+        let mut w = LineProtocolWriter::new(String::new(), "power meter").meter("a,b");
+        w.state(&state).unwrap();
+        assert_eq!(
+            w.into_inner(),
+            "power\\ meter,meter=a\\,b power_to_grid_active=1.5 1704106800000000000\n"
+        );
+    }
+
+    #[test]
+    fn objects() {
+        let mut w = LineProtocolWriter::new(String::new(), "han");
+        w.objects([
+            Object::DateTime(datetime!(2024-01-01 12:00 +1)),
+            Object::EquipmentId(Text::new("say \"hi\"")),
+            Object::TariffIndicator(2),
+        ])
+        .unwrap();
+        assert_eq!(
+            w.into_inner(),
+            "han 0-0:96.1.1=\"say \\\"hi\\\"\",0-0:96.14.0=2i 1704106800000000000\n"
+        );
+
+        let mut w = LineProtocolWriter::new(String::new(), "han");
+        w.objects([]).unwrap();
+        assert_eq!(w.into_inner(), "");
+    }
+}
@@ -0,0 +1,365 @@
+//! A stable JSON representation of [`State`] and [`Telegram`], for
+//! dashboards and flows such as Node-RED that consume the output of several
+//! crate versions.
+//!
+//! The representations are versioned by their `"version"` field, currently
+//! `1`. The `json` feature enables `time`, so that timestamps have the same
+//! type in every build.
+//! Fields may be added within a version, but are never renamed, removed or
+//! given other units or types. The fields, in their default snake case
+//! naming, are:
+//!
+//! | Field | Value |
+//! | --- | --- |
+//! | `version` | `1` |
+//! | `datetime` | Unix timestamp in seconds |
+//! | `energy` | [measurement](#measurements) in kWh, kvarh and kVAh |
+//! | `tariff_energy` | array of 4 [measurements](#measurements) in kWh, kvarh and kVAh |
+//! | `power` | [measurement](#measurements) in kW, kvar and kVA |
+//! | `lines` | array of 3 [lines](#lines) |
+//! | `power_factor` | -1.0 to 1.0 |
+//! | `neutral_current` | A |
+//! | `frequency` | Hz |
+//! | `demand` | object of `average` and `max` in kW and `max_time` as `datetime` |
+//! | `sub_meters` | object of `gas`, `water` and `hot_water` in m3 and `heat` in GJ |
+//! | `channels` | array of 4 objects of `medium` (`"Electricity"`, `"Heat"`, `"Gas"`, `"Water"` or `"HotWater"`), `reading`, `unit` (e.g. `"m3"`) and `timestamp` as `datetime` |
+//! | `power_failures` | count |
+//! | `long_power_failures` | count |
+//!
+//! # Measurements
+//!
+//! Objects of `from_grid` and `to_grid`, each an object of `active`,
+//! `reactive` and `apparent`.
+//!
+//! # Lines
+//!
+//! Objects of `power` as a [measurement](#measurements) in kW, kvar and kVA,
+//! `voltage` in V, `current` in A, `power_factor` and the `voltage_sags` and
+//! `voltage_swells` counts.
+//!
+//! # Telegrams
+//!
+//! [`Telegram::to_json`] maps the [`Obis`](crate::Obis) reference of each
+//! recognized object, e.g. `"1-0:1.8.0"`, to its value:
+//!
+//! | Object | Value |
+//! | --- | --- |
+//! | time | as `datetime` |
+//! | text, e.g. the equipment identifier | string, or array of bytes if not UTF-8 |
+//! | other | the value as a scaled integer, e.g. Wh for kWh registers (see [`Object::scaled_value`](crate::Object::scaled_value)) |
+//!
+//! along with `version` and an `unknown` array of the references that were
+//! not recognized. Malformed objects are left out.
+//!
+//! # Options
+//!
+//! [`JsonOptions`] can rename the fields to camel case and suffix values
+//! having a unit with it, e.g. `active_kwh` or `activeKwh`. Unset values are
+//! left out unless [`JsonOptions::explicit_nulls`] is given.
+//!
+//! ```
+//! use han::json::JsonOptions;
+//! use han::Telegram;
+//!
+//! let state = Telegram::parse(include_str!("../test/ell.txt"))?.to_state()?;
+//! let options = JsonOptions::new().camel_case().unit_suffixes();
+//! let json = state.to_json_with(&options);
+//!
+//! assert_eq!(json["version"], 1);
+//! assert_eq!(json["energy"]["fromGrid"]["activeKwh"], 6136.936);
+//! assert_eq!(json["lines"][2]["voltageV"], 235.5);
+//! assert!(json["tariffEnergy"][0]["toGrid"].get("activeKwh").is_none());
+//! # Ok::<(), han::Error>(())
+//! ```
+
+use std::string::String;
+use std::vec::Vec;
+
+use serde_json::{Map, Value};
+
+use crate::state::{Measurement, State};
+#[cfg(doc)]
+use crate::Telegram;
+use crate::{Direction, Medium, Power, Timestamp, Unit};
+
+/// The version of the JSON representation.
+pub const VERSION: u32 = 1;
+
+/// Naming options for the JSON representation of a [`State`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct JsonOptions {
+    camel_case: bool,
+    unit_suffixes: bool,
+    explicit_nulls: bool,
+}
+
+impl JsonOptions {
+    /// Snake case field names without unit suffixes, leaving out unset
+    /// values.
+    pub const fn new() -> Self {
+        Self {
+            camel_case: false,
+            unit_suffixes: false,
+            explicit_nulls: false,
+        }
+    }
+
+    /// Name fields in camel case, e.g. `neutralCurrent`.
+    pub const fn camel_case(mut self) -> Self {
+        self.camel_case = true;
+        self
+    }
+
+    /// Suffix the names of values having a unit with the lowercase unit,
+    /// e.g. `neutral_current_a`.
+    pub const fn unit_suffixes(mut self) -> Self {
+        self.unit_suffixes = true;
+        self
+    }
+
+    /// Write unset values as `null` rather than leaving them out.
+    pub const fn explicit_nulls(mut self) -> Self {
+        self.explicit_nulls = true;
+        self
+    }
+
+    fn key(&self, name: &str, unit: Option<Unit>) -> String {
+        let mut key = String::from(name);
+        if let Some(unit) = unit.filter(|_| self.unit_suffixes) {
+            key.push('_');
+            key.push_str(&unit.as_str().to_ascii_lowercase());
+        }
+        if !self.camel_case {
+            return key;
+        }
+
+        let mut camel = String::with_capacity(key.len());
+        let mut upper = false;
+        for c in key.chars() {
+            match c {
+                '_' => upper = true,
+                c if upper => {
+                    camel.push(c.to_ascii_uppercase());
+                    upper = false;
+                }
+                c => camel.push(c),
+            }
+        }
+        camel
+    }
+}
+
+impl Default for JsonOptions {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// An object being built according to the options.
+struct Object<'o> {
+    options: &'o JsonOptions,
+    map: Map<String, Value>,
+}
+
+impl<'o> Object<'o> {
+    fn new(options: &'o JsonOptions) -> Self {
+        Self {
+            options,
+            map: Map::new(),
+        }
+    }
+
+    fn field(&mut self, name: &str, unit: Option<Unit>, value: Option<impl Into<Value>>) {
+        if value.is_some() || self.options.explicit_nulls {
+            self.map.insert(
+                self.options.key(name, unit),
+                value.map_or(Value::Null, Into::into),
+            );
+        }
+    }
+
+    fn object(&mut self, name: &str, value: Object) {
+        self.map.insert(self.options.key(name, None), value.into());
+    }
+
+    fn array(&mut self, name: &str, values: impl IntoIterator<Item = Value>) {
+        let values = values.into_iter().collect::<Vec<_>>();
+        self.map.insert(self.options.key(name, None), values.into());
+    }
+}
+
+/// A measurement whose components are in `units`, in the order active,
+/// reactive and apparent.
+fn measurement<'o>(options: &'o JsonOptions, m: &Measurement, units: [Unit; 3]) -> Object<'o> {
+    let mut dirs = Object::new(options);
+    for (dir, name) in [
+        (Direction::FromGrid, "from_grid"),
+        (Direction::ToGrid, "to_grid"),
+    ] {
+        let mut components = Object::new(options);
+        for (power, unit) in [Power::Active, Power::Reactive, Power::Apparent]
+            .into_iter()
+            .zip(units)
+        {
+            components.field(power.name(), Some(unit), m.get(power, dir));
+        }
+        dirs.object(name, components);
+    }
+    dirs
+}
+
+impl From<Object<'_>> for Value {
+    fn from(o: Object) -> Self {
+        o.map.into()
+    }
+}
+
+/// A timestamp as in the `datetime` field.
+pub(crate) fn timestamp(time: Timestamp) -> Value {
+    time.unix_timestamp().into()
+}
+
+/// The name of a medium, spelled out so that renaming a variant doesn't
+/// change the representation.
+fn medium(m: Medium) -> &'static str {
+    match m {
+        Medium::Electricity => "Electricity",
+        Medium::Heat => "Heat",
+        Medium::Gas => "Gas",
+        Medium::Water => "Water",
+        Medium::HotWater => "HotWater",
+    }
+}
+
+const ENERGY: [Unit; 3] = [Unit::KWh, Unit::KVArh, Unit::KVAh];
+const POWER: [Unit; 3] = [Unit::KW, Unit::KVAr, Unit::KVA];
+
+impl State {
+    /// Convert the state to the stable JSON representation described in the
+    /// [module documentation](self).
+    pub fn to_json_with(&self, options: &JsonOptions) -> Value {
+        let mut o = Object::new(options);
+        o.field("version", None, Some(VERSION));
+        o.field("datetime", None, self.datetime.map(timestamp));
+        o.object("energy", measurement(options, &self.energy, ENERGY));
+
+        let tariffs = self
+            .tariff_energy
+            .iter()
+            .map(|m| measurement(options, m, ENERGY));
+        o.array("tariff_energy", tariffs.map(Into::into));
+        o.object("power", measurement(options, &self.power, POWER));
+
+        let lines = self.lines.iter().map(|l| {
+            let mut line = Object::new(options);
+            line.object("power", measurement(options, &l.power, POWER));
+            line.field("voltage", Some(Unit::V), l.voltage);
+            line.field("current", Some(Unit::A), l.current);
+            line.field("power_factor", None, l.power_factor);
+            line.field("voltage_sags", None, l.voltage_sags);
+            line.field("voltage_swells", None, l.voltage_swells);
+            line.into()
+        });
+        o.array("lines", lines);
+
+        o.field("power_factor", None, self.power_factor);
+        o.field("neutral_current", Some(Unit::A), self.neutral_current);
+        o.field("frequency", Some(Unit::Hz), self.frequency);
+
+        let mut demand = Object::new(options);
+        demand.field("average", Some(Unit::KW), self.demand.average);
+        demand.field("max", Some(Unit::KW), self.demand.max);
+        demand.field("max_time", None, self.demand.max_time.map(timestamp));
+        o.object("demand", demand);
+
+        let mut sub_meters = Object::new(options);
+        sub_meters.field("gas", Some(Unit::M3), self.sub_meters.gas);
+        sub_meters.field("water", Some(Unit::M3), self.sub_meters.water);
+        sub_meters.field("hot_water", Some(Unit::M3), self.sub_meters.hot_water);
+        sub_meters.field("heat", Some(Unit::GJ), self.sub_meters.heat);
+        o.object("sub_meters", sub_meters);
+
+        let channels = self.channels.iter().map(|c| {
+            let mut channel = Object::new(options);
+            channel.field("medium", None, c.medium.map(medium));
+            channel.field("reading", None, c.reading);
+            channel.field("unit", None, c.unit.map(|u| u.as_str()));
+            channel.field("timestamp", None, c.timestamp.map(timestamp));
+            channel.into()
+        });
+        o.array("channels", channels);
+
+        o.field("power_failures", None, self.power_failures);
+        o.field("long_power_failures", None, self.long_power_failures);
+        o.into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use crate::state::State;
+    use crate::{Decivolts, Line, Object, Telegram};
+
+    use super::JsonOptions;
+
+    #[test]
+    fn naming() {
+        let options = JsonOptions::new();
+        assert_eq!(options.key("hot_water", Some(crate::Unit::M3)), "hot_water");
+        let options = options.unit_suffixes();
+        assert_eq!(
+            options.key("hot_water", Some(crate::Unit::M3)),
+            "hot_water_m3"
+        );
+        let options = options.camel_case();
+        assert_eq!(
+            options.key("hot_water", Some(crate::Unit::M3)),
+            "hotWaterM3"
+        );
+        assert_eq!(options.key("voltage_sags", None), "voltageSags");
+    }
+
+    #[test]
+    fn nulls() {
+        let mut state = State::default();
+        state.insert(Object::Voltage(Line::L1, Decivolts(2301)));
+
+        let json = state.to_json_with(&JsonOptions::new());
+        assert_eq!(
+            json["lines"][0],
+            json!({ "power": { "from_grid": {}, "to_grid": {} }, "voltage": 230.1 })
+        );
+        assert!(json.get("datetime").is_none());
+
+        let json = state.to_json_with(&JsonOptions::new().explicit_nulls());
+        assert_eq!(
+            json["lines"][0],
+            json!({
+                "power": {
+                    "from_grid": { "active": null, "reactive": null, "apparent": null },
+                    "to_grid": { "active": null, "reactive": null, "apparent": null },
+                },
+                "voltage": 230.1,
+                "current": null,
+                "power_factor": null,
+                "voltage_sags": null,
+                "voltage_swells": null,
+            })
+        );
+        assert!(json["datetime"].is_null());
+    }
+
+    #[test]
+    fn channels() {
+        let telegram = Telegram::parse(include_str!("../test/dsmr5.txt")).unwrap();
+        let json = telegram
+            .to_state()
+            .unwrap()
+            .to_json_with(&JsonOptions::new());
+        assert_eq!(json["channels"][0]["medium"], "Gas");
+        assert_eq!(json["channels"][0]["unit"], "m3");
+        assert_eq!(json["channels"][0]["reading"], 12785.123);
+    }
+}
@@ -5,16 +5,64 @@
 #![cfg_attr(not(feature = "std"), no_std)]
 #![cfg_attr(docsrs, feature(doc_auto_cfg))]
 
+#[cfg(feature = "alloc")]
+extern crate alloc;
+#[cfg(test)]
+extern crate std;
+
+#[cfg(feature = "tokio-util")]
+mod codec;
+#[cfg(feature = "time")]
+pub mod cost;
+#[cfg(feature = "crypto")]
+mod crypto;
+mod decoder;
+mod expected;
+#[cfg(feature = "fixed")]
+mod fixed;
+pub mod hdlc;
+pub mod influx;
+#[cfg(feature = "json")]
+pub mod json;
+pub mod mbus;
 mod obis;
+mod parser;
+mod profile;
+#[cfg(feature = "prometheus")]
+pub mod prometheus;
+mod quantity;
 mod read;
+#[cfg(feature = "std")]
+pub mod serial;
+pub mod state;
+mod text;
+mod timestamp;
+mod unit;
+mod write;
 
 use core::fmt::Display;
 
+#[cfg(feature = "tokio-util")]
+pub use codec::*;
+#[cfg(feature = "crypto")]
+pub use crypto::*;
+pub use decoder::*;
+pub use expected::*;
+#[cfg(feature = "fixed")]
+pub use fixed::*;
 pub use obis::*;
+pub use parser::*;
+pub use profile::*;
+pub use quantity::*;
 pub use read::*;
+pub use text::*;
+pub use timestamp::*;
+pub use unit::*;
+pub use write::*;
 
 /// HAN error.
 #[derive(Debug)]
+#[cfg_attr(feature = "defmt-03", derive(defmt::Format))]
 pub enum Error {
     /// Parsing failed due to an invalid format.
     InvalidFormat,
@@ -23,6 +71,18 @@ pub enum Error {
     /// The parser came across a correctly formatted, but unrecognized,
     /// [`Obis`] reference.
     UnrecognizedReference,
+    /// The same [`Obis`] reference occurred more than once in a telegram.
+    DuplicateReference(Obis),
+    /// An expected [`Obis`] reference was missing from a telegram.
+    MissingReference(Obis),
+    /// A telegram did not fit in the read buffer.
+    BufferOverflow,
+    /// The input ended in the middle of a telegram.
+    UnexpectedEof,
+    /// An encrypted frame failed authentication, e.g. due to a wrong key.
+    Decryption,
+    /// A value had a different unit than expected for its [`Obis`] reference.
+    UnitMismatch,
 }
 
 impl Display for Error {
@@ -31,6 +91,12 @@ impl Display for Error {
             Error::InvalidFormat => "invalid format",
             Error::Checksum => "checksum mismatch",
             Error::UnrecognizedReference => "unrecognized obis reference",
+            Error::DuplicateReference(_) => "duplicate obis reference",
+            Error::MissingReference(_) => "missing obis reference",
+            Error::BufferOverflow => "telegram does not fit in the buffer",
+            Error::UnexpectedEof => "unexpected end of telegram",
+            Error::Decryption => "decryption failed",
+            Error::UnitMismatch => "unexpected unit",
         };
 
         f.write_str(msg)
@@ -0,0 +1,160 @@
+//! M-Bus long frames (EN 13757-2) carrying DLMS/COSEM data notifications, as
+//! used by some HAN ports instead of HDLC.
+//!
+//! The information field of a frame can be passed on to
+//! [`Notification::parse`](crate::hdlc::Notification::parse), once all
+//! segments have been received.
+//!
+//! ```
+//! use han::{hdlc::Notification, mbus::Frame};
+//!
+//! fn handle(frame: &[u8]) -> Result<(), han::Error> {
+//!     let frame = Frame::parse(frame)?;
+//!     assert!(!frame.segmented);
+//!
+//!     for obj in Notification::parse(frame.information)?.objects() {
+//!         println!("{:?}", obj?);
+//!     }
+//!
+//!     Ok(())
+//! }
+//! ```
+
+use crate::{Error, Result};
+
+/// The first byte of a long frame, repeated after the length.
+pub const START: u8 = 0x68;
+
+/// The last byte of a long frame.
+pub const STOP: u8 = 0x16;
+
+/// Bytes preceding the user data: the two start bytes and lengths.
+const HEADER_LEN: usize = 4;
+
+/// Control information fields indicating DLMS/COSEM transport, whose low
+/// nibble is the segment number.
+const DLMS_CI: core::ops::RangeInclusive<u8> = 0x00..=0x1f;
+
+/// Bit of the control information field marking the last segment.
+const FINAL_SEGMENT: u8 = 0x10;
+
+/// An M-Bus long frame with DLMS/COSEM transport.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Frame<'a> {
+    /// The control field.
+    pub control: u8,
+    /// The primary address of the meter.
+    pub address: u8,
+    /// Whether the information continues in the next frame.
+    pub segmented: bool,
+    /// The information field following the transport addresses, i.e. an APDU
+    /// or a segment thereof.
+    pub information: &'a [u8],
+}
+
+impl<'a> Frame<'a> {
+    /// Total length of the frame starting at the beginning of `buf`, or `None`
+    /// if more bytes are needed to tell.
+    ///
+    /// This can be used to find the end of a frame in a byte stream.
+    pub fn frame_len(buf: &[u8]) -> Result<Option<usize>> {
+        match buf {
+            [START, len, len2, START, ..] if len == len2 => {
+                Ok(Some(HEADER_LEN + usize::from(*len) + 2))
+            }
+            [START, len, len2] if len == len2 => Ok(None),
+            [START] | [START, _] | [] => Ok(None),
+            _ => Err(Error::InvalidFormat),
+        }
+    }
+
+    /// Parse a complete frame, from the first start byte through the stop
+    /// byte, verifying its checksum.
+    pub fn parse(frame: &'a [u8]) -> Result<Self> {
+        let len = Self::frame_len(frame)?.ok_or(Error::InvalidFormat)?;
+        if frame.len() != len || frame[len - 1] != STOP {
+            return Err(Error::InvalidFormat);
+        }
+
+        let user_data = &frame[HEADER_LEN..len - 2];
+        let checksum = user_data.iter().fold(0u8, |sum, b| sum.wrapping_add(*b));
+        if checksum != frame[len - 2] {
+            return Err(Error::Checksum);
+        }
+
+        match *user_data {
+            // the source and destination transport addresses precede the
+            // information
+            [control, address, ci, _stsap, _dtsap, ref information @ ..]
+                if DLMS_CI.contains(&ci) =>
+            {
+                Ok(Frame {
+                    control,
+                    address,
+                    segmented: ci & FINAL_SEGMENT == 0,
+                    information,
+                })
+            }
+            _ => Err(Error::InvalidFormat),
+        }
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use std::vec::Vec;
+
+    use crate::Error;
+
+    use super::Frame;
+
+    /// Wrap `information` in a long frame with a correct checksum.
+    pub(crate) fn frame(ci: u8, information: &[u8]) -> Vec<u8> {
+        let user_data = [&[0x53, 0xff, ci, 0x67, 0x67][..], information].concat();
+        let len = user_data.len() as u8;
+        let checksum = user_data.iter().fold(0u8, |sum, b| sum.wrapping_add(*b));
+
+        [
+            &[super::START, len, len, super::START][..],
+            &user_data,
+            &[checksum, super::STOP],
+        ]
+        .concat()
+    }
+
+    #[test]
+    fn frame_len() {
+        let frame = frame(0x10, b"abc");
+        assert_eq!(Frame::frame_len(&frame[..3]).unwrap(), None);
+        assert_eq!(Frame::frame_len(&frame).unwrap(), Some(frame.len()));
+        assert!(Frame::frame_len(&[0x68, 0x08, 0x09]).is_err());
+        assert!(Frame::frame_len(&[0x7e]).is_err());
+    }
+
+    #[test]
+    fn parse() {
+        let bytes = frame(0x10, b"abc");
+        let frame = Frame::parse(&bytes).unwrap();
+        assert_eq!(frame.control, 0x53);
+        assert_eq!(frame.address, 0xff);
+        assert!(!frame.segmented);
+        assert_eq!(frame.information, b"abc");
+
+        let bytes = super::tests::frame(0x01, b"abc");
+        assert!(Frame::parse(&bytes).unwrap().segmented);
+    }
+
+    #[test]
+    fn invalid() {
+        let mut bytes = frame(0x10, b"abc");
+        bytes[7] ^= 1;
+        assert!(matches!(Frame::parse(&bytes), Err(Error::Checksum)));
+
+        // not DLMS/COSEM transport
+        let bytes = frame(0x72, b"abc");
+        assert!(matches!(Frame::parse(&bytes), Err(Error::InvalidFormat)));
+
+        let bytes = frame(0x10, b"abc");
+        assert!(Frame::parse(&bytes[..bytes.len() - 1]).is_err());
+    }
+}
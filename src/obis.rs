@@ -1,10 +1,13 @@
 use core::fmt::{Debug, Display};
 use core::str::FromStr;
 
-use crate::{Error, Result};
+use crate::profile::split_values;
+use crate::{Deciamps, Decivolts, EnergyQuantity, Error, PowerQuantity, Result, Text, Unit, Watts};
 
 /// One conductor in a three-phase system.
-#[derive(Debug, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "defmt-03", derive(defmt::Format))]
 pub enum Line {
     /// Line 1
     L1,
@@ -14,19 +17,25 @@ pub enum Line {
     L3,
 }
 
-/// The type of power measured (*active* or *reactive*).
+/// The type of power measured (*active*, *reactive* or *apparent*).
 ///
 /// [Wikipedia](https://en.wikipedia.org/wiki/AC_power#Active,_reactive,_apparent,_and_complex_power_in_sinusoidal_steady-state)
-#[derive(Debug, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "defmt-03", derive(defmt::Format))]
 pub enum Power {
     /// Active power ([W](https://en.wikipedia.org/wiki/Watt)).
     Active,
     /// Reactive power ([VAr](https://en.wikipedia.org/wiki/Volt-ampere#Reactive)).
     Reactive,
+    /// Apparent power ([VA](https://en.wikipedia.org/wiki/Volt-ampere)).
+    Apparent,
 }
 
 /// Direction of the electricity flow.
-#[derive(Debug, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "defmt-03", derive(defmt::Format))]
 pub enum Direction {
     /// Energy received from the grid.
     FromGrid,
@@ -34,49 +43,296 @@ pub enum Direction {
     ToGrid,
 }
 
-use time::{Date, Month, OffsetDateTime, PrimitiveDateTime, Time, UtcOffset};
+#[cfg(feature = "time")]
+use time::{macros::offset, UtcOffset};
+
+use crate::{RawTimestamp, Timestamp};
 use Direction::*;
 use Power::*;
 
+/// The medium measured, as indicated by the A group of an [`Obis`]
+/// reference.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "defmt-03", derive(defmt::Format))]
+pub enum Medium {
+    /// Electricity (1).
+    Electricity,
+    /// Heat (6).
+    Heat,
+    /// Gas (7).
+    Gas,
+    /// Cold water (8).
+    Water,
+    /// Hot water (9).
+    HotWater,
+}
+
+impl Medium {
+    /// Determine the medium from the A group of an [`Obis`] reference.
+    pub fn from_code(a: u8) -> Option<Self> {
+        match a {
+            1 => Some(Medium::Electricity),
+            6 => Some(Medium::Heat),
+            7 => Some(Medium::Gas),
+            8 => Some(Medium::Water),
+            9 => Some(Medium::HotWater),
+            _ => None,
+        }
+    }
+
+    /// The A group of an [`Obis`] reference measuring this medium.
+    pub fn code(&self) -> u8 {
+        match self {
+            Medium::Electricity => 1,
+            Medium::Heat => 6,
+            Medium::Gas => 7,
+            Medium::Water => 8,
+            Medium::HotWater => 9,
+        }
+    }
+
+    /// Determine the medium from the device type of an M-Bus meter, as
+    /// reported by [`Object::MBusDeviceType`].
+    ///
+    /// ```
+    /// use han::Medium;
+    ///
+    /// assert_eq!(Medium::from_device_type(3), Some(Medium::Gas));
+    /// ```
+    pub fn from_device_type(device_type: u8) -> Option<Self> {
+        match device_type {
+            0x02 => Some(Medium::Electricity),
+            0x03 => Some(Medium::Gas),
+            // outlet and inlet
+            0x04 | 0x0c => Some(Medium::Heat),
+            // warm (30–90 °C) and hot (≥ 90 °C)
+            0x06 | 0x15 => Some(Medium::HotWater),
+            0x07 | 0x16 => Some(Medium::Water),
+            _ => None,
+        }
+    }
+
+    /// The device type of an M-Bus meter measuring this medium.
+    pub fn device_type(&self) -> u8 {
+        match self {
+            Medium::Electricity => 0x02,
+            Medium::Heat => 0x04,
+            Medium::Gas => 0x03,
+            Medium::Water => 0x07,
+            Medium::HotWater => 0x06,
+        }
+    }
+
+    /// The C group of the accumulated volume register, if any.
+    pub(crate) fn volume_c(&self) -> Option<u8> {
+        match self {
+            Medium::Electricity => None,
+            Medium::Heat => Some(2),
+            Medium::Gas => Some(3),
+            Medium::Water | Medium::HotWater => Some(1),
+        }
+    }
+}
+
+/// The version of the P1 specification that a meter follows.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "defmt-03", derive(defmt::Format))]
+pub enum P1Version {
+    /// Dutch DSMR (1-3:0.2.8), e.g. 50 for DSMR 5.0.
+    Dsmr(u8),
+    /// Belgian eMUCS-P1 (0-0:96.1.4), e.g. 50217.
+    Emucs(u32),
+}
+
+/// Position of a remotely operated breaker or valve.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "defmt-03", derive(defmt::Format))]
+pub enum SwitchPosition {
+    /// Disconnected (0).
+    Off,
+    /// Connected (1).
+    On,
+    /// Ready for reconnection by the customer (2).
+    Released,
+}
+
+impl SwitchPosition {
+    /// Determine the position from its code.
+    pub fn from_code(code: u8) -> Option<Self> {
+        match code {
+            0 => Some(SwitchPosition::Off),
+            1 => Some(SwitchPosition::On),
+            2 => Some(SwitchPosition::Released),
+            _ => None,
+        }
+    }
+
+    /// The code of the position.
+    pub fn code(&self) -> u8 {
+        match self {
+            SwitchPosition::Off => 0,
+            SwitchPosition::On => 1,
+            SwitchPosition::Released => 2,
+        }
+    }
+}
+
 /// A parsed line of the body of a [`Telegram`](crate::Telegram).
 ///
 /// ```
 /// use core::str::FromStr;
-/// use han::{Object, Power, Direction};
+/// use han::{Direction, Object, VoltAmpereReactiveHours};
 ///
 /// // 1-0:3.8.0 specifies the received reactive energy.
 /// let obj = "1-0:3.8.0(00000008.909*kvarh)".parse::<Object>()?;
 /// assert_eq!(
 ///     obj,
-///     Object::Energy(
-///         Power::Reactive,
-///         Direction::FromGrid,
-///         8909, // VAr    
-///     ),
+///     Object::Energy(Direction::FromGrid, VoltAmpereReactiveHours(8909).into()),
 /// );
 /// # Ok::<(), han::Error>(())
 /// ```
-#[derive(Debug, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "defmt-03", derive(defmt::Format))]
 pub enum Object {
-    /// Timestamp with the correct timezone (CET/CEST[^dst]).
+    /// Timestamp with the correct timezone (CET/CEST[^dst], unless
+    /// configured with [`ParseOptions::offsets`]).
     ///
     /// [^dst]: According to the Swedish specification, only CET is ever used.
     ///     This library supports both, however.
-    DateTime(OffsetDateTime),
-    /// Energy received or returned across all [`Line`]s (Wh or VArh).
-    Energy(Power, Direction, u32),
-    /// Power of all lines combined (W or VAr).
-    TotalPower(Power, Direction, u32),
-    /// Power per [`Line`] (W or VAr).
-    Power(Line, Power, Direction, u32),
+    DateTime(#[cfg_attr(feature = "defmt-03", defmt(Debug2Format))] Timestamp),
+    /// Energy received or returned across all [`Line`]s (Wh, VArh or VAh).
+    Energy(Direction, EnergyQuantity),
+    /// Power of all lines combined (W, VAr or VA).
+    TotalPower(Direction, PowerQuantity),
+    /// Power per [`Line`] (W, VAr or VA).
+    Power(Line, Direction, PowerQuantity),
     /// Phase voltage per [`Line`] measured in decivolts (dV, 0.1 V).
-    Voltage(Line, u16),
+    Voltage(Line, Decivolts),
     /// Phase current per [`Line`] (dA, 0.1 A).
-    Current(Line, u16),
+    Current(Line, Deciamps),
+    /// Current in the neutral conductor (dA, 0.1 A).
+    NeutralCurrent(Deciamps),
+    /// Power factor per [`Line`] in thousandths (‰).
+    LinePowerFactor(Line, i16),
+    /// Power factor of all lines combined in thousandths (‰).
+    PowerFactor(i16),
+    /// Grid frequency in centihertz (cHz, 0.01 Hz).
+    Frequency(u16),
+    /// Accumulated volume of a non-electricity [`Medium`] in litres (l, 0.001 m³).
+    Volume(Medium, u32),
+    /// Accumulated heat energy in megajoules (MJ, 0.001 GJ).
+    HeatEnergy(u32),
+    /// Version of the P1 output.
+    Version(P1Version),
+    /// Energy received or returned during a tariff, numbered from 1, e.g.
+    /// day (1) and night (2) (Wh or VArh).
+    EnergyTariff(Direction, u8, EnergyQuantity),
+    /// Equipment identifier of the meter (0-0:96.1.1, or 0-0:96.1.0 on some
+    /// meters), usually its serial number.
+    EquipmentId(Text),
+    /// Text message from the grid operator.
+    TextMessage(Text),
+    /// Number of power failures in any phase.
+    PowerFailures(u32),
+    /// Number of long power failures in any phase.
+    LongPowerFailures(u32),
+    /// Number of voltage sags per [`Line`].
+    VoltageSags(Line, u32),
+    /// Number of voltage swells per [`Line`].
+    VoltageSwells(Line, u32),
+    /// Average active power received during the current quarter-hour (W).
+    AverageDemand(Watts),
+    /// Highest quarter-hour average demand of the running month and when it
+    /// occurred (W).
+    MaxDemand(
+        #[cfg_attr(feature = "defmt-03", defmt(Debug2Format))] Timestamp,
+        Watts,
+    ),
+    /// Number of months in the maximum demand history. The months themselves
+    /// are parsed by [`DemandHistory`](crate::DemandHistory).
+    MaxDemandHistory(u8),
+    /// Number of events in the power failure log. The events themselves are
+    /// parsed by [`PowerFailureLog`](crate::PowerFailureLog).
+    PowerFailureLog(u8),
+    /// Position of the breaker.
+    SwitchPosition(SwitchPosition),
+    /// Threshold of the power limiter (W).
+    LimiterThreshold(Watts),
+    /// Threshold of the fuse supervision (dA, 0.1 A).
+    FuseThreshold(Deciamps),
+    /// The active tariff, e.g. to tell peak from off-peak hours.
+    TariffIndicator(u16),
+    /// Device type of the meter on an M-Bus channel (1–4), e.g. 3 for gas,
+    /// 4 for heat and 7 for water.
+    ///
+    /// See [`Medium::from_device_type`].
+    MBusDeviceType(u8, u8),
+    /// Equipment identifier of the meter on an M-Bus channel (1–4).
+    MBusEquipmentId(u8, Text),
+    /// Position of the valve of the meter on an M-Bus channel (1–4), e.g. of
+    /// gas.
+    ValvePosition(u8, SwitchPosition),
+    /// The last reading of a meter, e.g. of gas, on an M-Bus channel.
+    MBusReading {
+        /// The M-Bus channel (1–4).
+        channel: u8,
+        /// When the meter was read.
+        #[cfg_attr(feature = "defmt-03", defmt(Debug2Format))]
+        timestamp: Timestamp,
+        /// The reading in thousandths of the unit, e.g. litres (l, 0.001 m³).
+        value: u32,
+        /// The unit of the reading.
+        unit: Unit,
+    },
+}
+
+/// A parsed line, or the raw values of a line whose reference is not
+/// recognized, e.g. a vendor-specific one.
+///
+/// ```
+/// use han::{LenientObject, Obis};
+///
+/// let obj = LenientObject::parse("0-0:96.99.0(ABCDEF)")?;
+/// assert_eq!(
+///     obj,
+///     LenientObject::Other(Obis(0, 0, 96, 99, 0).into(), "(ABCDEF)"),
+/// );
+/// # Ok::<(), han::Error>(())
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt-03", derive(defmt::Format))]
+pub enum LenientObject<'a> {
+    /// A recognized object.
+    Known(Object),
+    /// An object with an unrecognized reference and its values, including
+    /// the parentheses.
+    Other(ObisFull, &'a str),
+}
+
+impl<'a> LenientObject<'a> {
+    /// Parse a line, keeping the values of unrecognized references rather
+    /// than failing with [`Error::UnrecognizedReference`]. Units are not
+    /// checked, like with [`Object::parse_ignoring_units`].
+    pub fn parse(line: &'a str) -> Result<Self> {
+        match Object::parse_ignoring_units(line) {
+            Ok(obj) => Ok(LenientObject::Known(obj)),
+            Err(Error::UnrecognizedReference) => {
+                let (obis, _) = line.split_once('(').ok_or(Error::InvalidFormat)?;
+                Ok(LenientObject::Other(obis.parse()?, &line[obis.len()..]))
+            }
+            Err(e) => Err(e),
+        }
+    }
 }
 
 /// An *OBject Identifier System* identifier with the F group omitted.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "defmt-03", derive(defmt::Format))]
 pub struct Obis(pub u8, pub u8, pub u8, pub u8, pub u8);
 
 impl Display for Obis {
@@ -106,64 +362,618 @@ impl FromStr for Obis {
     }
 }
 
-/// Get the scalar and the unit from a value with a trailing parenthesis.
-fn split_value(s: &str) -> Option<(&str, &str)> {
-    let end = s.len().checked_sub(1)?; // s has a trailing parenthesis
-    let inner = s.get(..end)?;
-    inner.split_once('*')
+/// An [`Obis`] reference with its F group, which e.g. selects a historical
+/// billing period.
+///
+/// The F group follows either a `*`, as in `1-0:1.8.0*03`, or a `.`, as in
+/// `1-0:1.8.0.255`.
+///
+/// ```
+/// use han::{Obis, ObisFull};
+///
+/// let obis = "1-0:1.8.0*03".parse::<ObisFull>()?;
+/// assert_eq!(obis.obis, Obis(1, 0, 1, 8, 0));
+/// assert_eq!(obis.f, Some(3));
+/// # Ok::<(), han::Error>(())
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "defmt-03", derive(defmt::Format))]
+pub struct ObisFull {
+    /// The A to E groups.
+    pub obis: Obis,
+    /// The F group, if any.
+    pub f: Option<u8>,
+}
+
+impl ObisFull {
+    /// Returns `true` if the F group is absent or 255, i.e. if the reference
+    /// is to the current value rather than e.g. a historical one.
+    pub fn is_current(&self) -> bool {
+        matches!(self.f, None | Some(255))
+    }
+}
+
+impl From<Obis> for ObisFull {
+    fn from(obis: Obis) -> Self {
+        Self { obis, f: None }
+    }
+}
+
+impl Display for ObisFull {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        Display::fmt(&self.obis, f)?;
+        match self.f {
+            Some(group) => write!(f, "*{:02}", group),
+            None => Ok(()),
+        }
+    }
+}
+
+impl FromStr for ObisFull {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (a_to_e, f) = match s.split_once('*') {
+            Some((obis, f)) => (obis, Some(f)),
+            // the third dot, if any, precedes the F group
+            None => match s.match_indices('.').nth(2) {
+                Some((i, _)) => (&s[..i], Some(&s[i + 1..])),
+                None => (s, None),
+            },
+        };
+
+        let f = match f {
+            Some(f) => Some(
+                parse_digits(f)
+                    .and_then(|f| f.try_into().ok())
+                    .ok_or(Error::InvalidFormat)?,
+            ),
+            None => None,
+        };
+
+        Ok(Self {
+            obis: a_to_e.parse()?,
+            f,
+        })
+    }
+}
+
+/// Get the scalar from a value with a trailing parenthesis, e.g. `1.234*kW)`.
+fn split_value(s: &str, expected: Unit, check_units: bool) -> Result<&str> {
+    strip_unit(
+        s.strip_suffix(')').ok_or(Error::InvalidFormat)?,
+        expected,
+        check_units,
+    )
+}
+
+/// Get the scalar from a value with a unit, e.g. `1.234*kW`. Unless
+/// `check_units` is false, the unit must be the expected one.
+fn strip_unit(s: &str, expected: Unit, check_units: bool) -> Result<&str> {
+    let (scalar, unit) = s.split_once('*').ok_or(Error::InvalidFormat)?;
+    if check_units && unit.parse::<Unit>()? != expected {
+        return Err(Error::UnitMismatch);
+    }
+    Ok(scalar)
+}
+
+/// Parse an unsigned decimal with exactly `F` fractional digits into an
+/// integer scaled by 10<sup>`F`</sup>.
+fn parse_decimal<const F: u8>(decimal: &str) -> Option<u32> {
+    parse_wide_decimal::<F>(decimal)?.try_into().ok()
 }
 
-fn parse_decimal<const F: u8>(s: &str) -> Option<u32> {
-    let (decimal, _unit) = split_value(s)?;
+/// Like [`parse_decimal`], but for values that may not fit in a `u32`, such
+/// as energy registers.
+fn parse_wide_decimal<const F: u8>(decimal: &str) -> Option<u64> {
     let (i, f) = decimal.rsplit_once('.')?;
-    if f.len() != F.into() {
+    let is_digits = |s: &str| !s.is_empty() && s.bytes().all(|b| b.is_ascii_digit());
+    if f.len() != usize::from(F) || !is_digits(i) || !is_digits(f) {
         return None;
     }
-    let i: u32 = i.parse().ok()?;
-    let f: u32 = f.parse().ok()?;
+    let i: u64 = i.parse().ok()?;
+    let f: u64 = f.parse().ok()?;
+
+    i.checked_mul(10u64.pow(F.into()))?.checked_add(f)
+}
+
+/// Like [`parse_decimal`], but accepts a leading `-`.
+fn parse_signed_decimal<const F: u8>(decimal: &str) -> Option<i32> {
+    match decimal.strip_prefix('-') {
+        Some(decimal) => parse_decimal::<F>(decimal)?
+            .try_into()
+            .ok()
+            .map(i32::wrapping_neg),
+        None => parse_decimal::<F>(decimal)?.try_into().ok(),
+    }
+}
+
+/// Parse a value with the expected unit, e.g. `04.329*kW`, without any
+/// parentheses.
+pub(crate) fn parse_quantity<const F: u8>(s: &str, expected: Unit) -> Result<u32> {
+    parse_decimal::<F>(strip_unit(s, expected, true)?).ok_or(Error::InvalidFormat)
+}
 
-    i.checked_mul(10u32.pow(F.into()))?.checked_add(f)
+fn parse_kilo(s: &str, unit: Unit, check_units: bool) -> Result<u32, Error> {
+    parse_decimal::<3>(split_value(s, unit, check_units)?).ok_or(Error::InvalidFormat)
 }
 
-fn parse_kilo(s: &str) -> Result<u32, Error> {
-    parse_decimal::<3>(s).ok_or(Error::InvalidFormat)
+/// Like [`parse_kilo`], but for energy registers.
+fn parse_energy(s: &str, unit: Unit, check_units: bool) -> Result<u64, Error> {
+    parse_wide_decimal::<3>(split_value(s, unit, check_units)?).ok_or(Error::InvalidFormat)
 }
 
-fn parse_deci(s: &str) -> Result<u16, Error> {
-    parse_decimal::<1>(s)
+/// Parse a value with one decimal, without decimals as DSMR meters send
+/// currents, or with two decimals as eMUCS meters do (truncated).
+fn parse_deci(s: &str, unit: Unit, check_units: bool) -> Result<u16, Error> {
+    let decimal = split_value(s, unit, check_units)?;
+    let v = match decimal.rsplit_once('.') {
+        Some((_, f)) if f.len() == 2 => parse_decimal::<2>(decimal).map(|v| v / 10),
+        Some(_) => parse_decimal::<1>(decimal),
+        None => parse_digits(decimal).and_then(|v| v.checked_mul(10)),
+    };
+    v.and_then(|v| v.try_into().ok())
+        .ok_or(Error::InvalidFormat)
+}
+
+/// Parse a non-empty string of decimal digits.
+pub(crate) fn parse_digits(s: &str) -> Option<u32> {
+    if s.is_empty() || !s.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    s.parse().ok()
+}
+
+/// Parse a unitless integer with a trailing parenthesis, e.g. a counter.
+fn parse_integer<T: TryFrom<u32>>(s: &str) -> Result<T> {
+    s.strip_suffix(')')
+        .and_then(parse_digits)
         .and_then(|v| v.try_into().ok())
         .ok_or(Error::InvalidFormat)
 }
 
-/// Determine if the power specified is active or reactive, as well as the [`Direction`].
-fn pow_dir(a: u8) -> Result<(Power, Direction)> {
+/// Parse the number of entries of a buffer, i.e. the first of its values.
+fn parse_count(s: &str) -> Result<u8> {
+    let (n, _) = s.split_once(')').ok_or(Error::InvalidFormat)?;
+    parse_digits(n)
+        .and_then(|n| n.try_into().ok())
+        .ok_or(Error::InvalidFormat)
+}
+
+/// Parse a hex-encoded string with a trailing parenthesis.
+fn parse_text(s: &str) -> Result<Text> {
+    Text::from_hex(s.strip_suffix(')').ok_or(Error::InvalidFormat)?)
+}
+
+/// Parse an equipment identifier with a trailing parenthesis. Most meters
+/// hex-encode it, but some send the serial number as is.
+fn parse_equipment_id(s: &str) -> Result<Text> {
+    let s = s.strip_suffix(')').ok_or(Error::InvalidFormat)?;
+    match Text::from_hex(s) {
+        Ok(text)
+            if text
+                .as_bytes()
+                .iter()
+                .all(|&b| b == b' ' || b.is_ascii_graphic()) =>
+        {
+            Ok(text)
+        }
+        _ => Ok(Text::new(s)),
+    }
+}
+
+impl Power {
+    /// The name in snake case, e.g. for field names.
+    pub(crate) const fn name(self) -> &'static str {
+        match self {
+            Power::Active => "active",
+            Power::Reactive => "reactive",
+            Power::Apparent => "apparent",
+        }
+    }
+
+    /// Unit of the energy registers.
+    fn energy_unit(&self) -> Unit {
+        match self {
+            Active => Unit::KWh,
+            Reactive => Unit::KVArh,
+            Apparent => Unit::KVAh,
+        }
+    }
+
+    /// Unit of the instantaneous power.
+    fn power_unit(&self) -> Unit {
+        match self {
+            Active => Unit::KW,
+            Reactive => Unit::KVAr,
+            Apparent => Unit::KVA,
+        }
+    }
+}
+
+impl Direction {
+    /// The name in snake case, e.g. for field names.
+    pub(crate) const fn name(self) -> &'static str {
+        match self {
+            Direction::FromGrid => "from_grid",
+            Direction::ToGrid => "to_grid",
+        }
+    }
+}
+
+impl Line {
+    fn from_offset(offset: u8) -> Option<Self> {
+        match offset {
+            20 => Some(Line::L1),
+            40 => Some(Line::L2),
+            60 => Some(Line::L3),
+            _ => None,
+        }
+    }
+
+    /// The offset of the line's OBIS C group (20, 40 or 60).
+    fn obis_offset(&self) -> u8 {
+        match self {
+            Line::L1 => 20,
+            Line::L2 => 40,
+            Line::L3 => 60,
+        }
+    }
+}
+
+/// Inverse of [`pow_dir`].
+fn pow_dir_code(pow: Power, dir: Direction) -> u8 {
+    match (pow, dir) {
+        (Active, FromGrid) => 1,
+        (Active, ToGrid) => 2,
+        (Reactive, FromGrid) => 3,
+        (Reactive, ToGrid) => 4,
+        (Apparent, FromGrid) => 9,
+        (Apparent, ToGrid) => 10,
+    }
+}
+
+/// Parse a unitless, possibly negative, power factor with three decimals
+/// into thousandths.
+fn parse_power_factor(s: &str) -> Result<i16> {
+    s.strip_suffix(')')
+        .and_then(parse_signed_decimal::<3>)
+        .filter(|v| (-1000..=1000).contains(v))
+        .and_then(|v| v.try_into().ok())
+        .ok_or(Error::InvalidFormat)
+}
+
+/// Determine if the power specified is active, reactive or apparent, as well
+/// as the [`Direction`].
+pub(crate) fn pow_dir(a: u8) -> Result<(Power, Direction)> {
     match a {
         1 => Ok((Active, FromGrid)),
         2 => Ok((Active, ToGrid)),
         3 => Ok((Reactive, FromGrid)),
         4 => Ok((Reactive, ToGrid)),
+        9 => Ok((Apparent, FromGrid)),
+        10 => Ok((Apparent, ToGrid)),
         _ => Err(Error::InvalidFormat),
     }
 }
 
+impl Object {
+    /// The [`Obis`] reference identifying this object.
+    ///
+    /// ```
+    /// use han::{Decivolts, Line, Obis, Object};
+    ///
+    /// assert_eq!(Object::Voltage(Line::L1, Decivolts(2338)).obis(), Obis(1, 0, 32, 7, 0));
+    /// ```
+    pub fn obis(&self) -> Obis {
+        match *self {
+            Object::DateTime(_) => Obis(0, 0, 1, 0, 0),
+            Object::Energy(dir, e) => Obis(1, 0, pow_dir_code(e.kind(), dir), 8, 0),
+            Object::TotalPower(dir, p) => Obis(1, 0, pow_dir_code(p.kind(), dir), 7, 0),
+            Object::Power(line, dir, p) => {
+                Obis(1, 0, line.obis_offset() + pow_dir_code(p.kind(), dir), 7, 0)
+            }
+            Object::Current(line, _) => Obis(1, 0, line.obis_offset() + 11, 7, 0),
+            Object::Voltage(line, _) => Obis(1, 0, line.obis_offset() + 12, 7, 0),
+            Object::LinePowerFactor(line, _) => Obis(1, 0, line.obis_offset() + 13, 7, 0),
+            Object::PowerFactor(_) => Obis(1, 0, 13, 7, 0),
+            Object::NeutralCurrent(_) => Obis(1, 0, 91, 7, 0),
+            Object::Volume(medium, _) => {
+                Obis(medium.code(), 0, medium.volume_c().unwrap_or(0), 0, 0)
+            }
+            Object::HeatEnergy(_) => Obis(6, 0, 1, 0, 0),
+            Object::Frequency(_) => Obis(1, 0, 14, 7, 0),
+            Object::Version(P1Version::Dsmr(_)) => Obis(1, 3, 0, 2, 8),
+            Object::Version(P1Version::Emucs(_)) => Obis(0, 0, 96, 1, 4),
+            Object::EnergyTariff(dir, tariff, e) => {
+                Obis(1, 0, pow_dir_code(e.kind(), dir), 8, tariff)
+            }
+            Object::EquipmentId(_) => Obis(0, 0, 96, 1, 1),
+            Object::TextMessage(_) => Obis(0, 0, 96, 13, 0),
+            Object::PowerFailures(_) => Obis(0, 0, 96, 7, 21),
+            Object::LongPowerFailures(_) => Obis(0, 0, 96, 7, 9),
+            Object::VoltageSags(line, _) => Obis(1, 0, line.obis_offset() + 12, 32, 0),
+            Object::VoltageSwells(line, _) => Obis(1, 0, line.obis_offset() + 12, 36, 0),
+            Object::AverageDemand(_) => Obis(1, 0, 1, 4, 0),
+            Object::MaxDemand(..) => Obis(1, 0, 1, 6, 0),
+            Object::MaxDemandHistory(_) => Obis(0, 0, 98, 1, 0),
+            Object::PowerFailureLog(_) => Obis(1, 0, 99, 97, 0),
+            Object::SwitchPosition(_) => Obis(0, 0, 96, 3, 10),
+            Object::LimiterThreshold(_) => Obis(0, 0, 17, 0, 0),
+            Object::FuseThreshold(_) => Obis(1, 0, 31, 4, 0),
+            Object::TariffIndicator(_) => Obis(0, 0, 96, 14, 0),
+            Object::MBusDeviceType(channel, _) => Obis(0, channel, 24, 1, 0),
+            Object::MBusEquipmentId(channel, _) => Obis(0, channel, 96, 1, 0),
+            Object::ValvePosition(channel, _) => Obis(0, channel, 24, 4, 0),
+            Object::MBusReading { channel, .. } => Obis(0, channel, 24, 2, 1),
+        }
+    }
+
+    /// The value as an integer in the unit of the variant, e.g. decivolts,
+    /// or `None` for timestamps and text.
+    pub(crate) fn scaled_value(&self) -> Option<i64> {
+        let v: i64 = match *self {
+            Object::DateTime(_)
+            | Object::EquipmentId(_)
+            | Object::TextMessage(_)
+            | Object::MBusEquipmentId(..) => return None,
+            Object::Energy(_, e) | Object::EnergyTariff(_, _, e) => {
+                e.raw().try_into().unwrap_or(i64::MAX)
+            }
+            Object::TotalPower(_, p) | Object::Power(_, _, p) => p.raw().into(),
+            Object::Volume(_, v)
+            | Object::HeatEnergy(v)
+            | Object::PowerFailures(v)
+            | Object::LongPowerFailures(v)
+            | Object::VoltageSags(_, v)
+            | Object::VoltageSwells(_, v)
+            | Object::AverageDemand(Watts(v))
+            | Object::MaxDemand(_, Watts(v))
+            | Object::LimiterThreshold(Watts(v))
+            | Object::MBusReading { value: v, .. }
+            | Object::Version(P1Version::Emucs(v)) => v.into(),
+            Object::FuseThreshold(Deciamps(v))
+            | Object::TariffIndicator(v)
+            | Object::Frequency(v)
+            | Object::Voltage(_, Decivolts(v))
+            | Object::Current(_, Deciamps(v))
+            | Object::NeutralCurrent(Deciamps(v)) => v.into(),
+            Object::MaxDemandHistory(n)
+            | Object::PowerFailureLog(n)
+            | Object::MBusDeviceType(_, n)
+            | Object::Version(P1Version::Dsmr(n)) => n.into(),
+            Object::SwitchPosition(position) | Object::ValvePosition(_, position) => {
+                position.code().into()
+            }
+            Object::LinePowerFactor(_, v) | Object::PowerFactor(v) => v.into(),
+        };
+        Some(v)
+    }
+
+    /// Construct a numeric object from its [`Obis`] reference and a value of
+    /// `value × 10^scaler` in the base unit (Wh, varh, W, var, V, A, Hz, m³ or
+    /// GJ), as sent by meters pushing binary DLMS/COSEM data.
+    ///
+    /// The B group (channel) is ignored. Precision beyond that of the object
+    /// is truncated.
+    ///
+    /// ```
+    /// use han::{Decivolts, Line, Obis, Object};
+    ///
+    /// let obj = Object::from_scaled(Obis(1, 0, 32, 7, 0), 2301, -1)?;
+    /// assert_eq!(obj, Object::Voltage(Line::L1, Decivolts(2301)));
+    /// # Ok::<(), han::Error>(())
+    /// ```
+    pub fn from_scaled(obis: Obis, value: i64, scaler: i8) -> Result<Self> {
+        let scaled = |target: i8| rescale(value, scaler, target).ok_or(Error::InvalidFormat);
+        let u64 = |target| scaled(target)?.try_into().map_err(|_| Error::InvalidFormat);
+        let u32 = |target| scaled(target)?.try_into().map_err(|_| Error::InvalidFormat);
+        let u16 = |target| scaled(target)?.try_into().map_err(|_| Error::InvalidFormat);
+        let power_factor = || match scaled(-3)? {
+            v @ -1000..=1000 => Ok(v as i16),
+            _ => Err(Error::InvalidFormat),
+        };
+
+        match obis {
+            Obis(1, _, c @ (1..=4 | 9..=10), d @ 7..=8, 0) => {
+                let (pow, dir) = pow_dir(c)?;
+                match d {
+                    7 => Ok(Object::TotalPower(dir, PowerQuantity::new(pow, u32(0)?))),
+                    8 => Ok(Object::Energy(dir, EnergyQuantity::new(pow, u64(0)?))),
+                    _ => unreachable!(),
+                }
+            }
+            Obis(1, _, c @ 1..=4, 8, tariff @ 1..) => {
+                let (pow, dir) = pow_dir(c)?;
+                Ok(Object::EnergyTariff(
+                    dir,
+                    tariff,
+                    EnergyQuantity::new(pow, u64(0)?),
+                ))
+            }
+            Obis(1, _, c @ (21..=24 | 29..=30 | 41..=44 | 49..=50 | 61..=64 | 69..=70), 7, 0) => {
+                let line = Line::from_offset(c - c % 20).ok_or(Error::InvalidFormat)?;
+                let (pow, dir) = pow_dir(c % 20)?;
+                Ok(Object::Power(line, dir, PowerQuantity::new(pow, u32(0)?)))
+            }
+            Obis(1, _, c @ 31..=33 | c @ 51..=53 | c @ 71..=73, 7, 0) => {
+                let line = Line::from_offset(c - c % 10 - 10).ok_or(Error::InvalidFormat)?;
+
+                match c % 10 {
+                    1 => Ok(Object::Current(line, Deciamps(u16(-1)?))),
+                    2 => Ok(Object::Voltage(line, Decivolts(u16(-1)?))),
+                    3 => Ok(Object::LinePowerFactor(line, power_factor()?)),
+                    _ => unreachable!(),
+                }
+            }
+            Obis(6, 0, 1, 0, 0) => Ok(Object::HeatEnergy(u32(-3)?)),
+            Obis(1, _, 14, 7, 0) => Ok(Object::Frequency(u16(-2)?)),
+            Obis(1, _, 13, 7, 0) => Ok(Object::PowerFactor(power_factor()?)),
+            Obis(1, _, 91, 7, 0) => Ok(Object::NeutralCurrent(Deciamps(u16(-1)?))),
+            Obis(1, _, 1, 4, 0) => Ok(Object::AverageDemand(Watts(u32(0)?))),
+            Obis(a @ 6..=9, 0, c, 0, 0) => match Medium::from_code(a) {
+                Some(medium) if medium.volume_c() == Some(c) => {
+                    Ok(Object::Volume(medium, u32(-3)?))
+                }
+                _ => Err(Error::UnrecognizedReference),
+            },
+            _ => Err(Error::UnrecognizedReference),
+        }
+    }
+}
+
+/// Convert `value × 10^scaler` to a multiple of `10^target`, truncating.
+fn rescale(value: i64, scaler: i8, target: i8) -> Option<i64> {
+    let diff = i32::from(scaler) - i32::from(target);
+    let factor = 10i64.checked_pow(diff.unsigned_abs())?;
+
+    if diff >= 0 {
+        value.checked_mul(factor)
+    } else {
+        Some(value / factor)
+    }
+}
+
+/// Options for parsing [`Object`]s, to work around quirks of some meters.
+///
+/// ```
+/// # #[cfg(feature = "time")] {
+/// use han::{Object, ParseOptions};
+/// use time::macros::{datetime, offset};
+///
+/// let options = ParseOptions::new().default_offset(offset!(+1));
+/// let obj = Object::parse_with("0-0:1.0.0(220717231648)", &options)?;
+/// assert_eq!(obj, Object::DateTime(datetime!(2022-07-17 23:16:48 +1)));
+/// # }
+/// # Ok::<(), han::Error>(())
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ParseOptions {
+    check_units: bool,
+    #[cfg(feature = "time")]
+    standard_offset: UtcOffset,
+    #[cfg(feature = "time")]
+    dst_offset: UtcOffset,
+    #[cfg(feature = "time")]
+    default_offset: Option<UtcOffset>,
+}
+
+impl ParseOptions {
+    /// The strict options used by [`str::parse`].
+    pub const fn new() -> Self {
+        Self {
+            check_units: true,
+            #[cfg(feature = "time")]
+            standard_offset: CET,
+            #[cfg(feature = "time")]
+            dst_offset: CEST,
+            #[cfg(feature = "time")]
+            default_offset: None,
+        }
+    }
+
+    /// Interpret timestamps marked with `W` (winter) as being at `standard`
+    /// and those marked with `S` (summer) as being at `dst`, rather than at
+    /// CET and CEST, for meters outside Central Europe.
+    ///
+    /// ```
+    /// use han::{Object, ParseOptions};
+    /// use time::macros::{datetime, offset};
+    ///
+    /// // Eastern European Time
+    /// let options = ParseOptions::new().offsets(offset!(+2), offset!(+3));
+    /// let obj = Object::parse_with("0-0:1.0.0(220717231648S)", &options)?;
+    /// assert_eq!(obj, Object::DateTime(datetime!(2022-07-17 23:16:48 +3)));
+    /// # Ok::<(), han::Error>(())
+    /// ```
+    #[cfg(feature = "time")]
+    pub const fn offsets(mut self, standard: UtcOffset, dst: UtcOffset) -> Self {
+        self.standard_offset = standard;
+        self.dst_offset = dst;
+        self
+    }
+
+    /// Don't check that the units are the expected ones.
+    pub const fn ignore_units(mut self) -> Self {
+        self.check_units = false;
+        self
+    }
+
+    /// Accept timestamps without a DST indicator (`W` or `S`), as sent by
+    /// some Norwegian and older meters, assuming that they are at `offset`.
+    #[cfg(feature = "time")]
+    pub const fn default_offset(mut self, offset: UtcOffset) -> Self {
+        self.default_offset = Some(offset);
+        self
+    }
+}
+
+impl Default for ParseOptions {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl FromStr for Object {
     type Err = Error;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse_with(s, &ParseOptions::new())
+    }
+}
+
+impl Object {
+    /// Like [`str::parse`], but without checking that the units are the
+    /// expected ones, for meters that get them wrong.
+    ///
+    /// ```
+    /// use han::{Decivolts, Error, Line, Object};
+    ///
+    /// let line = "1-0:32.7.0(230.1*A)";
+    /// assert!(matches!(line.parse::<Object>(), Err(Error::UnitMismatch)));
+    /// assert_eq!(
+    ///     Object::parse_ignoring_units(line)?,
+    ///     Object::Voltage(Line::L1, Decivolts(2301)),
+    /// );
+    /// # Ok::<(), han::Error>(())
+    /// ```
+    pub fn parse_ignoring_units(s: &str) -> Result<Self> {
+        Self::parse_with(s, &ParseOptions::new().ignore_units())
+    }
+
+    /// Like [`str::parse`], but with [`ParseOptions`].
+    pub fn parse_with(s: &str, options: &ParseOptions) -> Result<Self> {
+        let check_units = options.check_units;
         let (obis, body) = s.split_once('(').ok_or(Error::InvalidFormat)?;
-        let obis: Obis = obis.parse()?;
+        // all values, e.g. `(101209112500W)(12785.123*m3)`
+        let values = &s[obis.len()..];
+        let obis: ObisFull = obis.parse()?;
+        if !obis.is_current() {
+            return Err(Error::UnrecognizedReference);
+        }
+        let obis = obis.obis;
 
         match obis {
-            Obis(0, 0, 1, 0, 0) => Ok(Object::DateTime(parse_datetime(body)?)),
-            Obis(1, 0, c @ 1..=4, d @ 7..=8, 0) => {
+            Obis(0, 0, 1, 0, 0) => {
+                let body = body.strip_suffix(')').ok_or(Error::InvalidFormat)?;
+                Ok(Object::DateTime(parse_datetime_with(body, options)?))
+            }
+            Obis(1, 0, c @ (1..=4 | 9..=10), d @ 7..=8, 0) => {
                 let (pow, dir) = pow_dir(c)?;
                 match d {
-                    7 => Ok(Object::TotalPower(pow, dir, parse_kilo(body)?)),
-                    8 => Ok(Object::Energy(pow, dir, parse_kilo(body)?)),
+                    7 => {
+                        let v = parse_kilo(body, pow.power_unit(), check_units)?;
+                        Ok(Object::TotalPower(dir, PowerQuantity::new(pow, v)))
+                    }
+                    8 => {
+                        let v = parse_energy(body, pow.energy_unit(), check_units)?;
+                        Ok(Object::Energy(dir, EnergyQuantity::new(pow, v)))
+                    }
                     _ => unreachable!(),
                 }
             }
-            Obis(1, 0, c @ 21..=24 | c @ 41..=44 | c @ 61..=64, 7, 0) => {
+            Obis(1, 0, c @ (21..=24 | 29..=30 | 41..=44 | 49..=50 | 61..=64 | 69..=70), 7, 0) => {
                 let line = match c / 20 {
                     1 => Line::L1,
                     2 => Line::L2,
@@ -171,72 +981,420 @@ impl FromStr for Object {
                     _ => unreachable!(),
                 };
                 let (pow, dir) = pow_dir(c % 20)?;
-                Ok(Object::Power(line, pow, dir, parse_kilo(body)?))
+                let v = parse_kilo(body, pow.power_unit(), check_units)?;
+                Ok(Object::Power(line, dir, PowerQuantity::new(pow, v)))
             }
-            Obis(1, 0, c @ 31..=32 | c @ 51..=52 | c @ 71..=72, 7, 0) => {
+            Obis(1, 0, c @ 31..=33 | c @ 51..=53 | c @ 71..=73, 7, 0) => {
                 let line = match c {
-                    31..=32 => Line::L1,
-                    51..=52 => Line::L2,
-                    71..=72 => Line::L3,
+                    31..=33 => Line::L1,
+                    51..=53 => Line::L2,
+                    71..=73 => Line::L3,
                     _ => unreachable!(),
                 };
 
                 match c % 10 {
-                    1 => Ok(Object::Current(line, parse_deci(body)?)),
-                    2 => Ok(Object::Voltage(line, parse_deci(body)?)),
+                    1 => Ok(Object::Current(
+                        line,
+                        Deciamps(parse_deci(body, Unit::A, check_units)?),
+                    )),
+                    2 => Ok(Object::Voltage(
+                        line,
+                        Decivolts(parse_deci(body, Unit::V, check_units)?),
+                    )),
+                    3 => Ok(Object::LinePowerFactor(line, parse_power_factor(body)?)),
+                    _ => unreachable!(),
+                }
+            }
+            Obis(6, 0, 1, 0, 0) => Ok(Object::HeatEnergy(parse_kilo(body, Unit::GJ, check_units)?)),
+            Obis(1, 0, 13, 7, 0) => Ok(Object::PowerFactor(parse_power_factor(body)?)),
+            Obis(1, 0, 91, 7, 0) => Ok(Object::NeutralCurrent(Deciamps(parse_deci(
+                body,
+                Unit::A,
+                check_units,
+            )?))),
+            Obis(1, 0, 14, 7, 0) => {
+                // usually with two decimals, but sometimes with one
+                let decimal = split_value(body, Unit::Hz, check_units)?;
+                let v = parse_decimal::<2>(decimal)
+                    .or_else(|| parse_decimal::<1>(decimal).and_then(|v| v.checked_mul(10)));
+                let v = v.and_then(|v| v.try_into().ok());
+                Ok(Object::Frequency(v.ok_or(Error::InvalidFormat)?))
+            }
+            Obis(a @ 6..=9, 0, c, 0, 0) => match Medium::from_code(a) {
+                Some(medium) if medium.volume_c() == Some(c) => Ok(Object::Volume(
+                    medium,
+                    parse_kilo(body, Unit::M3, check_units)?,
+                )),
+                _ => Err(Error::UnrecognizedReference),
+            },
+            Obis(1, 3, 0, 2, 8) => Ok(Object::Version(P1Version::Dsmr(parse_integer(body)?))),
+            Obis(0, 0, 96, 1, 4) => Ok(Object::Version(P1Version::Emucs(parse_integer(body)?))),
+            Obis(1, 0, c @ 1..=4, 8, tariff @ 1..) => {
+                let (pow, dir) = pow_dir(c)?;
+                let v = parse_energy(body, pow.energy_unit(), check_units)?;
+                Ok(Object::EnergyTariff(
+                    dir,
+                    tariff,
+                    EnergyQuantity::new(pow, v),
+                ))
+            }
+            Obis(0, 0, 96, 1, 0 | 1) => Ok(Object::EquipmentId(parse_equipment_id(body)?)),
+            Obis(0, 0, 96, 13, 0) => Ok(Object::TextMessage(parse_text(body)?)),
+            Obis(0, 0, 96, 7, 21) => Ok(Object::PowerFailures(parse_integer(body)?)),
+            Obis(0, 0, 96, 7, 9) => Ok(Object::LongPowerFailures(parse_integer(body)?)),
+            Obis(1, 0, c @ (32 | 52 | 72), d @ (32 | 36), 0) => {
+                let line = Line::from_offset(c - 12).ok_or(Error::InvalidFormat)?;
+                let n = parse_integer(body)?;
+                match d {
+                    32 => Ok(Object::VoltageSags(line, n)),
+                    36 => Ok(Object::VoltageSwells(line, n)),
                     _ => unreachable!(),
                 }
             }
+            Obis(1, 0, 1, 4, 0) => Ok(Object::AverageDemand(Watts(parse_kilo(
+                body,
+                Unit::KW,
+                check_units,
+            )?))),
+            Obis(1, 0, 1, 6, 0) => {
+                let (dt, v) = timestamped(values, options)?;
+                let v = parse_decimal::<3>(strip_unit(v, Unit::KW, check_units)?)
+                    .ok_or(Error::InvalidFormat)?;
+                Ok(Object::MaxDemand(dt, Watts(v)))
+            }
+            // the months are left to `DemandHistory`
+            Obis(0, 0, 98, 1, 0) => Ok(Object::MaxDemandHistory(parse_count(body)?)),
+            // the events are left to `PowerFailureLog`
+            Obis(1, 0, 99, 97, 0) => Ok(Object::PowerFailureLog(parse_count(body)?)),
+            Obis(0, 0, 96, 3, 10) => {
+                let position = SwitchPosition::from_code(parse_integer(body)?);
+                Ok(Object::SwitchPosition(
+                    position.ok_or(Error::InvalidFormat)?,
+                ))
+            }
+            Obis(0, 0, 17, 0, 0) => {
+                let decimal = split_value(body, Unit::KW, check_units)?;
+                let v = parse_decimal::<1>(decimal).and_then(|v| v.checked_mul(100));
+                Ok(Object::LimiterThreshold(Watts(
+                    v.ok_or(Error::InvalidFormat)?,
+                )))
+            }
+            Obis(1, 0, 31, 4, 0) => Ok(Object::FuseThreshold(Deciamps(parse_deci(
+                body,
+                Unit::A,
+                check_units,
+            )?))),
+            Obis(0, 0, 96, 14, 0) => Ok(Object::TariffIndicator(parse_integer(body)?)),
+            Obis(0, channel @ 1..=4, 24, 1, 0) => {
+                Ok(Object::MBusDeviceType(channel, parse_integer(body)?))
+            }
+            Obis(0, channel @ 1..=4, 96, 1, 0) => {
+                Ok(Object::MBusEquipmentId(channel, parse_equipment_id(body)?))
+            }
+            Obis(0, channel @ 1..=4, 24, 4, 0) => {
+                let position = SwitchPosition::from_code(parse_integer(body)?);
+                let position = position.ok_or(Error::InvalidFormat)?;
+                Ok(Object::ValvePosition(channel, position))
+            }
+            Obis(0, channel @ 1..=4, 24, 2, 1) => {
+                let (timestamp, v) = timestamped(values, options)?;
+                let (decimal, unit) = v.split_once('*').ok_or(Error::InvalidFormat)?;
+                Ok(Object::MBusReading {
+                    channel,
+                    timestamp,
+                    value: parse_decimal::<3>(decimal).ok_or(Error::InvalidFormat)?,
+                    unit: unit.parse()?,
+                })
+            }
             _ => Err(Error::UnrecognizedReference),
         }
     }
 }
 
-fn parse_datetime(s: &str) -> Result<OffsetDateTime> {
-    let parsetwo = |i| {
-        s.get(i..=(i + 1))
-            .and_then(|s| s.parse::<u8>().ok())
-            .ok_or(Error::InvalidFormat)
-    };
+/// Split the values of an object with a timestamp and a single value, e.g.
+/// `(101209112500W)(12785.123*m3)`.
+fn timestamped<'a>(values: &'a str, options: &ParseOptions) -> Result<(Timestamp, &'a str)> {
+    let mut values = split_values(values).ok_or(Error::InvalidFormat)?;
+    match (values.next(), values.next(), values.next()) {
+        (Some(dt), Some(v), None) => Ok((parse_datetime_with(dt, options)?, v)),
+        _ => Err(Error::InvalidFormat),
+    }
+}
 
-    let year: i32 = i32::from(parsetwo(0)?) + 2000;
-    let month = match s.get(2..4).ok_or(Error::InvalidFormat)? {
-        "01" => Month::January,
-        "02" => Month::February,
-        "03" => Month::March,
-        "04" => Month::April,
-        "05" => Month::May,
-        "06" => Month::June,
-        "07" => Month::July,
-        "08" => Month::August,
-        "09" => Month::September,
-        "10" => Month::October,
-        "11" => Month::November,
-        "12" => Month::December,
-        _ => return Err(Error::InvalidFormat),
+/// Write `v` as a decimal with `F` fractional digits and the integer part
+/// zero-padded to `width` digits.
+fn write_decimal<const F: u8>(
+    f: &mut core::fmt::Formatter<'_>,
+    v: u64,
+    width: usize,
+) -> core::fmt::Result {
+    let scale = 10u64.pow(F.into());
+    write!(
+        f,
+        "{:0width$}.{:0F$}",
+        v / scale,
+        v % scale,
+        F = usize::from(F)
+    )
+}
+
+impl Display for Object {
+    /// Format the object as a line of a telegram, zero-padded the way meters
+    /// usually do.
+    ///
+    /// ```
+    /// use han::{Direction, Object, WattHours};
+    ///
+    /// let obj = Object::Energy(Direction::FromGrid, WattHours(6136930).into());
+    /// assert_eq!(obj.to_string(), "1-0:1.8.0(00006136.930*kWh)");
+    /// ```
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{}(", self.obis())?;
+
+        match *self {
+            Object::DateTime(dt) => write_datetime(f, dt)?,
+            Object::Energy(_, e) => {
+                write_decimal::<3>(f, e.raw(), 8)?;
+                write!(f, "*{}", e.kind().energy_unit())?;
+            }
+            Object::TotalPower(_, p) | Object::Power(_, _, p) => {
+                write_decimal::<3>(f, p.raw().into(), 4)?;
+                write!(f, "*{}", p.kind().power_unit())?;
+            }
+            Object::Voltage(_, Decivolts(v)) => {
+                write_decimal::<1>(f, v.into(), 3)?;
+                write!(f, "*{}", Unit::V)?;
+            }
+            Object::Current(_, Deciamps(v)) | Object::NeutralCurrent(Deciamps(v)) => {
+                write_decimal::<1>(f, v.into(), 3)?;
+                write!(f, "*{}", Unit::A)?;
+            }
+            Object::LinePowerFactor(_, v) | Object::PowerFactor(v) => {
+                if v < 0 {
+                    f.write_str("-")?;
+                }
+                write_decimal::<3>(f, v.unsigned_abs().into(), 1)?;
+            }
+            Object::Volume(_, v) => {
+                write_decimal::<3>(f, v.into(), 8)?;
+                write!(f, "*{}", Unit::M3)?;
+            }
+            Object::HeatEnergy(v) => {
+                write_decimal::<3>(f, v.into(), 8)?;
+                write!(f, "*{}", Unit::GJ)?;
+            }
+            Object::Frequency(v) => {
+                write_decimal::<2>(f, v.into(), 2)?;
+                write!(f, "*{}", Unit::Hz)?;
+            }
+            Object::Version(P1Version::Dsmr(v)) => write!(f, "{:02}", v)?,
+            Object::Version(P1Version::Emucs(v)) => write!(f, "{:05}", v)?,
+            Object::EnergyTariff(_, _, e) => {
+                write_decimal::<3>(f, e.raw(), 6)?;
+                write!(f, "*{}", e.kind().energy_unit())?;
+            }
+            Object::EquipmentId(text)
+            | Object::TextMessage(text)
+            | Object::MBusEquipmentId(_, text) => text.write_hex(f)?,
+            Object::PowerFailures(v)
+            | Object::LongPowerFailures(v)
+            | Object::VoltageSags(_, v)
+            | Object::VoltageSwells(_, v) => write!(f, "{:05}", v)?,
+            Object::AverageDemand(Watts(v)) => {
+                write_decimal::<3>(f, v.into(), 2)?;
+                write!(f, "*{}", Unit::KW)?;
+            }
+            Object::MaxDemand(dt, Watts(v)) => {
+                write_datetime(f, dt)?;
+                f.write_str(")(")?;
+                write_decimal::<3>(f, v.into(), 2)?;
+                write!(f, "*{}", Unit::KW)?;
+            }
+            Object::MaxDemandHistory(n) | Object::PowerFailureLog(n) => write!(f, "{}", n)?,
+            Object::SwitchPosition(position) | Object::ValvePosition(_, position) => {
+                write!(f, "{}", position.code())?
+            }
+            Object::LimiterThreshold(Watts(v)) => {
+                write_decimal::<1>(f, (v / 100).into(), 3)?;
+                write!(f, "*{}", Unit::KW)?;
+            }
+            Object::FuseThreshold(Deciamps(v)) => {
+                write_decimal::<1>(f, v.into(), 3)?;
+                write!(f, "*{}", Unit::A)?;
+            }
+            Object::TariffIndicator(v) => write!(f, "{:04}", v)?,
+            Object::MBusDeviceType(_, v) => write!(f, "{:03}", v)?,
+            Object::MBusReading {
+                timestamp,
+                value,
+                unit,
+                ..
+            } => {
+                write_datetime(f, timestamp)?;
+                f.write_str(")(")?;
+                write_decimal::<3>(f, value.into(), 5)?;
+                write!(f, "*{}", unit)?;
+            }
+        }
+
+        f.write_str(")")
+    }
+}
+
+/// Write a timestamp in the `YYMMDDhhmmssX` form parsed by [`parse_datetime`].
+#[cfg(feature = "time")]
+fn write_datetime(f: &mut core::fmt::Formatter<'_>, dt: Timestamp) -> core::fmt::Result {
+    let (dt, dst) = if dt.offset() == CEST {
+        (dt, true)
+    } else {
+        (dt.to_offset(CET), false)
     };
-    let day = parsetwo(4)?;
-    let date = Date::from_calendar_date(year, month, day).map_err(|_| Error::InvalidFormat)?;
-    let time = Time::from_hms(parsetwo(6)?, parsetwo(8)?, parsetwo(10)?)
-        .map_err(|_| Error::InvalidFormat)?;
-
-    let offset = match s.get(12..=12) {
-        Some("W") => UtcOffset::from_hms(1, 0, 0).unwrap(),
-        Some("S") => UtcOffset::from_hms(2, 0, 0).unwrap(),
-        _ => return Err(Error::InvalidFormat),
+    let raw = RawTimestamp {
+        year: 2000 + dt.year().rem_euclid(100) as u16,
+        month: dt.month().into(),
+        day: dt.day(),
+        hour: dt.hour(),
+        min: dt.minute(),
+        sec: dt.second(),
+        dst: Some(dst),
+    };
+    write!(f, "{}", raw)
+}
+
+/// Write a timestamp in the `YYMMDDhhmmssX` form parsed by [`parse_datetime`].
+#[cfg(not(feature = "time"))]
+fn write_datetime(f: &mut core::fmt::Formatter<'_>, dt: Timestamp) -> core::fmt::Result {
+    write!(f, "{}", dt)
+}
+
+/// Central European Time, indicated by a trailing `W` (winter).
+#[cfg(feature = "time")]
+pub(crate) const CET: UtcOffset = offset!(+1);
+/// Central European Summer Time, indicated by a trailing `S` (summer).
+#[cfg(feature = "time")]
+pub(crate) const CEST: UtcOffset = offset!(+2);
+
+/// Parse a `YYMMDDhhmmssX` timestamp, where `X` is the DST indicator.
+pub(crate) fn parse_datetime(s: &str) -> Result<Timestamp> {
+    parse_datetime_with(s, &ParseOptions::new())
+}
+
+/// Like [`parse_datetime`], but the DST indicator may be left out if
+/// [`ParseOptions::default_offset`] is set.
+///
+/// Without the `time` feature, the DST indicator is always optional.
+#[cfg(feature = "time")]
+fn parse_datetime_with(s: &str, options: &ParseOptions) -> Result<Timestamp> {
+    let raw = s.parse::<RawTimestamp>()?;
+    let offset = match (raw.dst, options.default_offset) {
+        (Some(false), _) => options.standard_offset,
+        (Some(true), _) => options.dst_offset,
+        (None, Some(offset)) => offset,
+        (None, None) => return Err(Error::InvalidFormat),
     };
+    raw.assume_offset(offset)
+}
 
-    Ok(PrimitiveDateTime::new(date, time).assume_offset(offset))
+/// Like [`parse_datetime`], but the DST indicator may be left out.
+#[cfg(not(feature = "time"))]
+fn parse_datetime_with(s: &str, _options: &ParseOptions) -> Result<Timestamp> {
+    s.parse::<RawTimestamp>()
 }
 
 #[cfg(test)]
 mod tests {
-    use time::macros::datetime;
+    use std::string::ToString;
+
+    #[cfg(feature = "time")]
+    use time::macros::{datetime, offset};
+
+    use crate::{
+        Deciamps, Decivolts, Error, Line, Text, VoltAmpereHours, VoltAmpereReactiveHours,
+        VoltAmperes, VoltAmperesReactive, WattHours, Watts,
+    };
+    #[cfg(feature = "time")]
+    use crate::{EnergyQuantity, PowerQuantity, Unit};
+
+    #[cfg(feature = "time")]
+    use super::{parse_datetime, parse_datetime_with, ParseOptions, Power, SwitchPosition};
+    use super::{
+        parse_decimal, parse_signed_decimal, Direction, LenientObject, Medium, Obis, ObisFull,
+        Object, P1Version,
+    };
+
+    #[test]
+    fn from_scaled() {
+        use super::rescale;
+
+        assert_eq!(rescale(1234, -2, -1), Some(123));
+        assert_eq!(rescale(1234, 1, 0), Some(12340));
+        assert_eq!(rescale(-5, 0, -3), Some(-5000));
+        assert_eq!(rescale(1, 30, 0), None);
 
-    use crate::Line;
+        let cases = [
+            (
+                Obis(1, 0, 1, 8, 0),
+                613693,
+                1,
+                Object::Energy(Direction::FromGrid, WattHours(6136930).into()),
+            ),
+            (
+                Obis(1, 1, 2, 7, 0),
+                1500,
+                0,
+                Object::TotalPower(Direction::ToGrid, Watts(1500).into()),
+            ),
+            (
+                Obis(1, 0, 43, 7, 0),
+                7,
+                0,
+                Object::Power(Line::L2, Direction::FromGrid, VoltAmperesReactive(7).into()),
+            ),
+            (
+                Obis(1, 0, 71, 7, 0),
+                1234,
+                -2,
+                Object::Current(Line::L3, Deciamps(123)),
+            ),
+            (
+                Obis(1, 1, 52, 7, 0),
+                230,
+                0,
+                Object::Voltage(Line::L2, Decivolts(2300)),
+            ),
+            (
+                Obis(1, 0, 33, 7, 0),
+                -98,
+                -2,
+                Object::LinePowerFactor(Line::L1, -980),
+            ),
+            (
+                Obis(7, 0, 3, 0, 0),
+                12345,
+                -2,
+                Object::Volume(Medium::Gas, 123450),
+            ),
+        ];
+
+        for (obis, value, scaler, obj) in cases {
+            assert_eq!(Object::from_scaled(obis, value, scaler).unwrap(), obj);
+        }
 
-    use super::{parse_datetime, Direction, Object, Power};
+        assert!(matches!(
+            Object::from_scaled(Obis(1, 0, 32, 7, 0), -1, 0),
+            Err(Error::InvalidFormat)
+        ));
+        assert!(matches!(
+            Object::from_scaled(Obis(1, 0, 33, 7, 0), 2, 0),
+            Err(Error::InvalidFormat)
+        ));
+        assert!(matches!(
+            Object::from_scaled(Obis(0, 0, 96, 1, 0), 1, 0),
+            Err(Error::UnrecognizedReference)
+        ));
+    }
 
+    #[cfg(feature = "time")]
     #[test]
     fn datetime_obj() {
         assert_eq!(
@@ -245,11 +1403,17 @@ mod tests {
         );
     }
 
+    #[cfg(feature = "time")]
     #[test]
     fn datetime_parsing() {
         assert!(parse_datetime("9999999999W").is_err());
         assert!(parse_datetime("aaaaaa").is_err());
         assert!(parse_datetime("220717231648").is_err()); // missing dst indicator
+        assert!(parse_datetime("22071723164W").is_err()); // 11 characters
+        assert!(parse_datetime("2207172316480W").is_err()); // 14 characters
+        assert!(parse_datetime("220717231648WW").is_err());
+        assert!("0-0:1.0.0(2207172316480W)".parse::<Object>().is_err());
+        assert!("0-0:1.0.0(220717231648W".parse::<Object>().is_err());
 
         // spec says dst shouldn't be used, but it never hurts to overdo timezones
         assert_eq!(
@@ -258,16 +1422,718 @@ mod tests {
         );
     }
 
+    #[cfg(feature = "time")]
+    #[test]
+    fn datetime_without_dst() {
+        let options = ParseOptions::new().default_offset(offset!(+1));
+        assert_eq!(
+            parse_datetime_with("220717231648", &options).unwrap(),
+            datetime!(2022-07-17 23:16:48 +1)
+        );
+        // the indicator still wins
+        assert_eq!(
+            parse_datetime_with("220717231648S", &options).unwrap(),
+            datetime!(2022-07-17 23:16:48 +2)
+        );
+        assert!(parse_datetime_with("220717231648X", &options).is_err());
+
+        let options = options.offsets(offset!(-5), offset!(-4));
+        assert_eq!(
+            parse_datetime_with("221022162844W", &options).unwrap(),
+            datetime!(2022-10-22 16:28:44 -5)
+        );
+        assert_eq!(
+            parse_datetime_with("220717231648S", &options).unwrap(),
+            datetime!(2022-07-17 23:16:48 -4)
+        );
+        assert_eq!(
+            Object::parse_with("1-0:1.6.0(230302183000)(04.329*kW)", &options).unwrap(),
+            Object::MaxDemand(datetime!(2023-03-02 18:30 +1), Watts(4329))
+        );
+    }
+
+    #[cfg(feature = "time")]
+    #[test]
+    fn datetime_offsets() {
+        assert_eq!(
+            parse_datetime("221022162844W").unwrap().offset(),
+            offset!(+01:00)
+        );
+        assert_eq!(
+            parse_datetime("220717231648S").unwrap().offset(),
+            offset!(+02:00)
+        );
+    }
+
     #[test]
     fn parse() {
         assert_eq!(
             "1-0:1.8.0(00006136.930*kWh)".parse::<Object>().unwrap(),
-            Object::Energy(Power::Active, Direction::FromGrid, 6136930)
+            Object::Energy(Direction::FromGrid, WattHours(6136930).into())
         );
 
         assert_eq!(
             "1-0:72.7.0(235.5*V)".parse::<Object>().unwrap(),
-            Object::Voltage(Line::L3, 2355)
+            Object::Voltage(Line::L3, Decivolts(2355))
+        );
+    }
+
+    #[test]
+    fn obis() {
+        for line in include_str!("../test/ell.txt").lines().skip(2) {
+            let Ok(obj) = line.parse::<Object>() else {
+                continue;
+            };
+            let (obis, _) = line.split_once('(').unwrap();
+            assert_eq!(obj.obis(), obis.parse().unwrap());
+        }
+    }
+
+    #[test]
+    fn obis_full() {
+        let full = |s: &str| s.parse::<ObisFull>().unwrap();
+        assert_eq!(full("1-0:1.8.0"), Obis(1, 0, 1, 8, 0).into());
+        assert_eq!(full("1-0:1.8.0*03").f, Some(3));
+        assert_eq!(full("1-0:1.8.0.255").f, Some(255));
+        assert!(full("1-0:1.8.0.255").is_current());
+        assert!(!full("1-0:1.8.0*03").is_current());
+        assert_eq!(full("1-0:1.8.0*3").to_string(), "1-0:1.8.0*03");
+        assert!("1-0:1.8.0*".parse::<ObisFull>().is_err());
+        assert!("1-0:1.8.0*256".parse::<ObisFull>().is_err());
+
+        // billing periods are recognized, but not supported
+        assert!(matches!(
+            "1-0:1.8.0*03(00006136.930*kWh)".parse::<Object>(),
+            Err(Error::UnrecognizedReference)
+        ));
+        assert_eq!(
+            "1-0:1.8.0.255(00006136.930*kWh)".parse::<Object>().unwrap(),
+            Object::Energy(Direction::FromGrid, WattHours(6136930).into())
+        );
+        assert_eq!(
+            LenientObject::parse("1-0:1.8.0*03(00006136.930*kWh)").unwrap(),
+            LenientObject::Other(full("1-0:1.8.0*03"), "(00006136.930*kWh)")
+        );
+    }
+
+    #[test]
+    fn line_power_factor() {
+        assert_eq!(
+            "1-0:33.7.0(0.998)".parse::<Object>().unwrap(),
+            Object::LinePowerFactor(Line::L1, 998)
+        );
+        assert_eq!(
+            "1-0:53.7.0(1.000)".parse::<Object>().unwrap(),
+            Object::LinePowerFactor(Line::L2, 1000)
+        );
+        assert_eq!(
+            "1-0:73.7.0(-0.870)".parse::<Object>().unwrap(),
+            Object::LinePowerFactor(Line::L3, -870)
+        );
+        assert_eq!(
+            Object::LinePowerFactor(Line::L3, -870).obis(),
+            Obis(1, 0, 73, 7, 0)
+        );
+
+        assert!("1-0:33.7.0(1.001)".parse::<Object>().is_err());
+        assert!("1-0:33.7.0(0.99)".parse::<Object>().is_err());
+        assert!("1-0:33.7.0(0.998".parse::<Object>().is_err());
+        assert!("1-0:33.7.0(+0.998)".parse::<Object>().is_err());
+    }
+
+    #[test]
+    fn medium() {
+        assert_eq!(
+            "8-0:1.0.0(00000123.456*m3)".parse::<Object>().unwrap(),
+            Object::Volume(Medium::Water, 123456)
+        );
+        assert_eq!(
+            "7-0:3.0.0(00001234.567*m3)".parse::<Object>().unwrap(),
+            Object::Volume(Medium::Gas, 1234567)
+        );
+        assert_eq!(
+            "6-0:1.0.0(00000012.345*GJ)".parse::<Object>().unwrap(),
+            Object::HeatEnergy(12345)
+        );
+        assert_eq!(
+            Object::Volume(Medium::Water, 123456).obis(),
+            Obis(8, 0, 1, 0, 0)
+        );
+
+        // electricity registers under another medium are not electricity
+        assert!(matches!(
+            "8-0:1.8.0(00006136.930*kWh)".parse::<Object>(),
+            Err(Error::UnrecognizedReference)
+        ));
+        assert!(matches!(
+            "8-0:3.0.0(00000123.456*m3)".parse::<Object>(),
+            Err(Error::UnrecognizedReference)
+        ));
+        assert!(matches!(
+            "8-0:1.0.0(00000123.456*kWh)".parse::<Object>(),
+            Err(Error::UnitMismatch)
+        ));
+    }
+
+    #[test]
+    fn large_energy() {
+        // beyond u32::MAX Wh
+        let obj = "1-0:1.8.0(04294967.296*kWh)".parse::<Object>().unwrap();
+        assert_eq!(
+            obj,
+            Object::Energy(Direction::FromGrid, WattHours(4294967296).into())
+        );
+        assert_eq!(obj.to_string(), "1-0:1.8.0(04294967.296*kWh)");
+
+        assert_eq!(
+            "1-0:2.8.2(123456789012.345*kWh)".parse::<Object>().unwrap(),
+            Object::EnergyTariff(Direction::ToGrid, 2, WattHours(123456789012345).into())
+        );
+        assert_eq!(
+            Object::from_scaled(Obis(1, 0, 1, 8, 0), 5_000_000, 3).unwrap(),
+            Object::Energy(Direction::FromGrid, WattHours(5_000_000_000).into())
+        );
+        assert!(matches!(
+            "1-0:1.8.0(18446744073709552.000*kWh)".parse::<Object>(),
+            Err(Error::InvalidFormat)
+        ));
+        // other values still fit in a u32
+        assert!(matches!(
+            "1-0:1.7.0(4294967.296*kW)".parse::<Object>(),
+            Err(Error::InvalidFormat)
+        ));
+    }
+
+    #[test]
+    fn decimals() {
+        assert_eq!(parse_decimal::<3>("0006136.930"), Some(6136930));
+        assert_eq!(parse_decimal::<3>("6136.93"), None);
+        assert_eq!(parse_decimal::<3>("-0.870"), None);
+        assert_eq!(parse_decimal::<3>("+0.870"), None);
+        assert_eq!(parse_decimal::<1>(".5"), None);
+        assert_eq!(parse_decimal::<1>("4294967295.9"), None); // overflow
+
+        assert_eq!(parse_signed_decimal::<3>("-0.870"), Some(-870));
+        assert_eq!(parse_signed_decimal::<1>("-1.5"), Some(-15));
+        assert_eq!(parse_signed_decimal::<1>("1.5"), Some(15));
+        assert_eq!(parse_signed_decimal::<1>("--1.5"), None);
+        assert_eq!(parse_signed_decimal::<1>("-1.50"), None);
+
+        assert!(matches!(
+            "1-0:1.8.0(-0006136.930*kWh)".parse::<Object>(),
+            Err(Error::InvalidFormat)
+        ));
+        assert!(matches!(
+            "1-0:32.7.0(-233.8*V)".parse::<Object>(),
+            Err(Error::InvalidFormat)
+        ));
+    }
+
+    #[test]
+    fn display() {
+        for line in include_str!("../test/ell.txt").lines().skip(2) {
+            if let Ok(obj) = line.parse::<Object>() {
+                assert_eq!(obj.to_string(), line);
+            }
+        }
+    }
+
+    #[cfg(feature = "time")]
+    #[test]
+    fn display_round_trip() {
+        let objects = [
+            Object::DateTime(datetime!(2022-10-22 15:28:44 UTC)),
+            Object::DateTime(datetime!(2022-07-17 23:16:48 +2)),
+            Object::Energy(Direction::ToGrid, VoltAmpereReactiveHours(1548596).into()),
+            Object::TotalPower(Direction::FromGrid, Watts(806).into()),
+            Object::Power(Line::L2, Direction::ToGrid, VoltAmperesReactive(12).into()),
+            Object::Voltage(Line::L1, Decivolts(2338)),
+            Object::Current(Line::L3, Deciamps(15)),
+            Object::LinePowerFactor(Line::L1, 998),
+            Object::LinePowerFactor(Line::L2, -870),
+            Object::LinePowerFactor(Line::L3, -1000),
+            Object::Volume(Medium::Gas, 1234567),
+            Object::HeatEnergy(12345),
+        ];
+
+        for obj in objects {
+            assert_eq!(obj.to_string().parse::<Object>().unwrap(), obj);
+        }
+
+        assert_eq!(
+            Object::DateTime(datetime!(2022-07-17 23:16:48 +2)).to_string(),
+            "0-0:1.0.0(220717231648S)"
+        );
+        assert_eq!(
+            Object::LinePowerFactor(Line::L2, -870).to_string(),
+            "1-0:53.7.0(-0.870)"
+        );
+    }
+
+    #[cfg(feature = "time")]
+    #[test]
+    fn dsmr5() {
+        let telegram = crate::Telegram::parse(include_str!("../test/dsmr5.txt")).unwrap();
+        let objects = telegram.objects().filter_map(Result::ok);
+        let mut n = 0;
+
+        for obj in objects {
+            n += 1;
+            match obj {
+                Object::Version(v) => assert_eq!(v, P1Version::Dsmr(50)),
+                Object::EnergyTariff(_, 1..=2, EnergyQuantity::Active(WattHours(v))) => {
+                    assert_eq!(v, 123456789)
+                }
+                Object::EquipmentId(id) => assert_eq!(id.as_str(), Some("K8EG004046395507")),
+                Object::TextMessage(text) => {
+                    assert!(text.is_truncated());
+                    assert!(text.as_str().unwrap().starts_with("0123456789:;<=>?"));
+                }
+                Object::PowerFailures(v) => assert_eq!(v, 4),
+                Object::LongPowerFailures(v) => assert_eq!(v, 2),
+                Object::TariffIndicator(v) => assert_eq!(v, 2),
+                Object::PowerFailureLog(n) => assert_eq!(n, 2),
+                Object::VoltageSags(Line::L1, n) => assert_eq!(n, 2),
+                Object::VoltageSwells(Line::L2, n) => assert_eq!(n, 3),
+                Object::MBusDeviceType(channel, v) => assert_eq!((channel, v), (1, 3)),
+                Object::MBusEquipmentId(channel, id) => {
+                    assert_eq!(channel, 1);
+                    assert_eq!(id.as_str(), Some("2222ABCD123456789"));
+                }
+                Object::MBusReading {
+                    channel,
+                    timestamp,
+                    value,
+                    unit,
+                } => {
+                    assert_eq!(channel, 1);
+                    assert_eq!(timestamp, datetime!(2010-12-09 11:25 +1));
+                    assert_eq!(value, 12785123);
+                    assert_eq!(unit, Unit::M3);
+                }
+                Object::Current(Line::L2, Deciamps(v)) => assert_eq!(v, 20),
+                _ => {}
+            }
+        }
+
+        assert_eq!(n, 35);
+    }
+
+    /// A xorshift generator of pseudo-random objects, to check that every
+    /// object survives formatting and parsing.
+    #[cfg(feature = "time")]
+    struct Arbitrary(u64);
+
+    #[cfg(feature = "time")]
+    impl Arbitrary {
+        fn next(&mut self) -> u64 {
+            self.0 ^= self.0 << 13;
+            self.0 ^= self.0 >> 7;
+            self.0 ^= self.0 << 17;
+            self.0
+        }
+
+        fn pick<T: Copy>(&mut self, items: &[T]) -> T {
+            items[self.next() as usize % items.len()]
+        }
+
+        fn u8(&mut self) -> u8 {
+            self.next() as u8
+        }
+
+        fn u16(&mut self) -> u16 {
+            self.next() as u16
+        }
+
+        fn u32(&mut self) -> u32 {
+            self.next() as u32
+        }
+
+        fn channel(&mut self) -> u8 {
+            self.pick(&[1, 2, 3, 4])
+        }
+
+        fn datetime(&mut self) -> time::OffsetDateTime {
+            // any second of 2000–2099, as years are written with two digits
+            let t = datetime!(2000-01-01 00:00 +1)
+                + time::Duration::seconds((self.next() % 3_155_673_600) as i64);
+            t.to_offset(self.pick(&[offset!(+1), offset!(+2)]))
+        }
+
+        fn text(&mut self) -> Text {
+            let mut hex = std::string::String::new();
+            for _ in 0..self.next() % 20 {
+                // printable, as equipment identifiers may not be hex-encoded
+                hex.push_str(&std::format!("{:02X}", 0x20 + self.next() % 95));
+            }
+            Text::from_hex(&hex).unwrap()
+        }
+
+        fn object(&mut self) -> Object {
+            let line = self.pick(&[Line::L1, Line::L2, Line::L3]);
+            let pow = self.pick(&[Power::Active, Power::Reactive, Power::Apparent]);
+            let dir = self.pick(&[Direction::FromGrid, Direction::ToGrid]);
+            let position = self.pick(&[
+                SwitchPosition::Off,
+                SwitchPosition::On,
+                SwitchPosition::Released,
+            ]);
+
+            match self.next() % 32 {
+                0 => Object::DateTime(self.datetime()),
+                1 => Object::Energy(dir, EnergyQuantity::new(pow, self.next() % 10u64.pow(12))),
+                2 => Object::TotalPower(dir, PowerQuantity::new(pow, self.u32())),
+                3 => Object::Power(line, dir, PowerQuantity::new(pow, self.u32())),
+                4 => Object::Voltage(line, Decivolts(self.u16())),
+                5 => Object::Current(line, Deciamps(self.u16())),
+                6 => Object::NeutralCurrent(Deciamps(self.u16())),
+                7 => Object::LinePowerFactor(line, (self.next() % 2001) as i16 - 1000),
+                8 => Object::PowerFactor((self.next() % 2001) as i16 - 1000),
+                9 => Object::Frequency(self.u16()),
+                10 => {
+                    let medium =
+                        self.pick(&[Medium::Heat, Medium::Gas, Medium::Water, Medium::HotWater]);
+                    Object::Volume(medium, self.u32())
+                }
+                11 => Object::HeatEnergy(self.u32()),
+                12 => Object::Version(P1Version::Dsmr(self.u8())),
+                13 => Object::Version(P1Version::Emucs(self.u32())),
+                14 => Object::EnergyTariff(
+                    dir,
+                    self.u8().max(1),
+                    EnergyQuantity::new(
+                        self.pick(&[Power::Active, Power::Reactive]),
+                        self.next() % 10u64.pow(12),
+                    ),
+                ),
+                15 => Object::EquipmentId(self.text()),
+                16 => Object::TextMessage(self.text()),
+                17 => Object::PowerFailures(self.u32()),
+                18 => Object::LongPowerFailures(self.u32()),
+                19 => Object::VoltageSags(line, self.u32()),
+                20 => Object::VoltageSwells(line, self.u32()),
+                21 => Object::AverageDemand(Watts(self.u32())),
+                22 => Object::MaxDemand(self.datetime(), Watts(self.u32())),
+                23 => Object::MaxDemandHistory(self.u8()),
+                24 => Object::PowerFailureLog(self.u8()),
+                25 => Object::SwitchPosition(position),
+                // written in tenths of kW
+                26 => Object::LimiterThreshold(Watts(self.u32() / 100 * 100)),
+                27 => Object::FuseThreshold(Deciamps(self.u16())),
+                28 => Object::TariffIndicator(self.u16()),
+                29 => Object::MBusDeviceType(self.channel(), self.u8()),
+                30 => Object::MBusEquipmentId(self.channel(), self.text()),
+                _ => match self.next() % 2 {
+                    0 => Object::ValvePosition(self.channel(), position),
+                    _ => Object::MBusReading {
+                        channel: self.channel(),
+                        timestamp: self.datetime(),
+                        value: self.u32(),
+                        unit: self.pick(&[Unit::M3, Unit::GJ, Unit::KWh]),
+                    },
+                },
+            }
+        }
+    }
+
+    #[cfg(feature = "time")]
+    #[test]
+    fn arbitrary_round_trip() {
+        let mut arbitrary = Arbitrary(0x9e37_79b9_7f4a_7c15);
+
+        for _ in 0..10_000 {
+            let obj = arbitrary.object();
+            let line = obj.to_string();
+            assert_eq!(line.parse::<Object>().unwrap(), obj, "{line}");
+        }
+    }
+
+    #[cfg(feature = "time")]
+    #[test]
+    fn dsmr5_round_trip() {
+        let objects = [
+            Object::Version(P1Version::Dsmr(42)),
+            Object::Version(P1Version::Emucs(50217)),
+            Object::EnergyTariff(Direction::ToGrid, 2, WattHours(1234).into()),
+            Object::EquipmentId(Text::from_hex("4B384547").unwrap()),
+            Object::TextMessage(Text::from_hex("").unwrap()),
+            Object::PowerFailures(4),
+            Object::LongPowerFailures(12345),
+            Object::VoltageSags(Line::L3, 7),
+            Object::VoltageSwells(Line::L1, 0),
+            Object::TariffIndicator(2),
+            Object::MBusDeviceType(2, 7),
+            Object::ValvePosition(1, SwitchPosition::Off),
+            Object::Frequency(4998),
+            Object::PowerFactor(-999),
+            Object::NeutralCurrent(Deciamps(12)),
+            Object::MBusEquipmentId(3, Text::from_hex("4B384547").unwrap()),
+            Object::MBusReading {
+                channel: 4,
+                timestamp: datetime!(2021-06-06 14:00:10 +2),
+                value: 1234567,
+                unit: Unit::M3,
+            },
+        ];
+
+        for obj in objects {
+            assert_eq!(obj.to_string().parse::<Object>().unwrap(), obj);
+        }
+    }
+
+    #[test]
+    fn tariff_energy() {
+        assert_eq!(
+            Object::EnergyTariff(Direction::FromGrid, 1, WattHours(123456789).into()).to_string(),
+            "1-0:1.8.1(123456.789*kWh)"
+        );
+        assert_eq!(
+            "1-0:4.8.3(000012.345*kvarh)".parse::<Object>().unwrap(),
+            Object::EnergyTariff(Direction::ToGrid, 3, VoltAmpereReactiveHours(12345).into())
+        );
+        assert_eq!(
+            Object::EnergyTariff(
+                Direction::FromGrid,
+                1,
+                VoltAmpereReactiveHours(12345).into()
+            )
+            .to_string(),
+            "1-0:3.8.1(000012.345*kvarh)"
+        );
+        assert_eq!(
+            Object::from_scaled(Obis(1, 0, 2, 8, 2), 1234, 1).unwrap(),
+            Object::EnergyTariff(Direction::ToGrid, 2, WattHours(12340).into())
+        );
+    }
+
+    #[test]
+    fn version() {
+        assert_eq!(
+            Object::Version(P1Version::Dsmr(50)).to_string(),
+            "1-3:0.2.8(50)"
+        );
+        assert!("1-3:0.2.8(500)".parse::<Object>().is_err());
+    }
+
+    #[test]
+    fn counters() {
+        assert_eq!(Object::PowerFailures(4).to_string(), "0-0:96.7.21(00004)");
+        assert_eq!(
+            Object::VoltageSwells(Line::L2, 3).to_string(),
+            "1-0:52.36.0(00003)"
+        );
+        assert_eq!(Object::TariffIndicator(2).to_string(), "0-0:96.14.0(0002)");
+        assert!("0-0:96.7.21(-1)".parse::<Object>().is_err());
+    }
+
+    #[test]
+    fn frequency() {
+        assert_eq!(Object::Frequency(5002).to_string(), "1-0:14.7.0(50.02*Hz)");
+        assert_eq!(
+            "1-0:14.7.0(49.9*Hz)".parse::<Object>().unwrap(),
+            Object::Frequency(4990)
+        );
+        assert_eq!(
+            Object::from_scaled(Obis(1, 0, 14, 7, 0), 500, -1).unwrap(),
+            Object::Frequency(5000)
+        );
+    }
+
+    #[test]
+    fn power_factor() {
+        assert_eq!(Object::PowerFactor(870).to_string(), "1-0:13.7.0(0.870)");
+        assert!("1-0:13.7.0(1.001)".parse::<Object>().is_err());
+        assert_eq!(
+            Object::from_scaled(Obis(1, 0, 13, 7, 0), -95, -2).unwrap(),
+            Object::PowerFactor(-950)
+        );
+    }
+
+    #[test]
+    fn neutral_current() {
+        assert_eq!(
+            Object::NeutralCurrent(Deciamps(12)).to_string(),
+            "1-0:91.7.0(001.2*A)"
+        );
+        assert_eq!(
+            Object::from_scaled(Obis(1, 0, 91, 7, 0), 1234, -3).unwrap(),
+            Object::NeutralCurrent(Deciamps(12))
+        );
+    }
+
+    #[test]
+    fn current_without_decimals() {
+        assert_eq!(
+            "1-0:31.7.0(001*A)".parse::<Object>().unwrap(),
+            Object::Current(Line::L1, Deciamps(10))
+        );
+        assert!("1-0:31.7.0(*A)".parse::<Object>().is_err());
+    }
+
+    #[test]
+    fn average_demand() {
+        assert_eq!(
+            Object::from_scaled(Obis(1, 0, 1, 4, 0), 2351, 0).unwrap(),
+            Object::AverageDemand(Watts(2351))
+        );
+    }
+
+    #[test]
+    fn apparent() {
+        assert_eq!(
+            "1-0:9.7.0(01.234*kVA)".parse::<Object>().unwrap(),
+            Object::TotalPower(Direction::FromGrid, VoltAmperes(1234).into())
+        );
+        assert_eq!(
+            "1-0:70.7.0(00.012*kVA)".parse::<Object>().unwrap(),
+            Object::Power(Line::L3, Direction::ToGrid, VoltAmperes(12).into())
+        );
+        assert_eq!(
+            Object::Energy(Direction::ToGrid, VoltAmpereHours(1234).into()).to_string(),
+            "1-0:10.8.0(00000001.234*kVAh)"
+        );
+        assert_eq!(
+            Object::from_scaled(Obis(1, 0, 49, 7, 0), 2631, 0).unwrap(),
+            Object::Power(Line::L2, Direction::FromGrid, VoltAmperes(2631).into())
+        );
+        assert!("1-0:9.7.0(01.234*kW)".parse::<Object>().is_err());
+    }
+
+    #[cfg(feature = "time")]
+    #[test]
+    fn mbus() {
+        assert_eq!(Object::MBusDeviceType(1, 3).to_string(), "0-1:24.1.0(003)");
+        assert_eq!(
+            Object::MBusReading {
+                channel: 1,
+                timestamp: datetime!(2021-06-06 14:00:10 +2),
+                value: 1234567,
+                unit: Unit::M3,
+            }
+            .to_string(),
+            "0-1:24.2.1(210606140010S)(01234.567*m3)"
+        );
+        assert!("0-5:24.2.1(210606140010S)(01234.567*m3)"
+            .parse::<Object>()
+            .is_err());
+    }
+
+    #[test]
+    fn equipment_id() {
+        assert_eq!(
+            "0-0:96.1.0(4B384547303034303436333935353037)"
+                .parse::<Object>()
+                .unwrap(),
+            Object::EquipmentId(Text::new("K8EG004046395507"))
+        );
+        // not hex-encoded
+        assert_eq!(
+            "0-0:96.1.0(7359992890941742)".parse::<Object>().unwrap(),
+            Object::EquipmentId(Text::new("7359992890941742"))
+        );
+        assert_eq!(
+            "0-0:96.1.1(4B3)".parse::<Object>().unwrap(),
+            Object::EquipmentId(Text::new("4B3"))
+        );
+    }
+
+    #[cfg(feature = "time")]
+    #[test]
+    fn emucs() {
+        let telegram = crate::Telegram::parse(include_str!("../test/emucs.txt")).unwrap();
+        let objects = telegram
+            .objects()
+            .filter_map(Result::ok)
+            .collect::<std::vec::Vec<_>>();
+
+        assert!(objects.contains(&Object::Version(P1Version::Emucs(50217))));
+        assert!(objects.contains(&Object::AverageDemand(Watts(2351))));
+        assert!(objects.contains(&Object::MaxDemand(
+            datetime!(2023-03-02 18:30 +1),
+            Watts(4329)
+        )));
+        assert!(objects.contains(&Object::MaxDemandHistory(3)));
+        assert!(objects.contains(&Object::SwitchPosition(SwitchPosition::On)));
+        assert!(objects.contains(&Object::LimiterThreshold(Watts(999900))));
+        assert!(objects.contains(&Object::FuseThreshold(Deciamps(9990))));
+        assert!(objects.contains(&Object::TariffIndicator(1)));
+        // currents with two decimals
+        assert!(objects.contains(&Object::Current(Line::L1, Deciamps(12))));
+        assert!(objects.contains(&Object::Current(Line::L2, Deciamps(16))));
+
+        let round_trip = [
+            Object::AverageDemand(Watts(2351)),
+            Object::MaxDemand(datetime!(2023-07-02 18:30 +2), Watts(4329)),
+            Object::MaxDemandHistory(13),
+            Object::PowerFailureLog(0),
+            Object::SwitchPosition(SwitchPosition::Released),
+            Object::LimiterThreshold(Watts(6900)),
+            Object::FuseThreshold(Deciamps(250)),
+        ];
+        for obj in round_trip {
+            assert_eq!(obj.to_string().parse::<Object>().unwrap(), obj);
+        }
+
+        assert_eq!(
+            Object::MaxDemand(datetime!(2023-03-02 18:30 +1), Watts(4329)).to_string(),
+            "1-0:1.6.0(230302183000W)(04.329*kW)"
+        );
+        assert!("0-0:96.3.10(3)".parse::<Object>().is_err());
+        assert_eq!(
+            "0-1:24.4.0(2)".parse::<Object>().unwrap(),
+            Object::ValvePosition(1, SwitchPosition::Released)
+        );
+        assert!("1-0:1.6.0(230302183000W)".parse::<Object>().is_err());
+        assert!("1-0:1.6.0(230302183000W)(04.329*kW)(1)"
+            .parse::<Object>()
+            .is_err());
+    }
+
+    #[test]
+    fn missing_parenthesis() {
+        assert!(matches!(
+            "1-0:72.7.0(235.5*V".parse::<Object>(),
+            Err(Error::InvalidFormat)
+        ));
+        assert!(matches!(
+            "1-0:1.8.0(00006136.930*kWh".parse::<Object>(),
+            Err(Error::InvalidFormat)
+        ));
+        assert!(matches!(
+            "1-0:72.7.0(235.5*V))".parse::<Object>(),
+            Err(Error::InvalidFormat)
+        ));
+    }
+
+    #[test]
+    fn unit_mismatch() {
+        assert!(matches!(
+            "1-0:72.7.0(235.5*A)".parse::<Object>(),
+            Err(Error::UnitMismatch)
+        ));
+        assert!(matches!(
+            "1-0:31.7.0(002.2*V)".parse::<Object>(),
+            Err(Error::UnitMismatch)
+        ));
+        assert!(matches!(
+            "1-0:1.8.0(00006136.930*kvarh)".parse::<Object>(),
+            Err(Error::UnitMismatch)
+        ));
+        assert!(matches!(
+            "1-0:4.7.0(0000.206*kW)".parse::<Object>(),
+            Err(Error::UnitMismatch)
+        ));
+        assert!(matches!(
+            "1-0:1.6.0(230302183000W)(04.329*kWh)".parse::<Object>(),
+            Err(Error::UnitMismatch)
+        ));
+        assert_eq!(
+            Object::parse_ignoring_units("1-0:1.8.0(00006136.930*V)").unwrap(),
+            Object::Energy(Direction::FromGrid, WattHours(6136930).into())
+        );
+        assert_eq!(
+            LenientObject::parse("1-0:31.7.0(002.2*V)").unwrap(),
+            LenientObject::Known(Object::Current(Line::L1, Deciamps(22)))
         );
     }
 }
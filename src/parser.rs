@@ -0,0 +1,400 @@
+use core::str::FromStr;
+
+use crate::{obis::Object, Error, Result};
+
+/// An event emitted by [`Parser::feed`].
+#[derive(Debug)]
+pub enum Event {
+    /// A `/` marking the start of a telegram was received.
+    TelegramStart,
+    /// A line of the telegram body was received. Objects are emitted before
+    /// the checksum has been verified, so they should be discarded if the
+    /// telegram ends with [`Event::ChecksumError`].
+    Object(Result<Object>),
+    /// The telegram ended with a valid checksum, which is included.
+    TelegramComplete(u16),
+    /// The telegram ended with a checksum that did not match its contents.
+    ChecksumError,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum State {
+    /// Waiting for the `/` starting the next telegram.
+    Idle,
+    /// Reading the header line.
+    Header,
+    /// Reading object lines.
+    Body,
+    /// Reading the four checksum characters after the `!`.
+    Checksum,
+}
+
+/// A push parser for the raw UART output of a power meter.
+///
+/// Unlike [`Reader`](crate::Reader), the parser does not own any I/O. Bytes
+/// are fed one at a time, e.g. from an interrupt handler, and [`Event`]s are
+/// emitted as soon as they can be determined. Only the current line is
+/// buffered, in `N` bytes; longer lines are reported as
+/// [`Error::InvalidFormat`].
+///
+/// ```
+/// use han::{Event, Parser};
+///
+/// let mut parser = Parser::new();
+/// let mut objects = 0;
+///
+/// for &b in include_bytes!("../test/ell.txt") {
+///     match parser.feed(b) {
+///         Some(Event::Object(obj)) => {
+///             obj?;
+///             objects += 1;
+///         }
+///         Some(Event::TelegramComplete(checksum)) => assert_eq!(checksum, 0x9ab5),
+///         _ => {}
+///     }
+/// }
+///
+/// assert_eq!(objects, 27);
+/// # Ok::<(), han::Error>(())
+/// ```
+#[derive(Debug)]
+pub struct Parser<const N: usize = 128> {
+    state: State,
+    line: [u8; N],
+    len: usize,
+    overflow: bool,
+    crc: crc16::State<crc16::ARC>,
+    checksum: [u8; 4],
+}
+
+impl Parser {
+    /// Construct a new parser.
+    pub fn new() -> Self {
+        Self::new_sized()
+    }
+}
+
+impl Default for Parser {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<const N: usize> Parser<N> {
+    /// Construct a new parser with a line buffer of `N` bytes.
+    pub fn new_sized() -> Self {
+        Self {
+            state: State::Idle,
+            line: [0; N],
+            len: 0,
+            overflow: false,
+            crc: crc16::State::new(),
+            checksum: [0; 4],
+        }
+    }
+
+    /// Discard the telegram currently being parsed, if any, and wait for the
+    /// start of the next one.
+    pub fn reset(&mut self) {
+        self.state = State::Idle;
+    }
+
+    /// Feed a single byte to the parser, returning an event if the byte
+    /// completed one.
+    pub fn feed(&mut self, b: u8) -> Option<Event> {
+        match self.state {
+            State::Idle => {
+                if b != b'/' {
+                    return None;
+                }
+
+                self.state = State::Header;
+                self.crc = crc16::State::new();
+                self.crc.update(&[b]);
+                Some(Event::TelegramStart)
+            }
+            State::Header => {
+                self.crc.update(&[b]);
+                if b == b'\n' {
+                    self.state = State::Body;
+                    self.clear_line();
+                }
+                None
+            }
+            State::Body => {
+                self.crc.update(&[b]);
+                match b {
+                    b'!' => {
+                        self.state = State::Checksum;
+                        self.len = 0;
+                        None
+                    }
+                    b'\n' => {
+                        let event = self.take_line();
+                        self.clear_line();
+                        event
+                    }
+                    _ => {
+                        self.extend_line(&[b]);
+                        None
+                    }
+                }
+            }
+            State::Checksum => {
+                self.checksum[self.len] = b;
+                self.len += 1;
+
+                if self.len < self.checksum.len() {
+                    return None;
+                }
+
+                self.state = State::Idle;
+                let checksum = core::str::from_utf8(&self.checksum)
+                    .ok()
+                    .and_then(|s| u16::from_str_radix(s, 16).ok());
+
+                match checksum {
+                    Some(checksum) if checksum == self.crc.get() => {
+                        Some(Event::TelegramComplete(checksum))
+                    }
+                    _ => Some(Event::ChecksumError),
+                }
+            }
+        }
+    }
+
+    /// Feed a chunk of bytes to the parser, such as a DMA buffer, returning
+    /// the events completed by them.
+    ///
+    /// The chunk is scanned for delimiters a line at a time rather than
+    /// byte by byte. If the iterator is dropped early, the rest of the chunk
+    /// is still fed and its events are discarded.
+    ///
+    /// ```
+    /// use han::{Event, Parser};
+    ///
+    /// let mut parser = Parser::new();
+    /// let mut objects = 0;
+    ///
+    /// for chunk in include_bytes!("../test/ell.txt").chunks(256) {
+    ///     for event in parser.feed_slice(chunk) {
+    ///         match event {
+    ///             Event::Object(obj) => {
+    ///                 obj?;
+    ///                 objects += 1;
+    ///             }
+    ///             Event::TelegramComplete(checksum) => assert_eq!(checksum, 0x9ab5),
+    ///             _ => {}
+    ///         }
+    ///     }
+    /// }
+    ///
+    /// assert_eq!(objects, 27);
+    /// # Ok::<(), han::Error>(())
+    /// ```
+    pub fn feed_slice<'a>(&'a mut self, chunk: &'a [u8]) -> FeedSlice<'a, N> {
+        FeedSlice {
+            parser: self,
+            chunk,
+        }
+    }
+
+    /// Feed bytes up to and including the next one that may complete an
+    /// event, returning the number of bytes consumed.
+    fn feed_span(&mut self, chunk: &[u8]) -> (usize, Option<Event>) {
+        let find = |chunk: &[u8], pred: fn(&u8) -> bool| chunk.iter().position(pred);
+        let end = match self.state {
+            State::Idle => find(chunk, |&b| b == b'/'),
+            State::Header => match find(chunk, |&b| b == b'\n') {
+                Some(i) => {
+                    self.crc.update(&chunk[..i]);
+                    Some(i)
+                }
+                None => {
+                    self.crc.update(chunk);
+                    None
+                }
+            },
+            State::Body => {
+                let i = find(chunk, |&b| matches!(b, b'\n' | b'!'));
+                let span = &chunk[..i.unwrap_or(chunk.len())];
+                self.crc.update(span);
+                self.extend_line(span);
+                i
+            }
+            State::Checksum => Some(0),
+        };
+
+        match end {
+            Some(i) => (i + 1, self.feed(chunk[i])),
+            None => (chunk.len(), None),
+        }
+    }
+
+    fn extend_line(&mut self, bytes: &[u8]) {
+        match self.line.get_mut(self.len..self.len + bytes.len()) {
+            Some(dst) => dst.copy_from_slice(bytes),
+            None => self.overflow = true,
+        }
+        self.len += bytes.len();
+    }
+
+    fn clear_line(&mut self) {
+        self.len = 0;
+        self.overflow = false;
+    }
+
+    /// Parse the buffered line, skipping blank lines.
+    fn take_line(&self) -> Option<Event> {
+        if self.overflow {
+            return Some(Event::Object(Err(Error::InvalidFormat)));
+        }
+
+        let line = &self.line[..self.len];
+        let line = line.strip_suffix(b"\r").unwrap_or(line);
+
+        if line.is_empty() {
+            return None;
+        }
+
+        let obj = core::str::from_utf8(line)
+            .map_err(|_| Error::InvalidFormat)
+            .and_then(Object::from_str);
+        Some(Event::Object(obj))
+    }
+}
+
+/// Iterator over the events of a chunk, returned by [`Parser::feed_slice`].
+#[derive(Debug)]
+pub struct FeedSlice<'a, const N: usize> {
+    parser: &'a mut Parser<N>,
+    chunk: &'a [u8],
+}
+
+impl<const N: usize> Iterator for FeedSlice<'_, N> {
+    type Item = Event;
+
+    fn next(&mut self) -> Option<Event> {
+        while !self.chunk.is_empty() {
+            let (n, event) = self.parser.feed_span(self.chunk);
+            self.chunk = &self.chunk[n..];
+            if event.is_some() {
+                return event;
+            }
+        }
+        None
+    }
+}
+
+impl<const N: usize> Drop for FeedSlice<'_, N> {
+    fn drop(&mut self) {
+        self.for_each(drop);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Error;
+
+    use super::{Event, Parser};
+
+    fn events<const N: usize>(
+        parser: &mut Parser<N>,
+        bytes: &[u8],
+    ) -> (usize, usize, Option<Event>) {
+        let (mut ok, mut err) = (0, 0);
+
+        for &b in bytes {
+            match parser.feed(b) {
+                Some(Event::TelegramStart) => (ok, err) = (0, 0),
+                Some(Event::Object(Ok(_))) => ok += 1,
+                Some(Event::Object(Err(_))) => err += 1,
+                Some(event) => return (ok, err, Some(event)),
+                None => {}
+            }
+        }
+
+        (ok, err, None)
+    }
+
+    #[test]
+    fn ellevio() {
+        let bytes = include_bytes!("../test/ell.txt");
+        let mut parser = Parser::new();
+
+        for _ in 0..2 {
+            let (ok, err, event) = events(&mut parser, bytes);
+            assert_eq!((ok, err), (27, 0));
+            assert!(matches!(event, Some(Event::TelegramComplete(0x9ab5))));
+        }
+    }
+
+    #[test]
+    fn resync() {
+        let bytes = include_bytes!("../test/ell.txt");
+        let mut parser = Parser::new();
+
+        // start in the middle of a telegram
+        let (ok, _, event) = events(&mut parser, &bytes[300..]);
+        assert_eq!(ok, 0);
+        assert!(event.is_none());
+
+        let (ok, _, event) = events(&mut parser, bytes);
+        assert_eq!(ok, 27);
+        assert!(matches!(event, Some(Event::TelegramComplete(0x9ab5))));
+    }
+
+    #[test]
+    fn malformed_line() {
+        let bytes = include_bytes!("../test/ell-badline.txt");
+        let (ok, err, event) = events(&mut Parser::new(), bytes);
+
+        assert_eq!((ok, err), (26, 1));
+        assert!(matches!(event, Some(Event::TelegramComplete(_))));
+    }
+
+    #[test]
+    fn checksum_error() {
+        let mut bytes = *include_bytes!("../test/ell.txt");
+        bytes[100] ^= 1;
+        let (_, _, event) = events(&mut Parser::new(), &bytes);
+
+        assert!(matches!(event, Some(Event::ChecksumError)));
+    }
+
+    #[test]
+    fn line_overflow() {
+        let bytes = include_bytes!("../test/ell.txt");
+        let mut parser = Parser::<16>::new_sized();
+
+        for &b in bytes {
+            if let Some(Event::Object(obj)) = parser.feed(b) {
+                assert!(matches!(obj, Err(Error::InvalidFormat)));
+            }
+        }
+    }
+
+    #[test]
+    fn feed_slice() {
+        use std::{format, vec::Vec};
+
+        let mut bytes = include_bytes!("../test/ell.txt").repeat(2);
+        bytes[700] ^= 1;
+        let mut parser = Parser::<32>::new_sized();
+        let expected = bytes
+            .iter()
+            .filter_map(|&b| parser.feed(b))
+            .map(|e| format!("{e:?}"))
+            .collect::<Vec<_>>();
+
+        for size in [1, 7, 256, bytes.len()] {
+            let mut parser = Parser::<32>::new_sized();
+            let events = bytes
+                .chunks(size)
+                .flat_map(|chunk| parser.feed_slice(chunk).collect::<Vec<_>>())
+                .map(|e| format!("{e:?}"))
+                .collect::<Vec<_>>();
+            assert_eq!(events, expected, "chunks of {size}");
+        }
+    }
+}
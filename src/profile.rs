@@ -0,0 +1,296 @@
+use core::str::Split;
+
+use crate::obis::{parse_datetime, parse_digits, parse_quantity};
+use crate::{Error, Result, Timestamp, Unit};
+
+/// Split the values within parentheses following an [`Obis`](crate::Obis)
+/// reference, e.g. `(1)(2)(3)`.
+pub(crate) fn split_values(values: &str) -> Option<Split<'_, &'static str>> {
+    let inner = values.strip_prefix('(')?.strip_suffix(')')?;
+    Some(inner.split(")("))
+}
+
+/// Parse the count and the captured references at the start of a buffer,
+/// returning the count and the remaining values.
+fn parse_buffer<'a>(
+    line: &'a str,
+    obis: &str,
+    captured: &[&str],
+) -> Result<(usize, Split<'a, &'static str>)> {
+    let values = line
+        .strip_prefix(obis)
+        .ok_or(Error::UnrecognizedReference)?;
+    let mut values = split_values(values).ok_or(Error::InvalidFormat)?;
+
+    let len = values
+        .next()
+        .and_then(|n| n.parse().ok())
+        .ok_or(Error::InvalidFormat)?;
+
+    if len > 0 {
+        for &expected in captured {
+            if values.next() != Some(expected) {
+                return Err(Error::InvalidFormat);
+            }
+        }
+    }
+
+    Ok((len, values))
+}
+
+/// Timestamp written by meters in place of a missing one.
+fn parse_optional_datetime(s: &str) -> Result<Option<Timestamp>> {
+    match s {
+        "632525252525W" | "632525252525S" => Ok(None),
+        s => parse_datetime(s).map(Some),
+    }
+}
+
+/// The highest quarter-hour demand of a month.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MonthlyPeak {
+    /// Start of the billing period.
+    pub period: Timestamp,
+    /// When the peak occurred, if it did.
+    pub time: Option<Timestamp>,
+    /// The average demand of the quarter-hour (W).
+    pub demand: u32,
+}
+
+/// The maximum demand history of Belgian meters (`0-0:98.1.0`), covering up
+/// to 13 months.
+///
+/// The entries are parsed lazily from the line, without allocating.
+///
+/// ```
+/// use han::DemandHistory;
+///
+/// let line = "0-0:98.1.0(1)(1-0:1.6.0)(1-0:1.6.0)(230101000000W)(221206183000W)(01.234*kW)";
+/// let history = DemandHistory::parse(line)?;
+/// assert_eq!(history.len(), 1);
+///
+/// for peak in history {
+///     assert_eq!(peak?.demand, 1234);
+/// }
+/// # Ok::<(), han::Error>(())
+/// ```
+#[derive(Debug, Clone)]
+pub struct DemandHistory<'a> {
+    len: usize,
+    values: Split<'a, &'static str>,
+}
+
+impl<'a> DemandHistory<'a> {
+    /// Parse an entire `0-0:98.1.0` line.
+    pub fn parse(line: &'a str) -> Result<Self> {
+        let (len, values) = parse_buffer(line, "0-0:98.1.0", &["1-0:1.6.0", "1-0:1.6.0"])?;
+        Ok(Self { len, values })
+    }
+
+    /// Number of months in the history.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns `true` if the history is empty.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+}
+
+impl Iterator for DemandHistory<'_> {
+    type Item = Result<MonthlyPeak>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.len == 0 {
+            return None;
+        }
+        self.len -= 1;
+
+        let peak = match (self.values.next(), self.values.next(), self.values.next()) {
+            (Some(period), Some(time), Some(demand)) => (|| {
+                Ok(MonthlyPeak {
+                    period: parse_datetime(period)?,
+                    time: parse_optional_datetime(time)?,
+                    demand: parse_quantity::<3>(demand, Unit::KW)?,
+                })
+            })(),
+            _ => Err(Error::InvalidFormat),
+        };
+
+        if peak.is_err() {
+            self.len = 0;
+        }
+        Some(peak)
+    }
+}
+
+/// A power failure in any phase.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PowerFailureEvent {
+    /// When the power returned.
+    pub end_time: Timestamp,
+    /// How long the failure lasted (s).
+    pub duration_s: u32,
+}
+
+/// The log of long power failures (`1-0:99.97.0`) of DSMR meters.
+///
+/// The events are parsed lazily from the line, without allocating.
+///
+/// ```
+/// use han::PowerFailureLog;
+///
+/// let line = "1-0:99.97.0(1)(0-0:96.7.19)(101208152415W)(0000000240*s)";
+/// let log = PowerFailureLog::parse(line)?;
+/// assert_eq!(log.len(), 1);
+///
+/// for event in log {
+///     assert_eq!(event?.duration_s, 240);
+/// }
+/// # Ok::<(), han::Error>(())
+/// ```
+#[derive(Debug, Clone)]
+pub struct PowerFailureLog<'a> {
+    len: usize,
+    values: Split<'a, &'static str>,
+}
+
+impl<'a> PowerFailureLog<'a> {
+    /// Parse an entire `1-0:99.97.0` line.
+    pub fn parse(line: &'a str) -> Result<Self> {
+        let (len, values) = parse_buffer(line, "1-0:99.97.0", &["0-0:96.7.19"])?;
+        Ok(Self { len, values })
+    }
+
+    /// Number of events in the log.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns `true` if the log is empty.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+}
+
+impl Iterator for PowerFailureLog<'_> {
+    type Item = Result<PowerFailureEvent>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.len == 0 {
+            return None;
+        }
+        self.len -= 1;
+
+        let event = match (self.values.next(), self.values.next()) {
+            (Some(end_time), Some(duration)) => (|| {
+                let duration = duration.strip_suffix("*s").ok_or(Error::InvalidFormat)?;
+                Ok(PowerFailureEvent {
+                    end_time: parse_datetime(end_time)?,
+                    duration_s: parse_digits(duration).ok_or(Error::InvalidFormat)?,
+                })
+            })(),
+            _ => Err(Error::InvalidFormat),
+        };
+
+        if event.is_err() {
+            self.len = 0;
+        }
+        Some(event)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #[cfg(feature = "time")]
+    use time::macros::datetime;
+
+    #[cfg(feature = "time")]
+    use crate::Error;
+
+    use super::split_values;
+    #[cfg(feature = "time")]
+    use super::{DemandHistory, MonthlyPeak, PowerFailureEvent, PowerFailureLog};
+
+    #[test]
+    fn values() {
+        assert!(split_values("(1)(2)(3)").unwrap().eq(["1", "2", "3"]));
+        assert!(split_values("()").unwrap().eq([""]));
+        assert!(split_values("(1)(2").is_none());
+    }
+
+    #[cfg(feature = "time")]
+    #[test]
+    fn demand_history() {
+        let line = "0-0:98.1.0(2)(1-0:1.6.0)(1-0:1.6.0)(230101000000W)(632525252525W)(00.000*kW)(230201000000W)(230117224500W)(04.329*kW)";
+        let history = DemandHistory::parse(line).unwrap();
+        assert_eq!(history.len(), 2);
+
+        let peaks = history.collect::<Result<std::vec::Vec<_>, _>>().unwrap();
+        assert_eq!(
+            peaks,
+            [
+                MonthlyPeak {
+                    period: datetime!(2023-01-01 00:00 +1),
+                    time: None,
+                    demand: 0,
+                },
+                MonthlyPeak {
+                    period: datetime!(2023-02-01 00:00 +1),
+                    time: Some(datetime!(2023-01-17 22:45 +1)),
+                    demand: 4329,
+                },
+            ]
+        );
+
+        assert!(DemandHistory::parse("0-0:98.1.0(0)").unwrap().is_empty());
+        assert!(matches!(
+            DemandHistory::parse("0-0:98.1.0(1)(1-0:1.4.0)(1-0:1.4.0)"),
+            Err(Error::InvalidFormat)
+        ));
+        assert!(matches!(
+            DemandHistory::parse("1-0:1.6.0(1)"),
+            Err(Error::UnrecognizedReference)
+        ));
+
+        // truncated
+        let mut history =
+            DemandHistory::parse("0-0:98.1.0(2)(1-0:1.6.0)(1-0:1.6.0)(230101000000W)").unwrap();
+        assert!(matches!(history.next(), Some(Err(Error::InvalidFormat))));
+        assert!(history.next().is_none());
+    }
+
+    #[cfg(feature = "time")]
+    #[test]
+    fn power_failure_log() {
+        let line =
+            "1-0:99.97.0(2)(0-0:96.7.19)(101208152415W)(0000000240*s)(101208151004W)(0000000301*s)";
+        let log = PowerFailureLog::parse(line).unwrap();
+        assert_eq!(log.len(), 2);
+
+        let events = log.collect::<Result<std::vec::Vec<_>, _>>().unwrap();
+        assert_eq!(
+            events,
+            [
+                PowerFailureEvent {
+                    end_time: datetime!(2010-12-08 15:24:15 +1),
+                    duration_s: 240,
+                },
+                PowerFailureEvent {
+                    end_time: datetime!(2010-12-08 15:10:04 +1),
+                    duration_s: 301,
+                },
+            ]
+        );
+
+        assert!(PowerFailureLog::parse("1-0:99.97.0(0)(0-0:96.7.19)")
+            .unwrap()
+            .is_empty());
+
+        let mut log =
+            PowerFailureLog::parse("1-0:99.97.0(1)(0-0:96.7.19)(101208152415W)(240*min)").unwrap();
+        assert!(matches!(log.next(), Some(Err(Error::InvalidFormat))));
+        assert!(log.next().is_none());
+    }
+}
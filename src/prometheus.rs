@@ -0,0 +1,331 @@
+//! [Prometheus text exposition] output, for exporters.
+//!
+//! [Prometheus text exposition]: https://prometheus.io/docs/instrumenting/exposition_formats/
+//!
+//! ```
+//! use han::{prometheus, Telegram};
+//!
+//! let telegram = Telegram::parse(include_str!("../test/ell.txt"))?;
+//! let mut s = String::new();
+//! prometheus::write_state(&mut s, &telegram.to_state()?).unwrap();
+//!
+//! assert!(s.contains("# TYPE han_active_energy_watt_hours_total counter\n"));
+//! let energy = "han_active_energy_watt_hours_total{direction=\"from_grid\"} 6136936\n";
+//! assert!(s.contains(energy));
+//! assert!(s.contains("han_voltage_volts{phase=\"L3\"} 235.5\n"));
+//! # Ok::<(), han::Error>(())
+//! ```
+
+use core::fmt::{self, Display, Write};
+
+use crate::state::{Measurement, State};
+use crate::{Direction, Line, Power};
+
+const LINES: [(Line, &str); 3] = [(Line::L1, "L1"), (Line::L2, "L2"), (Line::L3, "L3")];
+
+/// The name and help of a family per component of a measurement.
+type Components = [(Power, &'static str, &'static str); 3];
+
+const ENERGY: Components = [
+    (
+        Power::Active,
+        "han_active_energy_watt_hours_total",
+        "Cumulative active energy.",
+    ),
+    (
+        Power::Reactive,
+        "han_reactive_energy_var_hours_total",
+        "Cumulative reactive energy.",
+    ),
+    (
+        Power::Apparent,
+        "han_apparent_energy_volt_ampere_hours_total",
+        "Cumulative apparent energy.",
+    ),
+];
+
+const TARIFF_ENERGY: Components = [
+    (
+        Power::Active,
+        "han_tariff_active_energy_watt_hours_total",
+        "Cumulative active energy per tariff.",
+    ),
+    (
+        Power::Reactive,
+        "han_tariff_reactive_energy_var_hours_total",
+        "Cumulative reactive energy per tariff.",
+    ),
+    (
+        Power::Apparent,
+        "han_tariff_apparent_energy_volt_ampere_hours_total",
+        "Cumulative apparent energy per tariff.",
+    ),
+];
+
+const POWER: Components = [
+    (
+        Power::Active,
+        "han_active_power_watts",
+        "Active power across all lines.",
+    ),
+    (
+        Power::Reactive,
+        "han_reactive_power_var",
+        "Reactive power across all lines.",
+    ),
+    (
+        Power::Apparent,
+        "han_apparent_power_volt_amperes",
+        "Apparent power across all lines.",
+    ),
+];
+
+const LINE_POWER: Components = [
+    (
+        Power::Active,
+        "han_line_active_power_watts",
+        "Active power per line.",
+    ),
+    (
+        Power::Reactive,
+        "han_line_reactive_power_var",
+        "Reactive power per line.",
+    ),
+    (
+        Power::Apparent,
+        "han_line_apparent_power_volt_amperes",
+        "Apparent power per line.",
+    ),
+];
+
+/// Write the values of a state as metrics prefixed by `han_`, with phase
+/// labels for per-line values. Unset values are left out.
+///
+/// Values are in base units as Prometheus recommends, so energy and power
+/// are in Wh, varh and VAh and in W, var and VA rather than in the kilo
+/// units of [`State`].
+pub fn write_state<W: Write>(w: &mut W, state: &State) -> fmt::Result {
+    components(w, "counter", &ENERGY, [(None, &state.energy)])?;
+    let tariff_energy = ["1", "2", "3", "4"]
+        .into_iter()
+        .zip(&state.tariff_energy)
+        .map(|(tariff, m)| (Some(("tariff", tariff)), m));
+    components(w, "counter", &TARIFF_ENERGY, tariff_energy)?;
+    components(w, "gauge", &POWER, [(None, &state.power)])?;
+    let line_power = LINES
+        .into_iter()
+        .map(|(line, phase)| (Some(("phase", phase)), &state.line(line).power));
+    components(w, "gauge", &LINE_POWER, line_power)?;
+
+    let mut voltage = Family::new(w, "han_voltage_volts", "gauge", "Phase voltage.");
+    for (line, phase) in LINES {
+        voltage.sample(&[("phase", phase)], state.line(line).voltage)?;
+    }
+
+    let mut current = Family::new(w, "han_current_amperes", "gauge", "Phase current.");
+    for (line, phase) in LINES {
+        current.sample(&[("phase", phase)], state.line(line).current)?;
+    }
+
+    let mut power_factor = Family::new(
+        w,
+        "han_power_factor",
+        "gauge",
+        "Power factor across all lines.",
+    );
+    power_factor.sample(&[], state.power_factor)?;
+
+    let mut line_power_factor = Family::new(
+        w,
+        "han_line_power_factor",
+        "gauge",
+        "Power factor per line.",
+    );
+    for (line, phase) in LINES {
+        line_power_factor.sample(&[("phase", phase)], state.line(line).power_factor)?;
+    }
+
+    Family::new(
+        w,
+        "han_neutral_current_amperes",
+        "gauge",
+        "Current in the neutral conductor.",
+    )
+    .sample(&[], state.neutral_current)?;
+    Family::new(w, "han_frequency_hertz", "gauge", "Grid frequency.")
+        .sample(&[], state.frequency)?;
+    Family::new(
+        w,
+        "han_power_failures_total",
+        "counter",
+        "Number of power failures in any phase.",
+    )
+    .sample(&[], state.power_failures)?;
+    Family::new(
+        w,
+        "han_long_power_failures_total",
+        "counter",
+        "Number of long power failures in any phase.",
+    )
+    .sample(&[], state.long_power_failures)
+}
+
+/// Write a family per component of `measurements`, each labelled by
+/// direction after an optional label.
+fn components<'m, W: Write>(
+    w: &mut W,
+    kind: &'static str,
+    families: &Components,
+    measurements: impl IntoIterator<Item = (Option<(&'m str, &'m str)>, &'m Measurement)> + Clone,
+) -> fmt::Result {
+    for &(power, name, help) in families {
+        let mut family = Family::new(w, name, kind, help);
+        for (label, m) in measurements.clone() {
+            family.measurement(label, power, m)?;
+        }
+    }
+    Ok(())
+}
+
+/// A metric family whose `HELP` and `TYPE` lines are written along with the
+/// first sample.
+struct Family<'w, W> {
+    w: &'w mut W,
+    name: &'static str,
+    kind: &'static str,
+    help: &'static str,
+    empty: bool,
+}
+
+impl<'w, W: Write> Family<'w, W> {
+    fn new(w: &'w mut W, name: &'static str, kind: &'static str, help: &'static str) -> Self {
+        Self {
+            w,
+            name,
+            kind,
+            help,
+            empty: true,
+        }
+    }
+
+    fn sample(&mut self, labels: &[(&str, &str)], value: Option<impl Display>) -> fmt::Result {
+        let Some(value) = value else {
+            return Ok(());
+        };
+        if self.empty {
+            write!(
+                self.w,
+                "# HELP {} {}\n# TYPE {} {}\n",
+                self.name, self.help, self.name, self.kind
+            )?;
+            self.empty = false;
+        }
+
+        self.w.write_str(self.name)?;
+        for (i, (key, value)) in labels.iter().enumerate() {
+            let sep = if i == 0 { '{' } else { ',' };
+            write!(self.w, "{}{}=\"{}\"", sep, key, value)?;
+        }
+        if !labels.is_empty() {
+            self.w.write_char('}')?;
+        }
+        writeln!(self.w, " {}", value)
+    }
+
+    /// Write a sample of a component per direction, labelled by direction
+    /// after an optional `label`.
+    fn measurement(
+        &mut self,
+        label: Option<(&str, &str)>,
+        power: Power,
+        m: &Measurement,
+    ) -> fmt::Result {
+        for dir in [Direction::FromGrid, Direction::ToGrid] {
+            let labels = [label.unwrap_or_default(), ("direction", dir.name())];
+            let labels = if label.is_some() {
+                &labels
+            } else {
+                &labels[1..]
+            };
+            self.sample(labels, m.get(power, dir).map(from_kilo))?;
+        }
+        Ok(())
+    }
+}
+
+/// Convert a value in kilo units into base units. Meters send energy and
+/// power in whole Wh and W, so the result is rounded to undo the error of
+/// the [`State`] floats, e.g. 1000.9999999999999 for 1.001 kWh.
+fn from_kilo(v: f64) -> i64 {
+    let v = v * 1e3;
+    (if v < 0. { v - 0.5 } else { v + 0.5 }) as i64
+}
+
+#[cfg(test)]
+mod tests {
+    use std::string::String;
+
+    use crate::state::State;
+    use crate::{Decivolts, Direction, Line, Object, VoltAmpereReactiveHours, WattHours, Watts};
+
+    use super::write_state;
+
+    #[test]
+    fn empty() {
+        let mut s = String::new();
+        write_state(&mut s, &State::default()).unwrap();
+        assert_eq!(s, "");
+    }
+
+    #[test]
+    fn voltage() {
+        let mut state = State::default();
+        state.insert(Object::Voltage(Line::L2, Decivolts(2301)));
+
+        let mut s = String::new();
+        write_state(&mut s, &state).unwrap();
+        assert_eq!(
+            s,
+            "# HELP han_voltage_volts Phase voltage.\n\
+             # TYPE han_voltage_volts gauge\n\
+             han_voltage_volts{phase=\"L2\"} 230.1\n"
+        );
+    }
+
+    #[test]
+    fn components() {
+        let mut state = State::default();
+        state.insert(Object::EnergyTariff(
+            Direction::ToGrid,
+            2,
+            VoltAmpereReactiveHours(1500).into(),
+        ));
+        state.insert(Object::Power(
+            Line::L3,
+            Direction::FromGrid,
+            Watts(806).into(),
+        ));
+
+        let mut s = String::new();
+        write_state(&mut s, &state).unwrap();
+        assert_eq!(
+            s,
+            "# HELP han_tariff_reactive_energy_var_hours_total Cumulative reactive energy per tariff.\n\
+             # TYPE han_tariff_reactive_energy_var_hours_total counter\n\
+             han_tariff_reactive_energy_var_hours_total{tariff=\"2\",direction=\"to_grid\"} 1500\n\
+             # HELP han_line_active_power_watts Active power per line.\n\
+             # TYPE han_line_active_power_watts gauge\n\
+             han_line_active_power_watts{phase=\"L3\",direction=\"from_grid\"} 806\n"
+        );
+    }
+
+    #[test]
+    fn base_units() {
+        let mut state = State::default();
+        state.insert(Object::Energy(Direction::FromGrid, WattHours(1001).into()));
+
+        let mut s = String::new();
+        write_state(&mut s, &state).unwrap();
+        assert!(s.contains("han_active_energy_watt_hours_total{direction=\"from_grid\"} 1001\n"));
+    }
+}
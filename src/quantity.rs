@@ -0,0 +1,413 @@
+//! Values of [`Object`](crate::Object)s, typed by their unit so that e.g.
+//! decivolts are not mistaken for volts.
+
+use crate::Power;
+
+/// Active power in watts (W).
+///
+/// ```
+/// use han::Watts;
+///
+/// assert_eq!(Watts(1234).as_kilo(), 1.234);
+/// ```
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[repr(transparent)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "defmt-03", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", serde(transparent))]
+pub struct Watts(pub u32);
+
+impl Watts {
+    /// The power in watts.
+    pub fn as_f64(self) -> f64 {
+        self.0.into()
+    }
+
+    /// The power in kilowatts.
+    pub fn as_kilo(self) -> f64 {
+        f64::from(self.0) / 1e3
+    }
+}
+
+/// Active energy in watt-hours (Wh).
+///
+/// ```
+/// use han::WattHours;
+///
+/// assert_eq!(WattHours(6136930).as_kilo(), 6136.93);
+/// ```
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[repr(transparent)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "defmt-03", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", serde(transparent))]
+pub struct WattHours(pub u64);
+
+impl WattHours {
+    /// The energy in watt-hours.
+    pub fn as_f64(self) -> f64 {
+        self.0 as f64
+    }
+
+    /// The energy in kilowatt-hours.
+    pub fn as_kilo(self) -> f64 {
+        self.0 as f64 / 1e3
+    }
+}
+
+/// Reactive power in volt-amperes reactive (var).
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[repr(transparent)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "defmt-03", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", serde(transparent))]
+pub struct VoltAmperesReactive(pub u32);
+
+impl VoltAmperesReactive {
+    /// The power in var.
+    pub fn as_f64(self) -> f64 {
+        self.0.into()
+    }
+
+    /// The power in kvar.
+    pub fn as_kilo(self) -> f64 {
+        f64::from(self.0) / 1e3
+    }
+}
+
+/// Apparent power in volt-amperes (VA).
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[repr(transparent)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "defmt-03", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", serde(transparent))]
+pub struct VoltAmperes(pub u32);
+
+impl VoltAmperes {
+    /// The power in VA.
+    pub fn as_f64(self) -> f64 {
+        self.0.into()
+    }
+
+    /// The power in kVA.
+    pub fn as_kilo(self) -> f64 {
+        f64::from(self.0) / 1e3
+    }
+}
+
+/// Reactive energy in volt-ampere reactive hours (varh).
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[repr(transparent)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "defmt-03", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", serde(transparent))]
+pub struct VoltAmpereReactiveHours(pub u64);
+
+impl VoltAmpereReactiveHours {
+    /// The energy in varh.
+    pub fn as_f64(self) -> f64 {
+        self.0 as f64
+    }
+
+    /// The energy in kvarh.
+    pub fn as_kilo(self) -> f64 {
+        self.0 as f64 / 1e3
+    }
+}
+
+/// Apparent energy in volt-ampere hours (VAh).
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[repr(transparent)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "defmt-03", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", serde(transparent))]
+pub struct VoltAmpereHours(pub u64);
+
+impl VoltAmpereHours {
+    /// The energy in VAh.
+    pub fn as_f64(self) -> f64 {
+        self.0 as f64
+    }
+
+    /// The energy in kVAh.
+    pub fn as_kilo(self) -> f64 {
+        self.0 as f64 / 1e3
+    }
+}
+
+/// Active, reactive or apparent power, each in its own unit.
+///
+/// ```
+/// use han::{Power, PowerQuantity, VoltAmperesReactive, Watts};
+///
+/// let q = PowerQuantity::from(VoltAmperesReactive(412));
+/// assert_eq!(q.kind(), Power::Reactive);
+/// assert_eq!(q.raw(), 412);
+/// assert_eq!(q.active(), None);
+/// assert_eq!(PowerQuantity::new(Power::Active, 1234), Watts(1234).into());
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "defmt-03", derive(defmt::Format))]
+pub enum PowerQuantity {
+    /// Active power (W).
+    Active(Watts),
+    /// Reactive power (var).
+    Reactive(VoltAmperesReactive),
+    /// Apparent power (VA).
+    Apparent(VoltAmperes),
+}
+
+impl PowerQuantity {
+    /// A quantity of `kind` with the value `v` in W, var or VA.
+    pub const fn new(kind: Power, v: u32) -> Self {
+        match kind {
+            Power::Active => PowerQuantity::Active(Watts(v)),
+            Power::Reactive => PowerQuantity::Reactive(VoltAmperesReactive(v)),
+            Power::Apparent => PowerQuantity::Apparent(VoltAmperes(v)),
+        }
+    }
+
+    /// The kind of power.
+    pub const fn kind(self) -> Power {
+        match self {
+            PowerQuantity::Active(_) => Power::Active,
+            PowerQuantity::Reactive(_) => Power::Reactive,
+            PowerQuantity::Apparent(_) => Power::Apparent,
+        }
+    }
+
+    /// The value in W, var or VA, depending on the [`kind`](Self::kind).
+    pub const fn raw(self) -> u32 {
+        match self {
+            PowerQuantity::Active(Watts(v))
+            | PowerQuantity::Reactive(VoltAmperesReactive(v))
+            | PowerQuantity::Apparent(VoltAmperes(v)) => v,
+        }
+    }
+
+    /// The value in kW, kvar or kVA.
+    pub fn as_kilo(self) -> f64 {
+        f64::from(self.raw()) / 1e3
+    }
+
+    /// The power if it is active, e.g. for converting into a `uom` quantity.
+    pub const fn active(self) -> Option<Watts> {
+        match self {
+            PowerQuantity::Active(w) => Some(w),
+            _ => None,
+        }
+    }
+}
+
+impl From<Watts> for PowerQuantity {
+    fn from(v: Watts) -> Self {
+        PowerQuantity::Active(v)
+    }
+}
+
+impl From<VoltAmperesReactive> for PowerQuantity {
+    fn from(v: VoltAmperesReactive) -> Self {
+        PowerQuantity::Reactive(v)
+    }
+}
+
+impl From<VoltAmperes> for PowerQuantity {
+    fn from(v: VoltAmperes) -> Self {
+        PowerQuantity::Apparent(v)
+    }
+}
+
+/// Active, reactive or apparent energy, each in its own unit.
+///
+/// ```
+/// use han::{EnergyQuantity, Power, VoltAmpereReactiveHours};
+///
+/// let q = EnergyQuantity::from(VoltAmpereReactiveHours(8909));
+/// assert_eq!(q.kind(), Power::Reactive);
+/// assert_eq!(q.as_kilo(), 8.909);
+/// assert_eq!(q.active(), None);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "defmt-03", derive(defmt::Format))]
+pub enum EnergyQuantity {
+    /// Active energy (Wh).
+    Active(WattHours),
+    /// Reactive energy (varh).
+    Reactive(VoltAmpereReactiveHours),
+    /// Apparent energy (VAh).
+    Apparent(VoltAmpereHours),
+}
+
+impl EnergyQuantity {
+    /// A quantity of `kind` with the value `v` in Wh, varh or VAh.
+    pub const fn new(kind: Power, v: u64) -> Self {
+        match kind {
+            Power::Active => EnergyQuantity::Active(WattHours(v)),
+            Power::Reactive => EnergyQuantity::Reactive(VoltAmpereReactiveHours(v)),
+            Power::Apparent => EnergyQuantity::Apparent(VoltAmpereHours(v)),
+        }
+    }
+
+    /// The kind of energy.
+    pub const fn kind(self) -> Power {
+        match self {
+            EnergyQuantity::Active(_) => Power::Active,
+            EnergyQuantity::Reactive(_) => Power::Reactive,
+            EnergyQuantity::Apparent(_) => Power::Apparent,
+        }
+    }
+
+    /// The value in Wh, varh or VAh, depending on the [`kind`](Self::kind).
+    pub const fn raw(self) -> u64 {
+        match self {
+            EnergyQuantity::Active(WattHours(v))
+            | EnergyQuantity::Reactive(VoltAmpereReactiveHours(v))
+            | EnergyQuantity::Apparent(VoltAmpereHours(v)) => v,
+        }
+    }
+
+    /// The value in kWh, kvarh or kVAh.
+    pub fn as_kilo(self) -> f64 {
+        self.raw() as f64 / 1e3
+    }
+
+    /// The energy if it is active, e.g. for converting into a `uom`
+    /// quantity.
+    pub const fn active(self) -> Option<WattHours> {
+        match self {
+            EnergyQuantity::Active(wh) => Some(wh),
+            _ => None,
+        }
+    }
+}
+
+impl From<WattHours> for EnergyQuantity {
+    fn from(v: WattHours) -> Self {
+        EnergyQuantity::Active(v)
+    }
+}
+
+impl From<VoltAmpereReactiveHours> for EnergyQuantity {
+    fn from(v: VoltAmpereReactiveHours) -> Self {
+        EnergyQuantity::Reactive(v)
+    }
+}
+
+impl From<VoltAmpereHours> for EnergyQuantity {
+    fn from(v: VoltAmpereHours) -> Self {
+        EnergyQuantity::Apparent(v)
+    }
+}
+
+/// Voltage in decivolts (dV, 0.1 V).
+///
+/// ```
+/// use han::Decivolts;
+///
+/// assert_eq!(Decivolts(2301).as_f64(), 230.1);
+/// assert_eq!(Decivolts(2301).as_milli(), 230_100);
+/// ```
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[repr(transparent)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "defmt-03", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", serde(transparent))]
+pub struct Decivolts(pub u16);
+
+impl Decivolts {
+    /// The voltage in volts.
+    pub fn as_f64(self) -> f64 {
+        f64::from(self.0) / 10.
+    }
+
+    /// The voltage in millivolts.
+    pub fn as_milli(self) -> u32 {
+        u32::from(self.0) * 100
+    }
+}
+
+/// Current in deciamperes (dA, 0.1 A).
+///
+/// ```
+/// use han::Deciamps;
+///
+/// assert_eq!(Deciamps(12).as_f64(), 1.2);
+/// assert_eq!(Deciamps(12).as_milli(), 1200);
+/// ```
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[repr(transparent)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "defmt-03", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", serde(transparent))]
+pub struct Deciamps(pub u16);
+
+impl Deciamps {
+    /// The current in amperes.
+    pub fn as_f64(self) -> f64 {
+        f64::from(self.0) / 10.
+    }
+
+    /// The current in milliamperes.
+    pub fn as_milli(self) -> u32 {
+        u32::from(self.0) * 100
+    }
+}
+
+/// Conversions into [`uom`] quantities, for dimensional analysis.
+///
+/// Only active power and energy convert, as `uom` has no notion of reactive
+/// or apparent power. Use [`PowerQuantity::active`] and
+/// [`EnergyQuantity::active`] to convert a quantity of unknown kind.
+///
+/// ```
+/// use han::{Decivolts, EnergyQuantity, VoltAmpereReactiveHours, WattHours};
+/// use uom::si::electric_potential::millivolt;
+/// use uom::si::energy::{joule, kilowatt_hour};
+/// use uom::si::f64::{ElectricPotential, Energy};
+///
+/// let energy = Energy::from(WattHours(6136930));
+/// assert_eq!(energy.get::<kilowatt_hour>(), 6136.93);
+/// assert_eq!(energy.get::<joule>(), 6136.93 * 3.6e6);
+///
+/// let voltage = ElectricPotential::from(Decivolts(2301));
+/// assert_eq!(voltage.get::<millivolt>(), 230_100.);
+///
+/// let reactive = EnergyQuantity::from(VoltAmpereReactiveHours(8909));
+/// assert_eq!(reactive.active().map(Energy::from), None);
+/// ```
+#[cfg(feature = "uom")]
+mod uom_impls {
+    use uom::si::electric_current::ampere;
+    use uom::si::electric_potential::volt;
+    use uom::si::energy::watt_hour;
+    use uom::si::f64::{ElectricCurrent, ElectricPotential, Energy, Power};
+    use uom::si::power::watt;
+
+    use super::{Deciamps, Decivolts, WattHours, Watts};
+
+    impl From<Watts> for Power {
+        fn from(v: Watts) -> Self {
+            Power::new::<watt>(v.as_f64())
+        }
+    }
+
+    impl From<WattHours> for Energy {
+        fn from(v: WattHours) -> Self {
+            Energy::new::<watt_hour>(v.as_f64())
+        }
+    }
+
+    impl From<Decivolts> for ElectricPotential {
+        fn from(v: Decivolts) -> Self {
+            ElectricPotential::new::<volt>(v.as_f64())
+        }
+    }
+
+    impl From<Deciamps> for ElectricCurrent {
+        fn from(v: Deciamps) -> Self {
+            ElectricCurrent::new::<ampere>(v.as_f64())
+        }
+    }
+}
@@ -1,15 +1,32 @@
-use core::str::FromStr;
+use core::fmt::Display;
+#[cfg(feature = "embedded-hal-nb")]
+use embedded_hal_nb::{nb, serial};
 #[cfg(feature = "embedded-io-async")]
 use embedded_io_async::BufRead;
 
-use crate::{obis::Object, Error, Result};
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+
+use crate::{
+    obis::Object, Decoded, DemandHistory, Error, LenientObject, ObjectDecoder, P1Version,
+    ParseOptions, PowerFailureLog, Result,
+};
+
+/// Default size in bytes of the buffer holding a single telegram.
+pub const DEFAULT_BUFFER_SIZE: usize = 2048;
 
 /// A reader for the raw UART output of a power meter.
-pub struct Reader<I>
+///
+/// Telegrams are read into a buffer of `N` bytes. [`Iterator::next`] returns
+/// `None` if a telegram does not fit.
+pub struct Reader<I, const N: usize = DEFAULT_BUFFER_SIZE>
 where
     I: Iterator<Item = u8>,
 {
     iter: I,
+    skipped: usize,
+    /// Whether telegrams end with a checksum.
+    checksum: bool,
 }
 
 impl<I> Reader<I>
@@ -18,94 +35,363 @@ where
 {
     /// Construct a new reader from a byte iterator.
     pub fn new(iter: I) -> Self {
-        Self { iter }
+        Self::new_sized(iter)
     }
 }
 
-impl<I> Iterator for Reader<I>
+impl<I, const N: usize> Reader<I, N>
 where
     I: Iterator<Item = u8>,
 {
-    type Item = Readout;
+    /// Construct a new reader with a buffer of `N` bytes.
+    ///
+    /// ```
+    /// let bytes = include_bytes!("../test/ell.txt").iter().copied();
+    /// let mut reader = han::Reader::<_, 1024>::new_sized(bytes);
+    /// assert!(reader.next().is_some());
+    /// ```
+    pub fn new_sized(iter: I) -> Self {
+        Self {
+            iter,
+            skipped: 0,
+            checksum: true,
+        }
+    }
 
-    fn next(&mut self) -> Option<Self::Item> {
-        while self.iter.next()? != b'/' {}
+    /// Read telegrams that end with a bare `!` instead of a checksum, as sent
+    /// by older DSMR 2.2 and 3.0 meters.
+    ///
+    /// The resulting readouts are parsed without verification.
+    ///
+    /// ```
+    /// let s = "/ISk5\\2MT382-1004\r\n\r\n1-0:1.8.1(00001.001*kWh)\r\n!\r\n";
+    /// let mut reader = han::Reader::new(s.bytes()).without_checksum();
+    /// let readout = reader.next().unwrap();
+    /// assert_eq!(readout.to_telegram()?.flag_id, "ISk");
+    /// # Ok::<(), han::Error>(())
+    /// ```
+    pub fn without_checksum(mut self) -> Self {
+        self.checksum = false;
+        self
+    }
 
-        let mut buffer = [0u8; 2048];
-        buffer[0] = b'/';
+    /// Number of bytes discarded while looking for the start of a telegram
+    /// during the most recent call to [`Iterator::next`] or
+    /// [`Reader::try_next`].
+    ///
+    /// Line breaks following the previous telegram are not counted, so a
+    /// non-zero value indicates line noise or that the reader started in the
+    /// middle of a telegram.
+    pub fn bytes_skipped(&self) -> usize {
+        self.skipped
+    }
 
-        let mut i = 1;
-        let mut write = |b| {
-            if i >= buffer.len() {
-                return None; // Buffer overflow.
-            }
+    /// Read the next telegram into a [`VecReadout`], which grows to fit it
+    /// regardless of `N`.
+    ///
+    /// Since the buffer is unbounded, a stream of garbage without a `!` is
+    /// read into memory until the underlying iterator ends.
+    ///
+    /// Only this reader can read into a [`VecReadout`]. `AsyncReader`,
+    /// `BlockingReader` and `NbReader` fill a fixed buffer as bytes arrive,
+    /// so that they work without an allocator and can resume partial reads;
+    /// give them a larger `N` with `new_sized` instead.
+    #[cfg(feature = "alloc")]
+    pub fn next_vec(&mut self) -> Option<VecReadout> {
+        let mut buffer = Vec::new();
 
-            buffer[i] = b;
-            i += 1;
+        let crc = self
+            .read_telegram(|b| {
+                buffer.push(b);
+                Some(())
+            })?
+            .ok()?;
 
+        Some(VecReadout {
+            buffer,
+            crc,
+            verify: self.checksum,
+        })
+    }
+
+    /// Read the next readout, reporting why a telegram was dropped instead of
+    /// silently skipping it like [`Iterator::next`] does.
+    ///
+    /// Returns [`Error::BufferOverflow`] if the telegram does not fit in `N`
+    /// bytes and [`Error::UnexpectedEof`] if the iterator ends in the middle
+    /// of a telegram. Reading may continue after an error. `None` is returned
+    /// once the iterator ends outside of a telegram. Resynchronization is
+    /// reported by [`Reader::bytes_skipped`].
+    ///
+    /// ```
+    /// let bytes = include_bytes!("../test/ell.txt");
+    /// let mut reader = han::Reader::new(bytes[..100].iter().copied());
+    ///
+    /// assert!(matches!(reader.try_next(), Some(Err(han::Error::UnexpectedEof))));
+    /// assert!(reader.try_next().is_none());
+    /// ```
+    pub fn try_next(&mut self) -> Option<Result<Readout<N>>> {
+        let mut buffer = [0u8; N];
+        let mut i = 0;
+
+        let res = self.read_telegram(|b| {
+            // `None` on buffer overflow
+            *buffer.get_mut(i)? = b;
+            i += 1;
             Some(())
+        })?;
+
+        Some(res.map(|crc| Readout {
+            buffer,
+            crc: Some(crc),
+            verify: self.checksum,
+        }))
+    }
+
+    /// Skip to the next `/` and pass the telegram starting there to `write`,
+    /// byte by byte, through the four checksum characters after the `!`,
+    /// returning the CRC of the telegram.
+    ///
+    /// Returns `None` if the iterator ends before the start of a telegram.
+    fn read_telegram(&mut self, write: impl FnMut(u8) -> Option<()>) -> Option<Result<u16>> {
+        self.skipped = 0;
+        // the line ending after the previous telegram is not noise
+        let mut b = self.iter.next()?;
+        while matches!(b, b'\r' | b'\n') {
+            b = self.iter.next()?;
+        }
+        while b != b'/' {
+            self.skipped += 1;
+            b = self.iter.next()?;
+        }
+
+        Some(self.read_frame(write))
+    }
+
+    fn read_frame(&mut self, mut write: impl FnMut(u8) -> Option<()>) -> Result<u16> {
+        write(b'/').ok_or(Error::BufferOverflow)?;
+        let mut crc = crc16::State::<crc16::ARC>::new();
+        crc.update(b"/");
+
+        let mut next = || {
+            let b = self.iter.next().ok_or(Error::UnexpectedEof)?;
+            write(b).ok_or(Error::BufferOverflow)?;
+            Ok::<_, Error>(b)
         };
 
         loop {
-            let b = self.iter.next()?;
-            write(b)?;
+            let b = next()?;
+            crc.update(&[b]);
 
             if b == b'!' {
                 // Add CRC bytes
-                for _ in 0..4 {
-                    write(self.iter.next()?)?;
+                if self.checksum {
+                    for _ in 0..4 {
+                        next()?;
+                    }
                 }
 
-                return Some(Readout { buffer });
+                return Ok(crc.get());
             }
         }
     }
 }
 
-#[cfg(feature = "embedded-io-async")]
+impl<I, const N: usize> Iterator for Reader<I, N>
+where
+    I: Iterator<Item = u8>,
+{
+    type Item = Readout<N>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.try_next()?.ok()
+    }
+}
+
+#[cfg(any(
+    feature = "embedded-io",
+    feature = "embedded-io-async",
+    feature = "embedded-hal-nb"
+))]
 #[derive(Debug)]
-struct Buffer {
-    data: [u8; 2048],
-    pos: usize,
-    len: Option<usize>,
+struct Buffer<const N: usize> {
+    data: [u8; N],
+    /// Position of the next byte, or `None` while looking for a `/`.
+    pos: Option<usize>,
+    /// Position just past the end of the telegram, once the `!` is read.
+    end: Option<usize>,
+    /// CRC of the bytes up to and including the `!`.
+    crc: crc16::State<crc16::ARC>,
+    /// Whether the buffer holds a complete telegram.
+    complete: bool,
+    /// Whether telegrams end with a checksum.
+    checksum: bool,
 }
 
-#[cfg(feature = "embedded-io-async")]
-impl Buffer {
-    const fn new() -> Self {
+/// The result of pushing a byte to a [`Buffer`].
+#[cfg(any(
+    feature = "embedded-io",
+    feature = "embedded-io-async",
+    feature = "embedded-hal-nb"
+))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Push {
+    /// More bytes are needed.
+    Pending,
+    /// The byte completed a telegram.
+    Complete,
+    /// A telegram was discarded because it did not fit in the buffer.
+    Overflow,
+}
+
+#[cfg(any(
+    feature = "embedded-io",
+    feature = "embedded-io-async",
+    feature = "embedded-hal-nb"
+))]
+impl<const N: usize> Buffer<N> {
+    fn new() -> Self {
         Self {
-            data: [0; 2048],
-            pos: 0,
-            len: None,
+            data: [0; N],
+            pos: None,
+            end: None,
+            crc: crc16::State::new(),
+            complete: false,
+            checksum: true,
+        }
+    }
+
+    /// Handle a single byte, skipping to the next `/` if not in a telegram.
+    /// A complete telegram is discarded by the next byte.
+    fn push(&mut self, b: u8) -> Push {
+        if self.complete {
+            self.clear();
+        }
+
+        let pos = match self.pos {
+            Some(pos) => pos,
+            None if b == b'/' => 0,
+            None => return Push::Pending,
+        };
+        let Some(slot) = self.data.get_mut(pos) else {
+            // `b` might be the '/' of the next telegram
+            self.clear();
+            if pos > 0 {
+                self.push(b);
+            }
+            return Push::Overflow;
+        };
+
+        *slot = b;
+        self.pos = Some(pos + 1);
+        if self.end.is_none() {
+            self.crc.update(&[b]);
+            // end of telegram 4 bytes after the '!', if checksummed
+            if b == b'!' {
+                self.end = Some(pos + if self.checksum { 5 } else { 1 });
+            }
+        }
+
+        self.complete = self.end.is_some_and(|end| pos + 1 >= end);
+        if self.complete {
+            Push::Complete
+        } else {
+            Push::Pending
+        }
+    }
+
+    /// Push the bytes of `buf` until a telegram is complete, returning the
+    /// number of bytes consumed.
+    #[cfg(any(feature = "embedded-io", feature = "embedded-io-async"))]
+    fn feed(&mut self, buf: &[u8]) -> usize {
+        for (i, &b) in buf.iter().enumerate() {
+            if self.push(b) == Push::Complete {
+                return i + 1;
+            }
+        }
+        buf.len()
+    }
+
+    fn clear(&mut self) {
+        self.pos = None;
+        self.end = None;
+        self.crc = crc16::State::new();
+        self.complete = false;
+    }
+
+    fn readout(&self) -> Readout<N> {
+        Readout {
+            buffer: self.data,
+            crc: Some(self.crc.get()),
+            verify: self.checksum,
         }
     }
+
+    fn to_telegram(&self) -> Result<Telegram<'_>> {
+        parse_buffer(&self.data, Some(self.crc.get()), self.checksum)
+    }
 }
 
 /// A parser for the raw UART output of a power meter.
 #[cfg(feature = "embedded-io-async")]
-pub struct AsyncReader<R> {
+pub struct AsyncReader<R, const N: usize = DEFAULT_BUFFER_SIZE> {
     reader: R,
-    buffer: Option<Buffer>,
+    buffer: Buffer<N>,
 }
 
-#[cfg(feature = "embedded-io-async")]
-async fn scan_to_next<R>(reader: &mut R) -> Result<Option<Buffer>, R::Error>
-where
-    R: BufRead,
-{
-    loop {
-        let buf = reader.fill_buf().await?;
-        let n = buf.len();
-        if n == 0 {
-            return Ok(None);
+/// An error encountered while reading and parsing a telegram.
+#[derive(Debug)]
+pub enum ReadError<E> {
+    /// The underlying reader failed.
+    Io(E),
+    /// The telegram could not be parsed.
+    Framing(Error),
+}
+
+impl<E> From<Error> for ReadError<E> {
+    fn from(e: Error) -> Self {
+        ReadError::Framing(e)
+    }
+}
+
+impl<E: Display> Display for ReadError<E> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            ReadError::Io(e) => write!(f, "io error: {}", e),
+            ReadError::Framing(e) => write!(f, "framing error: {}", e),
         }
+    }
+}
 
-        if let Some(start) = buf.iter().position(|b| *b == b'/') {
-            reader.consume(start);
-            return Ok(Some(Buffer::new()));
-        } else {
-            reader.consume(n);
+/// An object of a [`Telegram`] that could not be parsed, along with where
+/// it was found.
+#[derive(Debug)]
+#[cfg_attr(feature = "defmt-03", derive(defmt::Format))]
+pub struct ParseError<'a> {
+    /// Index of the line among the object lines of the telegram.
+    pub line: usize,
+    /// The reference of the object, if it could be parsed.
+    pub obis: Option<crate::Obis>,
+    /// The values as written, e.g. `(0230.0*kV)`, or the whole line if it
+    /// has none.
+    pub value: &'a str,
+    /// The error.
+    pub error: Error,
+}
+
+impl From<ParseError<'_>> for Error {
+    fn from(e: ParseError) -> Self {
+        e.error
+    }
+}
+
+impl Display for ParseError<'_> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "line {}", self.line)?;
+        if let Some(obis) = self.obis {
+            write!(f, " ({})", obis)?;
         }
+        write!(f, ": {} in `{}`", self.error, self.value)
     }
 }
 
@@ -116,155 +402,1802 @@ where
 {
     /// Construct a new AsyncReader from a byte reader.
     pub fn new(reader: R) -> Self {
+        Self::new_sized(reader)
+    }
+}
+
+#[cfg(feature = "tokio-io")]
+impl<R> AsyncReader<embedded_io_adapters::tokio_1::FromTokio<R>>
+where
+    embedded_io_adapters::tokio_1::FromTokio<R>: BufRead,
+{
+    /// Construct a new AsyncReader from a `tokio::io::AsyncBufRead`, such as
+    /// a `tokio_serial::SerialStream` wrapped in a `tokio::io::BufReader`.
+    pub fn from_tokio(reader: R) -> Self {
+        Self::new(embedded_io_adapters::tokio_1::FromTokio::new(reader))
+    }
+}
+
+#[cfg(feature = "futures-io")]
+impl<R> AsyncReader<embedded_io_adapters::futures_03::FromFutures<R>>
+where
+    embedded_io_adapters::futures_03::FromFutures<R>: BufRead,
+{
+    /// Construct a new AsyncReader from a `futures::io::AsyncBufRead`.
+    pub fn from_futures(reader: R) -> Self {
+        Self::new(embedded_io_adapters::futures_03::FromFutures::new(reader))
+    }
+}
+
+#[cfg(feature = "embedded-io-async")]
+impl<R, const N: usize> AsyncReader<R, N>
+where
+    R: BufRead,
+{
+    /// Construct a new AsyncReader with a buffer of `N` bytes.
+    pub fn new_sized(reader: R) -> Self {
         Self {
             reader,
-            buffer: None,
+            buffer: Buffer::new(),
+        }
+    }
+
+    /// Read telegrams that end with a bare `!` instead of a checksum, as sent
+    /// by older DSMR 2.2 and 3.0 meters.
+    ///
+    /// See [`Reader::without_checksum`].
+    pub fn without_checksum(mut self) -> Self {
+        self.buffer.checksum = false;
+        self
+    }
+
+    /// Read until the buffer holds a complete telegram, returning `false` if
+    /// the reader is exhausted first.
+    async fn fill(&mut self) -> Result<bool, R::Error> {
+        loop {
+            let buf = self.reader.fill_buf().await?;
+            if buf.is_empty() {
+                return Ok(false);
+            }
+
+            let n = self.buffer.feed(buf);
+            self.reader.consume(n);
+            if self.buffer.complete {
+                return Ok(true);
+            }
         }
     }
 
     /// Read the next readout from the reader.
     ///
     /// This function is cancel-safe.
-    pub async fn next_readout(&mut self) -> Result<Option<Readout>, R::Error> {
-        loop {
-            match self.buffer {
-                Some(ref mut buffer) => 'fill_buf: loop {
-                    let buf = self.reader.fill_buf().await?;
-                    let n = buf.len();
+    pub async fn next_readout(&mut self) -> Result<Option<Readout<N>>, R::Error> {
+        if !self.fill().await? {
+            return Ok(None);
+        }
 
-                    if n == 0 {
-                        return Ok(None);
-                    }
+        Ok(Some(self.buffer.readout()))
+    }
 
-                    for (i, &b) in buf.iter().enumerate() {
-                        if buffer.pos >= buffer.data.len() {
-                            self.reader.consume(i);
-                            self.buffer = None;
-                            break 'fill_buf; // buffer overflow
-                        }
-
-                        buffer.data[buffer.pos] = b;
-
-                        if buffer.len.is_some_and(|len| buffer.pos >= len) {
-                            self.reader.consume(i);
-                            let readout = Readout {
-                                buffer: buffer.data,
-                            };
-                            self.buffer = None;
-                            return Ok(Some(readout));
-                        }
-
-                        // end of telegram 4 bytes after the '!'
-                        if b == b'!' {
-                            buffer.len = Some(buffer.pos + 4);
-                        }
-
-                        buffer.pos += 1;
-                    }
+    /// Read and parse the next telegram from the reader.
+    ///
+    /// Unlike [`AsyncReader::next_readout`], the telegram is parsed in place
+    /// without copying the buffer. This function is cancel-safe.
+    pub async fn next_telegram(&mut self) -> Result<Option<Telegram<'_>>, ReadError<R::Error>> {
+        if !self.fill().await.map_err(ReadError::Io)? {
+            return Ok(None);
+        }
 
-                    self.reader.consume(n);
-                },
-                None => match scan_to_next(&mut self.reader).await? {
-                    Some(buffer) => {
-                        self.buffer = Some(buffer);
-                    }
-                    None => return Ok(None),
-                },
+        Ok(Some(self.buffer.to_telegram()?))
+    }
+
+    /// Turn the reader into a [`Stream`](futures_core::Stream) of readouts,
+    /// ending when the underlying reader does.
+    ///
+    /// Like [`AsyncReader::next_readout`], polling the stream is cancel-safe.
+    #[cfg(feature = "futures")]
+    pub fn into_stream(self) -> impl futures_core::Stream<Item = Result<Readout<N>, R::Error>> {
+        futures_util::stream::unfold(self, |mut reader| async move {
+            match reader.next_readout().await {
+                Ok(Some(readout)) => Some((Ok(readout), reader)),
+                Ok(None) => None,
+                Err(e) => Some((Err(e), reader)),
+            }
+        })
+    }
+
+    /// Borrow the reader as a [`Stream`](futures_core::Stream) of readouts.
+    ///
+    /// Unlike [`AsyncReader::into_stream`], the reader can be used again once
+    /// the stream is dropped, e.g. after `take`-ing a number of readouts.
+    #[cfg(feature = "futures")]
+    pub fn stream(
+        &mut self,
+    ) -> impl futures_core::Stream<Item = Result<Readout<N>, R::Error>> + '_ {
+        futures_util::stream::unfold(self, |reader| async move {
+            match reader.next_readout().await {
+                Ok(Some(readout)) => Some((Ok(readout), reader)),
+                Ok(None) => None,
+                Err(e) => Some((Err(e), reader)),
             }
+        })
+    }
+}
+
+/// Total length of the HDLC or M-Bus frame starting at the beginning of
+/// `buf`, or `None` if more bytes are needed to tell.
+#[cfg(feature = "embedded-io-async")]
+fn dlms_frame_len(buf: &[u8]) -> Result<Option<usize>> {
+    match buf.first() {
+        Some(&crate::mbus::START) => crate::mbus::Frame::frame_len(buf),
+        _ => crate::hdlc::Frame::frame_len(buf),
+    }
+}
+
+/// Parse a complete HDLC or M-Bus frame, returning its information field and
+/// whether it continues in the next frame.
+#[cfg(feature = "embedded-io-async")]
+fn parse_dlms_frame(frame: &[u8]) -> Result<(&[u8], bool)> {
+    match frame.first() {
+        Some(&crate::mbus::START) => {
+            let frame = crate::mbus::Frame::parse(frame)?;
+            Ok((frame.information, frame.segmented))
+        }
+        _ => {
+            let frame = crate::hdlc::Frame::parse(frame)?;
+            Ok((frame.information, frame.segmented))
         }
     }
 }
 
-/// A single readout.
-pub struct Readout {
-    pub(crate) buffer: [u8; 2048],
+/// Reassembles APDUs from HDLC or M-Bus frames, one byte at a time.
+#[cfg(feature = "embedded-io-async")]
+struct Assembler<const N: usize> {
+    frame: [u8; N],
+    frame_pos: usize,
+    apdu: [u8; N],
+    apdu_len: usize,
 }
 
-impl Readout {
-    /// Attempt to parse this as a [`Telegram`].
-    pub fn to_telegram(&self) -> Result<Telegram<'_>> {
-        let buffer = core::str::from_utf8(&self.buffer).map_err(|_| Error::InvalidFormat)?;
-        let end = buffer.find('!').ok_or(Error::InvalidFormat)?;
-        let (buffer, postfix) = buffer.split_at(end + 1);
-        let received_checksum =
-            u16::from_str_radix(postfix.get(..4).ok_or(Error::InvalidFormat)?, 16)
-                .map_err(|_| Error::InvalidFormat)?;
-        let checksum = crc16::State::<crc16::ARC>::calculate(buffer.as_bytes());
+#[cfg(feature = "embedded-io-async")]
+impl<const N: usize> Assembler<N> {
+    fn new() -> Self {
+        Self {
+            frame: [0; N],
+            frame_pos: 0,
+            apdu: [0; N],
+            apdu_len: 0,
+        }
+    }
 
-        if received_checksum != checksum {
-            return Err(Error::Checksum);
+    /// Handle a single byte, returning `true` if it completed an APDU.
+    fn push(&mut self, b: u8) -> bool {
+        if self.frame_pos == 0 && b != crate::hdlc::FLAG && b != crate::mbus::START {
+            return false;
         }
 
-        let (header, body) = buffer.split_once("\r\n\r\n").ok_or(Error::InvalidFormat)?;
+        self.frame[self.frame_pos] = b;
+        self.frame_pos += 1;
 
-        Ok(Telegram {
-            checksum,
-            flag_id: header.get(1..4).ok_or(Error::InvalidFormat)?,
-            identification: header.get(5..).ok_or(Error::InvalidFormat)?,
-            object_buffer: body
-                .get(..body.len().checked_sub(3).ok_or(Error::InvalidFormat)?)
-                .ok_or(Error::InvalidFormat)?,
-        })
+        let len = match dlms_frame_len(&self.frame[..self.frame_pos]) {
+            Ok(None) => return false,
+            Ok(Some(len)) if len <= N => len,
+            Ok(Some(_)) => {
+                // buffer overflow
+                self.frame_pos = 0;
+                self.apdu_len = 0;
+                return false;
+            }
+            Err(_) => {
+                // e.g. a closing flag followed by an opening one, so the
+                // current byte might start the next frame
+                let restart = self.frame_pos > 1;
+                self.frame_pos = 0;
+                return restart && self.push(b);
+            }
+        };
+
+        if self.frame_pos < len {
+            return false;
+        }
+
+        self.frame_pos = 0;
+        let Ok((information, segmented)) = parse_dlms_frame(&self.frame[..len]) else {
+            self.apdu_len = 0;
+            return false;
+        };
+
+        let end = self.apdu_len + information.len();
+        if end > N {
+            self.apdu_len = 0;
+            return false;
+        }
+
+        self.apdu[self.apdu_len..end].copy_from_slice(information);
+        self.apdu_len = end;
+        !segmented
     }
 }
 
-/// A single telegram.
-pub struct Telegram<'a> {
-    /// CRC16 checksum.
-    pub checksum: u16,
-    /// 3-letter [FLAG ID](https://www.dlms.com/eng/flag-id-list-44143.shtml)
-    /// identifying the manufacturer.
-    pub flag_id: &'a str,
-    /// Power meter ID.
-    pub identification: &'a str,
-    object_buffer: &'a str,
+/// A reader for binary meters pushing DLMS/COSEM data notifications, framed
+/// either by HDLC or by M-Bus long frames.
+///
+/// This is the binary counterpart of [`AsyncReader`]. The framing is detected
+/// from the first byte of each frame, frames with invalid checksums are
+/// skipped, and segmented APDUs are reassembled in a buffer of `N` bytes.
+#[cfg(feature = "embedded-io-async")]
+pub struct DlmsReader<R, const N: usize = DEFAULT_BUFFER_SIZE> {
+    reader: R,
+    assembler: Assembler<N>,
+    /// Whether the assembler holds a complete APDU.
+    complete: bool,
 }
 
-impl<'a> Telegram<'a> {
-    /// Iterator of the data containedby the telegram.
-    pub fn objects(&self) -> impl Iterator<Item = Result<Object>> + 'a {
-        self.object_buffer.lines().map(Object::from_str)
+#[cfg(feature = "embedded-io-async")]
+impl<R> DlmsReader<R>
+where
+    R: BufRead,
+{
+    /// Construct a new DlmsReader from a byte reader.
+    pub fn new(reader: R) -> Self {
+        Self::new_sized(reader)
     }
 }
 
-#[cfg(test)]
-mod tests {
-    use super::Reader;
+#[cfg(feature = "tokio-io")]
+impl<R> DlmsReader<embedded_io_adapters::tokio_1::FromTokio<R>>
+where
+    embedded_io_adapters::tokio_1::FromTokio<R>: BufRead,
+{
+    /// Construct a new DlmsReader from a `tokio::io::AsyncBufRead`.
+    pub fn from_tokio(reader: R) -> Self {
+        Self::new(embedded_io_adapters::tokio_1::FromTokio::new(reader))
+    }
+}
 
-    #[test]
-    fn ellevio() {
-        let bytes = include_bytes!("../test/ell.txt");
-        let mut reader = Reader::new(bytes.iter().cloned());
-        let readout = reader.next().unwrap();
-        let telegram = readout.to_telegram().unwrap();
+#[cfg(feature = "futures-io")]
+impl<R> DlmsReader<embedded_io_adapters::futures_03::FromFutures<R>>
+where
+    embedded_io_adapters::futures_03::FromFutures<R>: BufRead,
+{
+    /// Construct a new DlmsReader from a `futures::io::AsyncBufRead`.
+    pub fn from_futures(reader: R) -> Self {
+        Self::new(embedded_io_adapters::futures_03::FromFutures::new(reader))
+    }
+}
 
-        assert_eq!(telegram.checksum, 0x9ab5);
-        assert_eq!(telegram.flag_id, "ELL");
-        assert_eq!(telegram.identification, "\\253833635_A");
+#[cfg(feature = "embedded-io-async")]
+impl<R, const N: usize> DlmsReader<R, N>
+where
+    R: BufRead,
+{
+    /// Construct a new DlmsReader with buffers of `N` bytes.
+    pub fn new_sized(reader: R) -> Self {
+        Self {
+            reader,
+            assembler: Assembler::new(),
+            complete: false,
+        }
+    }
 
-        for obj in telegram.objects() {
-            obj.unwrap();
+    /// Read until a complete APDU has been assembled, returning `false` if the
+    /// reader is exhausted first.
+    async fn fill(&mut self) -> Result<bool, R::Error> {
+        if self.complete {
+            self.assembler.apdu_len = 0;
+            self.complete = false;
         }
 
-        assert!(reader.next().is_none());
+        loop {
+            let buf = self.reader.fill_buf().await?;
+            let n = buf.len();
+
+            if n == 0 {
+                return Ok(false);
+            }
+
+            for (i, &b) in buf.iter().enumerate() {
+                if self.assembler.push(b) {
+                    self.reader.consume(i + 1);
+                    self.complete = true;
+                    return Ok(true);
+                }
+            }
+
+            self.reader.consume(n);
+        }
     }
 
-    #[cfg(feature = "embedded-io-async")]
-    #[tokio::test]
-    async fn ellevio_async() {
-        let bytes = include_bytes!("../test/ell.txt");
-        let mut reader = super::AsyncReader::new(&bytes[..]);
-        let readout = reader.next_readout().await.unwrap().unwrap();
-        let telegram = readout.to_telegram().unwrap();
+    /// Read the next complete APDU, with segments reassembled, from the
+    /// reader.
+    ///
+    /// This function is cancel-safe.
+    pub async fn next_apdu(&mut self) -> Result<Option<&[u8]>, R::Error> {
+        if !self.fill().await? {
+            return Ok(None);
+        }
 
-        assert_eq!(telegram.checksum, 0x9ab5);
-        assert_eq!(telegram.flag_id, "ELL");
-        assert_eq!(telegram.identification, "\\253833635_A");
+        Ok(Some(&self.assembler.apdu[..self.assembler.apdu_len]))
+    }
 
-        for obj in telegram.objects() {
-            obj.unwrap();
+    /// Read and parse the next data notification from the reader.
+    ///
+    /// This function is cancel-safe.
+    pub async fn next_notification(
+        &mut self,
+    ) -> Result<Option<crate::hdlc::Notification<'_>>, ReadError<R::Error>> {
+        match self.next_apdu().await.map_err(ReadError::Io)? {
+            Some(apdu) => Ok(Some(crate::hdlc::Notification::parse(apdu)?)),
+            None => Ok(None),
         }
+    }
+}
 
-        assert!(reader.next_readout().await.unwrap().is_none());
+/// A blocking reader for the raw UART output of a power meter, driven by an
+/// [`embedded_io::BufRead`] implementation.
+///
+/// This is the blocking counterpart of [`AsyncReader`], for projects without
+/// an async executor.
+#[cfg(feature = "embedded-io")]
+pub struct BlockingReader<R, const N: usize = DEFAULT_BUFFER_SIZE> {
+    reader: R,
+    buffer: Buffer<N>,
+}
+
+#[cfg(feature = "embedded-io")]
+impl<R> BlockingReader<R>
+where
+    R: embedded_io::BufRead,
+{
+    /// Construct a new BlockingReader from a byte reader.
+    pub fn new(reader: R) -> Self {
+        Self::new_sized(reader)
+    }
+}
+
+#[cfg(feature = "embedded-io")]
+impl<R, const N: usize> BlockingReader<R, N>
+where
+    R: embedded_io::BufRead,
+{
+    /// Construct a new BlockingReader with a buffer of `N` bytes.
+    pub fn new_sized(reader: R) -> Self {
+        Self {
+            reader,
+            buffer: Buffer::new(),
+        }
+    }
+
+    /// Read telegrams that end with a bare `!` instead of a checksum, as sent
+    /// by older DSMR 2.2 and 3.0 meters.
+    ///
+    /// See [`Reader::without_checksum`].
+    pub fn without_checksum(mut self) -> Self {
+        self.buffer.checksum = false;
+        self
+    }
+
+    /// Read until the buffer holds a complete telegram, returning `false` if
+    /// the reader is exhausted first.
+    fn fill(&mut self) -> Result<bool, R::Error> {
+        loop {
+            let buf = self.reader.fill_buf()?;
+            if buf.is_empty() {
+                return Ok(false);
+            }
+
+            let n = self.buffer.feed(buf);
+            self.reader.consume(n);
+            if self.buffer.complete {
+                return Ok(true);
+            }
+        }
+    }
+
+    /// Read the next readout from the reader, blocking until it is complete.
+    pub fn next_readout(&mut self) -> Result<Option<Readout<N>>, R::Error> {
+        if !self.fill()? {
+            return Ok(None);
+        }
+
+        Ok(Some(self.buffer.readout()))
+    }
+
+    /// Read and parse the next telegram from the reader.
+    ///
+    /// Like [`AsyncReader::next_telegram`], the telegram is parsed in place
+    /// without copying the buffer.
+    pub fn next_telegram(&mut self) -> Result<Option<Telegram<'_>>, ReadError<R::Error>> {
+        if !self.fill().map_err(ReadError::Io)? {
+            return Ok(None);
+        }
+
+        Ok(Some(self.buffer.to_telegram()?))
+    }
+}
+
+/// Adapter implementing [`embedded_io::BufRead`] for a [`std::io::BufRead`].
+#[cfg(feature = "std")]
+struct FromStd<R>(R);
+
+#[cfg(feature = "std")]
+impl<R> embedded_io::ErrorType for FromStd<R> {
+    type Error = std::io::Error;
+}
+
+#[cfg(feature = "std")]
+impl<R: std::io::BufRead> embedded_io::BufRead for FromStd<R> {
+    fn fill_buf(&mut self) -> Result<&[u8], Self::Error> {
+        // retry reads interrupted by a signal
+        loop {
+            match self.0.fill_buf() {
+                Err(e) if e.kind() == std::io::ErrorKind::Interrupted => continue,
+                Err(e) => return Err(e),
+                Ok(_) => break,
+            }
+        }
+
+        self.0.fill_buf()
+    }
+
+    fn consume(&mut self, amt: usize) {
+        self.0.consume(amt)
+    }
+}
+
+/// A reader for the raw UART output of a power meter, driven by a
+/// [`std::io::BufRead`] such as a serial port or a captured file.
+///
+/// Unbuffered readers should be wrapped in a [`std::io::BufReader`]. Like
+/// [`BlockingReader`], telegrams that overflow the buffer are skipped.
+///
+/// ```
+/// let file = &include_bytes!("../test/ell.txt")[..];
+/// let mut reader = han::IoReader::new(file);
+///
+/// let readout = reader.next_readout()?.unwrap();
+/// assert_eq!(readout.to_telegram().unwrap().checksum, 0x9ab5);
+/// assert!(reader.next_readout()?.is_none());
+/// # Ok::<(), std::io::Error>(())
+/// ```
+#[cfg(feature = "std")]
+pub struct IoReader<R, const N: usize = DEFAULT_BUFFER_SIZE>(BlockingReader<FromStd<R>, N>);
+
+#[cfg(feature = "std")]
+impl<R: std::io::BufRead> IoReader<R> {
+    /// Construct a new IoReader from a buffered reader.
+    pub fn new(reader: R) -> Self {
+        Self::new_sized(reader)
+    }
+}
+
+#[cfg(feature = "std")]
+impl<R: std::io::BufRead, const N: usize> IoReader<R, N> {
+    /// Construct a new IoReader with a buffer of `N` bytes.
+    pub fn new_sized(reader: R) -> Self {
+        Self(BlockingReader::new_sized(FromStd(reader)))
+    }
+
+    /// Read telegrams that end with a bare `!` instead of a checksum, as sent
+    /// by older DSMR 2.2 and 3.0 meters.
+    ///
+    /// See [`Reader::without_checksum`].
+    pub fn without_checksum(self) -> Self {
+        Self(self.0.without_checksum())
+    }
+
+    /// Read the next readout, returning `None` at EOF.
+    pub fn next_readout(&mut self) -> std::io::Result<Option<Readout<N>>> {
+        self.0.next_readout()
+    }
+
+    /// Read and parse the next telegram, returning `None` at EOF.
+    pub fn next_telegram(&mut self) -> Result<Option<Telegram<'_>>, ReadError<std::io::Error>> {
+        self.0.next_telegram()
+    }
+}
+
+#[cfg(feature = "std")]
+impl<R: std::io::BufRead, const N: usize> Iterator for IoReader<R, N> {
+    type Item = std::io::Result<Readout<N>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.next_readout().transpose()
+    }
+}
+
+/// A non-blocking reader for the raw UART output of a power meter, driven by
+/// an [`embedded_hal_nb::serial::Read`] implementation.
+#[cfg(feature = "embedded-hal-nb")]
+pub struct NbReader<S, const N: usize = DEFAULT_BUFFER_SIZE> {
+    serial: S,
+    buffer: Buffer<N>,
+}
+
+#[cfg(feature = "embedded-hal-nb")]
+impl<S> NbReader<S>
+where
+    S: serial::Read<u8>,
+{
+    /// Construct a new NbReader from a serial port.
+    pub fn new(serial: S) -> Self {
+        Self::new_sized(serial)
+    }
+}
+
+#[cfg(feature = "embedded-hal-nb")]
+impl<S, const N: usize> NbReader<S, N>
+where
+    S: serial::Read<u8>,
+{
+    /// Construct a new NbReader with a buffer of `N` bytes.
+    pub fn new_sized(serial: S) -> Self {
+        Self {
+            serial,
+            buffer: Buffer::new(),
+        }
+    }
+
+    /// Read telegrams that end with a bare `!` instead of a checksum, as sent
+    /// by older DSMR 2.2 and 3.0 meters.
+    ///
+    /// See [`Reader::without_checksum`].
+    pub fn without_checksum(mut self) -> Self {
+        self.buffer.checksum = false;
+        self
+    }
+
+    /// Read as many bytes as are available, returning the next readout once
+    /// it is complete.
+    ///
+    /// Returns [`nb::Error::WouldBlock`] if the serial port runs out of bytes
+    /// before a complete telegram has been read. The partial telegram is kept
+    /// and reading resumes on the next call. `Ok(None)` is returned if a
+    /// telegram was discarded because it overflowed the buffer.
+    pub fn next_readout(&mut self) -> nb::Result<Option<Readout<N>>, S::Error> {
+        loop {
+            match self.buffer.push(self.serial.read()?) {
+                Push::Pending => {}
+                Push::Complete => return Ok(Some(self.buffer.readout())),
+                Push::Overflow => return Ok(None),
+            }
+        }
+    }
+}
+
+/// Compute the CRC16 checksum of a telegram, as written in hexadecimal after
+/// the `!`.
+///
+/// The checksum covers everything from the leading `/` up to and including
+/// the first `!`. Anything after the `!` is ignored, so a complete telegram
+/// may be passed as is.
+///
+/// ```
+/// let crc = han::telegram_crc(b"/ELL5\\253833635_A\r\n\r\n!");
+/// assert_eq!(crc, 0x227b);
+/// ```
+pub fn telegram_crc(telegram: &[u8]) -> u16 {
+    let end = telegram
+        .iter()
+        .position(|&b| b == b'!')
+        .map_or(telegram.len(), |i| i + 1);
+    crc16::State::<crc16::ARC>::calculate(&telegram[..end])
+}
+
+/// A single readout, held in a buffer of `N` bytes.
+pub struct Readout<const N: usize = DEFAULT_BUFFER_SIZE> {
+    pub(crate) buffer: [u8; N],
+    /// CRC computed while the readout was read, if any.
+    pub(crate) crc: Option<u16>,
+    /// Whether the readout ends with a checksum to verify.
+    pub(crate) verify: bool,
+}
+
+impl<const N: usize> Readout<N> {
+    /// The CRC16 checksum of the readout, without parsing it.
+    ///
+    /// This is cheaper than [`Readout::to_telegram`] and can be used to skip
+    /// identical consecutive readouts. The readers compute the checksum as
+    /// bytes arrive, so this does not walk the buffer again. The received
+    /// checksum is not verified.
+    pub fn checksum(&self) -> Result<u16> {
+        match self.crc {
+            Some(crc) => Ok(crc),
+            None => {
+                let (frame, _) = split_frame(&self.buffer)?;
+                Ok(telegram_crc(frame))
+            }
+        }
+    }
+
+    /// Attempt to parse this as a [`Telegram`].
+    pub fn to_telegram(&self) -> Result<Telegram<'_>> {
+        parse_buffer(&self.buffer, self.crc, self.verify)
+    }
+}
+
+/// A single readout backed by a [`Vec`], as returned by
+/// [`Reader::next_vec`].
+///
+/// The other readers only return fixed-size [`Readout`]s.
+#[cfg(feature = "alloc")]
+pub struct VecReadout {
+    pub(crate) buffer: Vec<u8>,
+    crc: u16,
+    verify: bool,
+}
+
+#[cfg(feature = "alloc")]
+impl VecReadout {
+    /// Compute the CRC16 checksum of the readout without parsing it.
+    ///
+    /// See [`Readout::checksum`].
+    pub fn checksum(&self) -> Result<u16> {
+        Ok(self.crc)
+    }
+
+    /// Attempt to parse this as a [`Telegram`].
+    pub fn to_telegram(&self) -> Result<Telegram<'_>> {
+        parse_buffer(&self.buffer, Some(self.crc), self.verify)
+    }
+}
+
+/// An owned copy of a [`Telegram`] with its objects parsed, as returned by
+/// [`Telegram::to_owned_telegram`], e.g. to store it or send it elsewhere.
+#[cfg(feature = "alloc")]
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct OwnedTelegram {
+    /// CRC16 checksum.
+    pub checksum: u16,
+    /// 3-letter FLAG ID identifying the manufacturer.
+    pub flag_id: alloc::string::String,
+    /// The baud rate/switching character following the flag id, if present.
+    pub baud_indicator: Option<char>,
+    /// Power meter ID.
+    pub identification: alloc::string::String,
+    /// The objects with recognized references.
+    pub objects: Vec<Object>,
+}
+
+/// Split a buffer into the frame (from `/` up to and including `!`) and the
+/// four hexadecimal checksum characters that follow it.
+fn split_frame(buffer: &[u8]) -> Result<(&[u8], &[u8])> {
+    let end = buffer
+        .iter()
+        .position(|&b| b == b'!')
+        .ok_or(Error::InvalidFormat)?;
+    let (frame, postfix) = buffer.split_at(end + 1);
+
+    Ok((frame, postfix.get(..4).ok_or(Error::InvalidFormat)?))
+}
+
+/// Parse a buffer starting with a complete telegram, whose CRC is computed
+/// unless already known. The checksum following the frame is only read if
+/// `verify` is set.
+fn parse_buffer(buffer: &[u8], crc: Option<u16>, verify: bool) -> Result<Telegram<'_>> {
+    if !verify {
+        let end = buffer
+            .iter()
+            .position(|&b| b == b'!')
+            .ok_or(Error::InvalidFormat)?;
+        let frame = core::str::from_utf8(&buffer[..=end]).map_err(|_| Error::InvalidFormat)?;
+        return Telegram::from_frame(frame, None, crc);
+    }
+
+    let (frame, postfix) = split_frame(buffer)?;
+    let frame = core::str::from_utf8(frame).map_err(|_| Error::InvalidFormat)?;
+    let postfix = core::str::from_utf8(postfix).map_err(|_| Error::InvalidFormat)?;
+    Telegram::from_frame(frame, Some(postfix), crc)
+}
+
+/// Split the header (`/XXXZ...`) into the flag id, the optional baud rate
+/// character `Z` and the identification.
+fn split_header(header: &str) -> Option<(&str, Option<char>, &str)> {
+    let flag_id = header.get(1..4)?;
+    let rest = header.get(4..)?;
+    let (baud, identification) = match rest.as_bytes() {
+        [z] if z.is_ascii_digit() => (Some(char::from(*z)), &rest[1..]),
+        [z, next, ..] if z.is_ascii_digit() && !next.is_ascii_digit() => {
+            (Some(char::from(*z)), &rest[1..])
+        }
+        _ => (None, rest),
+    };
+
+    Some((flag_id, baud, identification))
+}
+
+/// Strip the trailing `!` and the line ending preceding it, if any, from the
+/// body.
+fn trim_body(body: &str) -> Option<&str> {
+    let body = body.strip_suffix('!')?;
+    Some(
+        body.strip_suffix("\r\n")
+            .or_else(|| body.strip_suffix('\n'))
+            .unwrap_or(body),
+    )
+}
+
+/// A single telegram.
+#[cfg_attr(feature = "defmt-03", derive(defmt::Format))]
+pub struct Telegram<'a> {
+    /// CRC16 checksum.
+    pub checksum: u16,
+    /// 3-letter [FLAG ID](https://www.dlms.com/eng/flag-id-list-44143.shtml)
+    /// identifying the manufacturer.
+    pub flag_id: &'a str,
+    /// The baud rate/switching character following the flag id (`5` in
+    /// `/ELL5\253833635_A`), if present.
+    pub baud_indicator: Option<char>,
+    /// Power meter ID.
+    pub identification: &'a str,
+    object_buffer: &'a str,
+    #[cfg_attr(feature = "defmt-03", defmt(Debug2Format))]
+    options: ParseOptions,
+}
+
+impl<'a> Telegram<'a> {
+    /// Parse a complete telegram, from the leading `/` through the checksum,
+    /// without going through a [`Reader`].
+    ///
+    /// Anything following the four checksum characters is ignored.
+    ///
+    /// ```
+    /// let s = include_str!("../test/ell.txt");
+    /// let telegram = han::Telegram::parse(s)?;
+    /// assert_eq!(telegram.flag_id, "ELL");
+    /// # Ok::<(), han::Error>(())
+    /// ```
+    pub fn parse(s: &'a str) -> Result<Self> {
+        let end = s.find('!').ok_or(Error::InvalidFormat)?;
+        let (frame, postfix) = s.split_at(end + 1);
+        Self::from_frame(
+            frame,
+            Some(postfix.get(..4).ok_or(Error::InvalidFormat)?),
+            None,
+        )
+    }
+
+    /// Parse a telegram ending with a bare `!`, as sent by older DSMR 2.2 and
+    /// 3.0 meters, without verifying any checksum.
+    ///
+    /// [`Telegram::checksum`] is still computed from the contents. Anything
+    /// following the `!` is ignored.
+    pub fn parse_without_checksum(s: &'a str) -> Result<Self> {
+        let end = s.find('!').ok_or(Error::InvalidFormat)?;
+        Self::from_frame(&s[..=end], None, None)
+    }
+
+    /// Parse the frame (from `/` up to and including `!`), verifying it
+    /// against the hexadecimal checksum, if any. The CRC of the frame is
+    /// computed unless passed as `crc`.
+    fn from_frame(frame: &'a str, checksum: Option<&str>, crc: Option<u16>) -> Result<Self> {
+        if !frame.starts_with('/') {
+            return Err(Error::InvalidFormat);
+        }
+
+        let computed = crc.unwrap_or_else(|| telegram_crc(frame.as_bytes()));
+
+        if let Some(checksum) = checksum {
+            let received_checksum =
+                u16::from_str_radix(checksum, 16).map_err(|_| Error::InvalidFormat)?;
+
+            if received_checksum != computed {
+                return Err(Error::Checksum);
+            }
+        }
+
+        let checksum = computed;
+
+        let (header, body) = frame.split_once("\r\n\r\n").ok_or(Error::InvalidFormat)?;
+        let (flag_id, baud_indicator, identification) =
+            split_header(header).ok_or(Error::InvalidFormat)?;
+
+        Ok(Telegram {
+            checksum,
+            flag_id,
+            baud_indicator,
+            identification,
+            object_buffer: trim_body(body).ok_or(Error::InvalidFormat)?,
+            options: ParseOptions::new(),
+        })
+    }
+
+    /// Parse the objects of the telegram with `options`, e.g. for meters
+    /// that leave out the DST indicator of timestamps.
+    ///
+    /// ```
+    /// # #[cfg(feature = "time")] {
+    /// use han::{Object, ParseOptions, Telegram};
+    /// use time::macros::{datetime, offset};
+    ///
+    /// let telegram = Telegram::parse_without_checksum("/XYZ5\r\n\r\n0-0:1.0.0(220717231648)\r\n!")?
+    ///     .with_options(ParseOptions::new().default_offset(offset!(+1)));
+    /// let obj = telegram.objects().next().unwrap()?;
+    /// assert_eq!(obj, Object::DateTime(datetime!(2022-07-17 23:16:48 +1)));
+    /// # }
+    /// # Ok::<(), han::Error>(())
+    /// ```
+    pub fn with_options(mut self, options: ParseOptions) -> Self {
+        self.options = options;
+        self
+    }
+
+    /// Iterator of the data containedby the telegram.
+    pub fn objects(&self) -> impl Iterator<Item = Result<Object>> + 'a {
+        let options = self.options;
+        self.object_buffer
+            .lines()
+            .map(move |line| Object::parse_with(line, &options))
+    }
+
+    /// Like [`Telegram::objects`], but with the line, [`Obis`](crate::Obis)
+    /// reference and value of objects that could not be parsed.
+    ///
+    /// ```
+    /// use han::Telegram;
+    ///
+    /// let telegram = Telegram::parse_without_checksum(
+    ///     "/XYZ5\r\n\r\n1-0:1.8.0(00006136.936*kWh)\r\n1-0:32.7.0(230,1*V)\r\n!",
+    /// )?;
+    /// let e = telegram.objects_with_context().nth(1).unwrap().unwrap_err();
+    /// assert_eq!(e.line, 1);
+    /// assert_eq!(e.obis, Some(han::Obis(1, 0, 32, 7, 0)));
+    /// assert_eq!(e.value, "(230,1*V)");
+    /// # Ok::<(), han::Error>(())
+    /// ```
+    pub fn objects_with_context(
+        &self,
+    ) -> impl Iterator<Item = core::result::Result<Object, ParseError<'a>>> + 'a {
+        let options = self.options;
+        self.object_buffer
+            .lines()
+            .enumerate()
+            .map(move |(i, line)| {
+                Object::parse_with(line, &options).map_err(|error| {
+                    let start = line.find('(').unwrap_or(0);
+                    let obis = line[..start].parse::<crate::ObisFull>().ok();
+                    ParseError {
+                        line: i,
+                        obis: obis.map(|full| full.obis),
+                        value: &line[start..],
+                        error,
+                    }
+                })
+            })
+    }
+
+    /// The version of the P1 specification that the telegram claims to
+    /// follow, if any. Swedish and Norwegian meters usually leave it out.
+    ///
+    /// ```
+    /// use han::{P1Version, Telegram};
+    ///
+    /// let telegram = Telegram::parse(include_str!("../test/emucs.txt"))?;
+    /// assert_eq!(telegram.version(), Some(P1Version::Emucs(50217)));
+    /// # Ok::<(), han::Error>(())
+    /// ```
+    pub fn version(&self) -> Option<P1Version> {
+        self.lines()
+            .filter(|line| line.starts_with("1-3:0.2.8(") || line.starts_with("0-0:96.1.4("))
+            .find_map(|line| match Object::parse_with(line, &self.options) {
+                Ok(Object::Version(version)) => Some(version),
+                _ => None,
+            })
+    }
+
+    /// The maximum demand history of Belgian meters (`0-0:98.1.0`), if
+    /// included.
+    ///
+    /// ```
+    /// let telegram = han::Telegram::parse(include_str!("../test/emucs.txt"))?;
+    /// let history = telegram.demand_history().unwrap()?;
+    /// assert_eq!(history.len(), 3);
+    /// # Ok::<(), han::Error>(())
+    /// ```
+    pub fn demand_history(&self) -> Option<Result<DemandHistory<'a>>> {
+        self.lines()
+            .find(|line| line.starts_with("0-0:98.1.0("))
+            .map(DemandHistory::parse)
+    }
+
+    /// The log of long power failures of DSMR meters (`1-0:99.97.0`), if
+    /// included.
+    ///
+    /// ```
+    /// let telegram = han::Telegram::parse(include_str!("../test/dsmr5.txt"))?;
+    /// for event in telegram.power_failure_log().unwrap()? {
+    ///     println!("{:?}", event?);
+    /// }
+    /// # Ok::<(), han::Error>(())
+    /// ```
+    pub fn power_failure_log(&self) -> Option<Result<PowerFailureLog<'a>>> {
+        self.lines()
+            .find(|line| line.starts_with("1-0:99.97.0("))
+            .map(PowerFailureLog::parse)
+    }
+
+    /// Aggregate the objects into a [`State`](crate::state::State), failing
+    /// on the first object that cannot be parsed.
+    ///
+    /// ```
+    /// use han::{Direction, Power};
+    ///
+    /// let telegram = han::Telegram::parse(include_str!("../test/ell.txt"))?;
+    /// let state = telegram.to_state()?;
+    /// let energy = state.energy.get(Power::Active, Direction::FromGrid);
+    /// assert_eq!(energy, Some(6136.936));
+    /// # Ok::<(), han::Error>(())
+    /// ```
+    pub fn to_state(&self) -> Result<crate::state::State> {
+        crate::state::State::from_telegram(self)
+    }
+
+    /// The raw object lines of the telegram.
+    pub(crate) fn lines(&self) -> core::str::Lines<'a> {
+        self.object_buffer.lines()
+    }
+
+    /// Like [`Telegram::objects`], but along with the line each object was
+    /// parsed from.
+    pub(crate) fn objects_with_lines(
+        &self,
+    ) -> impl Iterator<Item = (Result<Object>, &'a str)> + 'a {
+        let options = self.options;
+        self.object_buffer
+            .lines()
+            .map(move |line| (Object::parse_with(line, &options), line))
+    }
+
+    /// Copy the telegram into an [`OwnedTelegram`], parsing the objects with
+    /// recognized references.
+    ///
+    /// ```
+    /// let telegram = han::Telegram::parse(include_str!("../test/ell.txt"))?;
+    /// let owned = telegram.to_owned_telegram()?;
+    /// assert_eq!(owned.flag_id, "ELL");
+    /// assert_eq!(owned.objects.len(), 27);
+    /// # Ok::<(), han::Error>(())
+    /// ```
+    #[cfg(feature = "alloc")]
+    pub fn to_owned_telegram(&self) -> Result<OwnedTelegram> {
+        Ok(OwnedTelegram {
+            checksum: self.checksum,
+            flag_id: self.flag_id.into(),
+            baud_indicator: self.baud_indicator,
+            identification: self.identification.into(),
+            objects: self.known_objects().collect::<Result<_>>()?,
+        })
+    }
+
+    /// Convert the telegram to a JSON object keyed by [`Obis`](crate::Obis)
+    /// reference, in the stable representation described in the
+    /// [`json`](crate::json#telegrams) module.
+    ///
+    /// ```
+    /// let telegram = han::Telegram::parse(include_str!("../test/ell.txt"))?;
+    /// let json = telegram.to_json();
+    /// assert_eq!(json["version"], 1);
+    /// assert_eq!(json["1-0:1.8.0"], 6136936);
+    /// # Ok::<(), han::Error>(())
+    /// ```
+    #[cfg(feature = "json")]
+    pub fn to_json(&self) -> serde_json::Value {
+        use serde_json::{Map, Value};
+
+        let mut map = Map::new();
+        map.insert("version".into(), crate::json::VERSION.into());
+        let mut unknown = Vec::new();
+
+        for line in self.object_buffer.lines() {
+            match Object::parse_with(line, &self.options) {
+                Ok(obj) => {
+                    let value = match obj {
+                        Object::DateTime(dt) => crate::json::timestamp(dt),
+                        Object::EquipmentId(text)
+                        | Object::TextMessage(text)
+                        | Object::MBusEquipmentId(_, text) => match text.as_str() {
+                            Some(s) => s.into(),
+                            None => text.as_bytes().into(),
+                        },
+                        obj => obj.scaled_value().into(),
+                    };
+                    map.insert(obj.obis().to_string(), value);
+                }
+                Err(Error::UnrecognizedReference) => {
+                    if let Some((obis, _)) = line.split_once('(') {
+                        unknown.push(Value::from(obis));
+                    }
+                }
+                Err(_) => {}
+            }
+        }
+
+        map.insert("unknown".into(), unknown.into());
+        map.into()
+    }
+
+    /// Returns `true` if the telegram contains no objects, as is the case
+    /// for keepalive telegrams sent by some meters.
+    pub fn is_empty(&self) -> bool {
+        self.object_buffer.is_empty()
+    }
+
+    /// Like [`Telegram::objects`], but skips objects with unrecognized
+    /// [`Obis`](crate::Obis) references. Malformed objects are still
+    /// reported.
+    pub fn known_objects(&self) -> impl Iterator<Item = Result<Object>> + 'a {
+        self.objects()
+            .filter(|o| !matches!(o, Err(Error::UnrecognizedReference)))
+    }
+
+    /// Like [`Telegram::objects`], but decodes objects with `decoder` first,
+    /// e.g. to support vendor-specific references.
+    pub fn objects_with<D: ObjectDecoder + 'a>(
+        &self,
+        decoder: D,
+    ) -> impl Iterator<Item = Result<Decoded<D::Output>>> + 'a {
+        let options = self.options;
+        self.object_buffer
+            .lines()
+            .map(move |line| crate::decoder::decode(&decoder, line, &options))
+    }
+
+    /// Like [`Telegram::objects`], but yields the raw values of objects with
+    /// unrecognized [`Obis`](crate::Obis) references instead of failing.
+    pub fn lenient_objects(&self) -> impl Iterator<Item = Result<LenientObject<'a>>> + 'a {
+        self.object_buffer.lines().map(LenientObject::parse)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Deciamps, Decivolts, Error, LenientObject, Line, Obis, Object};
+
+    use super::{split_header, telegram_crc, trim_body, Reader, Readout, Telegram};
+
+    #[test]
+    fn ellevio() {
+        let bytes = include_bytes!("../test/ell.txt");
+        let mut reader = Reader::new(bytes.iter().cloned());
+        let readout = reader.next().unwrap();
+        let telegram = readout.to_telegram().unwrap();
+
+        assert_eq!(telegram.checksum, 0x9ab5);
+        assert_eq!(telegram.flag_id, "ELL");
+        assert_eq!(telegram.baud_indicator, Some('5'));
+        assert_eq!(telegram.identification, "\\253833635_A");
+
+        for obj in telegram.objects() {
+            obj.unwrap();
+        }
+
+        assert!(reader.next().is_none());
+    }
+
+    #[test]
+    fn back_to_back() {
+        let bytes = include_bytes!("../test/ell.txt");
+        let without_crlf = &bytes[..bytes.len() - 2];
+        let mut reader = Reader::new(without_crlf.iter().chain(without_crlf).cloned());
+
+        for _ in 0..2 {
+            let telegram = reader.next().unwrap();
+            assert_eq!(telegram.to_telegram().unwrap().checksum, 0x9ab5);
+            assert_eq!(reader.bytes_skipped(), 0);
+        }
+
+        assert!(reader.next().is_none());
+    }
+
+    #[test]
+    fn bytes_skipped() {
+        let junk = b"1-0:71.7.0(001.5*A)\r\n!9AB5\r\n";
+        let bytes = include_bytes!("../test/ell.txt");
+        let mut reader = Reader::new(junk.iter().chain(bytes).cloned());
+        assert_eq!(reader.bytes_skipped(), 0);
+
+        reader.next().unwrap().to_telegram().unwrap();
+        assert_eq!(reader.bytes_skipped(), junk.len());
+
+        assert!(reader.next().is_none());
+        assert_eq!(reader.bytes_skipped(), 0); // trailing CRLF
+
+        let mut reader = Reader::new(b"\r\nxy\n/".iter().cloned());
+        assert!(reader.next().is_none());
+        assert_eq!(reader.bytes_skipped(), 3);
+    }
+
+    #[test]
+    fn buffer_size() {
+        let bytes = include_bytes!("../test/ell.txt");
+
+        let mut reader = Reader::<_, 1024>::new_sized(bytes.iter().cloned());
+        let readout: Readout<1024> = reader.next().unwrap();
+        assert_eq!(readout.to_telegram().unwrap().checksum, 0x9ab5);
+
+        let mut reader = Reader::<_, 256>::new_sized(bytes.iter().cloned());
+        assert!(reader.next().is_none());
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn next_vec() {
+        let bytes = include_bytes!("../test/ell.txt");
+        let mut reader = Reader::<_, 256>::new_sized(bytes.iter().cloned());
+        let readout = reader.next_vec().unwrap();
+
+        assert_eq!(readout.buffer.len(), bytes.len() - 2);
+        assert_eq!(readout.checksum().unwrap(), 0x9ab5);
+        assert_eq!(readout.to_telegram().unwrap().checksum, 0x9ab5);
+        assert!(reader.next_vec().is_none());
+    }
+
+    #[test]
+    fn try_next() {
+        let bytes = include_bytes!("../test/ell.txt");
+        let stream = [&bytes[..], &bytes[..], &bytes[..200]].concat();
+        let mut reader = Reader::<_, 1024>::new_sized(stream.iter().cloned());
+
+        assert_eq!(
+            reader.try_next().unwrap().unwrap().checksum().unwrap(),
+            0x9ab5
+        );
+        assert_eq!(
+            reader.try_next().unwrap().unwrap().checksum().unwrap(),
+            0x9ab5
+        );
+        assert_eq!(reader.bytes_skipped(), 0);
+        assert!(matches!(reader.try_next(), Some(Err(Error::UnexpectedEof))));
+        assert!(reader.try_next().is_none());
+
+        let mut reader = Reader::<_, 256>::new_sized(stream.iter().cloned());
+        assert!(matches!(
+            reader.try_next(),
+            Some(Err(Error::BufferOverflow))
+        ));
+    }
+
+    #[test]
+    fn without_checksum() {
+        let bytes = include_bytes!("../test/ell.txt");
+        let legacy = [&bytes[..bytes.len() - 6], b"\r\n"].concat();
+        let stream = [&legacy[..], &legacy[..]].concat();
+
+        let mut reader = Reader::new(stream.iter().cloned()).without_checksum();
+        for _ in 0..2 {
+            let readout = reader.next().unwrap();
+            let telegram = readout.to_telegram().unwrap();
+            assert_eq!(telegram.checksum, 0x9ab5);
+            assert_eq!(telegram.objects().count(), 27);
+            assert_eq!(reader.bytes_skipped(), 0);
+        }
+        assert!(reader.next().is_none());
+
+        let s = core::str::from_utf8(&legacy).unwrap();
+        assert!(Telegram::parse(s).is_err());
+        assert_eq!(
+            Telegram::parse_without_checksum(s).unwrap().checksum,
+            0x9ab5
+        );
+    }
+
+    #[test]
+    fn without_baud_character() {
+        let bytes = include_bytes!("../test/ell-nobaud.txt");
+        let readout = Reader::new(bytes.iter().cloned()).next().unwrap();
+        let telegram = readout.to_telegram().unwrap();
+
+        assert_eq!(telegram.flag_id, "ELL");
+        assert_eq!(telegram.baud_indicator, None);
+        assert_eq!(telegram.identification, "\\253833635_A");
+    }
+
+    #[test]
+    fn parse_str() {
+        let s = include_str!("../test/ell.txt");
+        let telegram = Telegram::parse(s).unwrap();
+
+        assert_eq!(telegram.checksum, 0x9ab5);
+        assert_eq!(telegram.flag_id, "ELL");
+        assert_eq!(telegram.identification, "\\253833635_A");
+        assert_eq!(telegram.objects().count(), 27);
+
+        for obj in telegram.objects() {
+            obj.unwrap();
+        }
+
+        assert!(matches!(
+            Telegram::parse(&s[1..]),
+            Err(Error::InvalidFormat)
+        ));
+        assert!(matches!(
+            Telegram::parse(&s[..s.len() - 4]),
+            Err(Error::InvalidFormat)
+        ));
+        assert!(matches!(
+            Telegram::parse(&s.replace("9AB5", "9AB6")),
+            Err(Error::Checksum)
+        ));
+    }
+
+    #[test]
+    fn known_objects() {
+        let bytes = include_bytes!("../test/ell-unknown.txt");
+        let readout = Reader::new(bytes.iter().cloned()).next().unwrap();
+        let telegram = readout.to_telegram().unwrap();
+
+        assert_eq!(telegram.objects().count(), 28);
+        assert!(telegram
+            .objects()
+            .any(|o| matches!(o, Err(Error::UnrecognizedReference))));
+
+        assert_eq!(telegram.known_objects().count(), 27);
+        assert_eq!(
+            telegram
+                .known_objects()
+                .filter(|o| matches!(o, Err(Error::InvalidFormat)))
+                .count(),
+            1
+        );
+        assert!(!telegram
+            .known_objects()
+            .any(|o| matches!(o, Err(Error::UnrecognizedReference))));
+    }
+
+    #[test]
+    fn lenient_objects() {
+        let bytes = include_bytes!("../test/ell-unknown.txt");
+        let readout = Reader::new(bytes.iter().cloned()).next().unwrap();
+        let telegram = readout.to_telegram().unwrap();
+
+        let objects = telegram.lenient_objects().collect::<std::vec::Vec<_>>();
+        assert_eq!(objects.len(), 28);
+        assert_eq!(
+            objects.iter().filter(|o| o.is_err()).count(),
+            1,
+            "malformed objects are still reported"
+        );
+        assert!(objects.iter().any(|o| matches!(
+            o,
+            Ok(LenientObject::Other(obis, "(ABCDEF)")) if obis.obis == Obis(0, 0, 96, 99, 0)
+        )));
+    }
+
+    #[test]
+    fn crc() {
+        let bytes = include_bytes!("../test/ell.txt");
+        assert_eq!(telegram_crc(bytes), 0x9ab5);
+        assert_eq!(telegram_crc(&bytes[..bytes.len() - 6]), 0x9ab5);
+        assert_ne!(telegram_crc(&bytes[..bytes.len() - 7]), 0x9ab5);
+    }
+
+    #[test]
+    fn objects_with_context() {
+        let telegram = Telegram::parse(include_str!("../test/ell-badline.txt")).unwrap();
+        let mut errors = telegram.objects_with_context().filter_map(Result::err);
+
+        let e = errors.next().unwrap();
+        assert!(errors.next().is_none());
+        assert_eq!(
+            (e.line, e.obis, e.value),
+            (21, Some(Obis(1, 0, 32, 7, 0)), "(233.8V)")
+        );
+        assert!(matches!(e.error, Error::InvalidFormat));
+        assert_eq!(
+            std::format!("{e}"),
+            "line 21 (1-0:32.7.0): invalid format in `(233.8V)`"
+        );
+    }
+
+    #[cfg(feature = "json")]
+    #[test]
+    fn to_json() {
+        let bytes = include_bytes!("../test/ell-unknown.txt");
+        let readout = Reader::new(bytes.iter().cloned()).next().unwrap();
+        let json = readout.to_telegram().unwrap().to_json();
+
+        assert_eq!(json["0-0:1.0.0"], 1666452524);
+        assert_eq!(json["1-0:1.8.0"], 6136936);
+        assert_eq!(json["1-0:4.7.0"], 206);
+        assert_eq!(json["1-0:72.7.0"], 2355);
+        assert_eq!(json["1-0:31.7.0"], 22);
+        assert!(json.get("1-0:32.7.0").is_none()); // malformed
+        assert_eq!(json["unknown"], serde_json::json!(["0-0:96.99.0"]));
+        assert_eq!(json.as_object().unwrap().len(), 28);
+    }
+
+    #[cfg(all(feature = "serde", feature = "std"))]
+    #[test]
+    fn serde() {
+        let telegram = Telegram::parse(include_str!("../test/ell.txt")).unwrap();
+        let owned = telegram.to_owned_telegram().unwrap();
+
+        let json = serde_json::to_value(&owned).unwrap();
+        assert_eq!(json["identification"], "\\253833635_A");
+        assert_eq!(
+            json["objects"][1],
+            serde_json::json!({ "Energy": ["FromGrid", { "Active": 6136936 }] })
+        );
+
+        assert_eq!(
+            serde_json::from_value::<super::OwnedTelegram>(json).unwrap(),
+            owned
+        );
+        assert_eq!(
+            serde_json::to_value(Obis(1, 0, 1, 8, 0)).unwrap(),
+            serde_json::json!([1, 0, 1, 8, 0])
+        );
+    }
+
+    #[test]
+    fn body_line_endings() {
+        use std::format;
+
+        let header = "/ELL5\\253833635_A\r\n\r\n";
+        for body in [
+            "1-0:32.7.0(233.8*V)\r\n1-0:31.7.0(002.2*A)\r\n!",
+            "1-0:32.7.0(233.8*V)\r\n1-0:31.7.0(002.2*A)\n!",
+            "1-0:32.7.0(233.8*V)\r\n1-0:31.7.0(002.2*A)!",
+        ] {
+            let frame = format!("{header}{body}");
+            let s = format!("{frame}{:04X}\r\n", telegram_crc(frame.as_bytes()));
+            let telegram = Telegram::parse(&s).unwrap();
+
+            let mut objects = telegram.objects();
+            assert_eq!(
+                objects.next().unwrap().unwrap(),
+                Object::Voltage(Line::L1, Decivolts(2338))
+            );
+            assert_eq!(
+                objects.next().unwrap().unwrap(),
+                Object::Current(Line::L1, Deciamps(22))
+            );
+            assert!(objects.next().is_none());
+        }
+
+        assert_eq!(trim_body("!"), Some(""));
+        assert_eq!(trim_body("\r\n"), None);
+    }
+
+    #[test]
+    fn empty() {
+        use std::format;
+
+        for frame in [
+            "/ELL5\\253833635_A\r\n\r\n!",
+            "/ELL5\\253833635_A\r\n\r\n\r\n!",
+        ] {
+            let s = format!("{frame}{:04X}", telegram_crc(frame.as_bytes()));
+            let telegram = Telegram::parse(&s).unwrap();
+            assert!(telegram.is_empty());
+            assert_eq!(telegram.objects().count(), 0);
+        }
+
+        let telegram = Telegram::parse(include_str!("../test/ell.txt")).unwrap();
+        assert!(!telegram.is_empty());
+    }
+
+    #[test]
+    fn checksum() {
+        let bytes = include_bytes!("../test/ell.txt");
+        let a = Reader::new(bytes.iter().cloned()).next().unwrap();
+        let b = Reader::new(bytes.iter().cloned()).next().unwrap();
+        assert_eq!(a.checksum().unwrap(), 0x9ab5);
+        assert_eq!(a.checksum().unwrap(), b.checksum().unwrap());
+
+        let mut modified = *bytes;
+        modified[50] = b'9';
+        let modified = Reader::new(modified.iter().cloned()).next().unwrap();
+        assert_ne!(modified.checksum().unwrap(), a.checksum().unwrap());
+
+        // computed from the buffer when not known
+        let mut unread = Readout {
+            buffer: [0; 2048],
+            crc: None,
+            verify: true,
+        };
+        unread.buffer[..bytes.len()].copy_from_slice(bytes);
+        assert_eq!(unread.checksum().unwrap(), 0x9ab5);
+
+        let empty = Readout {
+            buffer: [0; 2048],
+            crc: None,
+            verify: true,
+        };
+        assert!(matches!(empty.checksum(), Err(Error::InvalidFormat)));
+    }
+
+    #[test]
+    fn header() {
+        assert_eq!(
+            split_header("/ELL5\\253833635_A"),
+            Some(("ELL", Some('5'), "\\253833635_A"))
+        );
+        assert_eq!(
+            split_header("/ELL\\253833635_A"),
+            Some(("ELL", None, "\\253833635_A"))
+        );
+        assert_eq!(split_header("/ELL5"), Some(("ELL", Some('5'), "")));
+        // a leading number in the identification is not a baud rate character
+        assert_eq!(split_header("/ABC123"), Some(("ABC", None, "123")));
+        assert_eq!(split_header("/AB"), None);
+    }
+
+    #[cfg(feature = "embedded-hal-nb")]
+    #[test]
+    fn ellevio_nb() {
+        use embedded_hal_nb::{nb, serial};
+
+        /// Yields one byte at a time, blocking before every other byte.
+        struct Serial<'a> {
+            bytes: &'a [u8],
+            block: bool,
+        }
+
+        impl serial::ErrorType for Serial<'_> {
+            type Error = core::convert::Infallible;
+        }
+
+        impl serial::Read for Serial<'_> {
+            fn read(&mut self) -> nb::Result<u8, Self::Error> {
+                self.block = !self.block;
+                match self.bytes.split_first() {
+                    Some((&b, rest)) if !self.block => {
+                        self.bytes = rest;
+                        Ok(b)
+                    }
+                    _ => Err(nb::Error::WouldBlock),
+                }
+            }
+        }
+
+        let bytes = include_bytes!("../test/ell.txt");
+        let mut reader = super::NbReader::new(Serial {
+            bytes: &bytes[..],
+            block: false,
+        });
+
+        let mut blocked = 0;
+        let readout = loop {
+            match reader.next_readout() {
+                Ok(readout) => break readout.unwrap(),
+                Err(nb::Error::WouldBlock) => blocked += 1,
+                Err(nb::Error::Other(e)) => match e {},
+            }
+        };
+        assert!(blocked > 0);
+
+        let telegram = readout.to_telegram().unwrap();
+        assert_eq!(telegram.checksum, 0x9ab5);
+
+        for obj in telegram.objects() {
+            obj.unwrap();
+        }
+    }
+
+    #[cfg(feature = "embedded-io")]
+    #[test]
+    fn ellevio_blocking() {
+        let bytes = include_bytes!("../test/ell.txt");
+        let mut reader = super::BlockingReader::new(&bytes[..]);
+        let telegram = reader.next_telegram().unwrap().unwrap();
+
+        assert_eq!(telegram.checksum, 0x9ab5);
+        assert_eq!(telegram.objects().count(), 27);
+        assert!(reader.next_readout().unwrap().is_none());
+    }
+
+    #[cfg(feature = "embedded-io")]
+    #[test]
+    fn without_checksum_blocking() {
+        let bytes = include_bytes!("../test/ell.txt");
+        let legacy = [&bytes[..bytes.len() - 6], b"\r\n"].concat();
+        let stream = [&legacy[..], &legacy[..]].concat();
+
+        let mut reader = super::BlockingReader::new(&stream[..]).without_checksum();
+        for _ in 0..2 {
+            let telegram = reader.next_telegram().unwrap().unwrap();
+            assert_eq!(telegram.objects().count(), 27);
+        }
+        assert!(reader.next_readout().unwrap().is_none());
+
+        // a checksum would swallow the start of the next telegram
+        let mut reader = super::BlockingReader::new(&stream[..]);
+        assert!(reader.next_telegram().is_err());
+    }
+
+    #[cfg(feature = "embedded-io")]
+    #[test]
+    fn oversized_blocking() {
+        let bytes = include_bytes!("../test/oversized.txt");
+        let mut reader = super::BlockingReader::new(&bytes[..]);
+        let readout = reader.next_readout().unwrap().unwrap();
+
+        assert_eq!(readout.to_telegram().unwrap().checksum, 0x9ab5);
+        assert!(reader.next_readout().unwrap().is_none());
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn io_reader() {
+        use std::io::{BufReader, Read};
+
+        /// Yields at most 7 bytes per read.
+        struct Trickle<'a>(&'a [u8]);
+
+        impl Read for Trickle<'_> {
+            fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+                let n = self.0.len().min(buf.len()).min(7);
+                buf[..n].copy_from_slice(&self.0[..n]);
+                self.0 = &self.0[n..];
+                Ok(n)
+            }
+        }
+
+        let bytes = include_bytes!("../test/ell.txt");
+        let without_crlf = &bytes[..bytes.len() - 2];
+        let stream = [&bytes[300..], without_crlf, without_crlf].concat();
+        let reader = super::IoReader::new(BufReader::new(Trickle(&stream)));
+
+        let checksums = reader
+            .map(|readout| readout.unwrap().checksum().unwrap())
+            .collect::<std::vec::Vec<_>>();
+        assert_eq!(checksums, [0x9ab5, 0x9ab5]);
+    }
+
+    #[cfg(feature = "tokio-io")]
+    #[tokio::test]
+    async fn from_tokio() {
+        let bytes = include_bytes!("../test/ell.txt");
+        let mut reader = super::AsyncReader::from_tokio(&bytes[..]);
+
+        let readout = reader.next_readout().await.unwrap().unwrap();
+        assert_eq!(readout.checksum().unwrap(), 0x9ab5);
+        assert!(reader.next_readout().await.unwrap().is_none());
+    }
+
+    #[cfg(feature = "futures-io")]
+    #[tokio::test]
+    async fn from_futures() {
+        let bytes = include_bytes!("../test/ell.txt");
+        let mut reader = super::AsyncReader::from_futures(&bytes[..]);
+
+        let readout = reader.next_readout().await.unwrap().unwrap();
+        assert_eq!(readout.checksum().unwrap(), 0x9ab5);
+        assert!(reader.next_readout().await.unwrap().is_none());
+    }
+
+    #[cfg(feature = "embedded-io-async")]
+    #[tokio::test]
+    async fn ellevio_async() {
+        let bytes = include_bytes!("../test/ell.txt");
+        let mut reader = super::AsyncReader::new(&bytes[..]);
+        let readout = reader.next_readout().await.unwrap().unwrap();
+        let telegram = readout.to_telegram().unwrap();
+
+        assert_eq!(telegram.checksum, 0x9ab5);
+        assert_eq!(telegram.flag_id, "ELL");
+        assert_eq!(telegram.identification, "\\253833635_A");
+
+        for obj in telegram.objects() {
+            obj.unwrap();
+        }
+
+        assert!(reader.next_readout().await.unwrap().is_none());
+    }
+
+    #[cfg(feature = "embedded-io-async")]
+    #[tokio::test]
+    async fn oversized_async() {
+        use embedded_io_async::{BufRead, ErrorType, Read};
+
+        /// Yields at most `chunk` bytes per fill.
+        struct Chunked<'a> {
+            bytes: &'a [u8],
+            chunk: usize,
+        }
+
+        impl ErrorType for Chunked<'_> {
+            type Error = core::convert::Infallible;
+        }
+
+        impl Read for Chunked<'_> {
+            async fn read(&mut self, buf: &mut [u8]) -> Result<usize, Self::Error> {
+                let n = self.fill_buf().await?.len().min(buf.len());
+                buf[..n].copy_from_slice(&self.bytes[..n]);
+                self.consume(n);
+                Ok(n)
+            }
+        }
+
+        impl BufRead for Chunked<'_> {
+            async fn fill_buf(&mut self) -> Result<&[u8], Self::Error> {
+                Ok(&self.bytes[..self.chunk.min(self.bytes.len())])
+            }
+
+            fn consume(&mut self, amt: usize) {
+                self.bytes = &self.bytes[amt..];
+            }
+        }
+
+        // the oversized telegram is immediately followed by a valid one,
+        // whose '/' is the byte that overflows the buffer
+        let bytes = include_bytes!("../test/oversized.txt");
+
+        for chunk in [1, 3, 64, 2048, 4096] {
+            let mut reader = super::AsyncReader::new(Chunked {
+                bytes: &bytes[..],
+                chunk,
+            });
+            let readout = reader.next_readout().await.unwrap().unwrap();
+            let telegram = readout.to_telegram().unwrap();
+
+            assert_eq!(telegram.checksum, 0x9ab5);
+            assert!(reader.next_readout().await.unwrap().is_none());
+        }
+    }
+
+    #[cfg(feature = "embedded-io-async")]
+    #[tokio::test]
+    async fn back_to_back_async() {
+        let bytes = include_bytes!("../test/ell.txt");
+        let without_crlf = &bytes[..bytes.len() - 2];
+        let bytes = [without_crlf, without_crlf].concat();
+        let mut reader = super::AsyncReader::new(&bytes[..]);
+
+        for _ in 0..2 {
+            let readout = reader.next_readout().await.unwrap().unwrap();
+            assert_eq!(readout.to_telegram().unwrap().checksum, 0x9ab5);
+        }
+
+        assert!(reader.next_readout().await.unwrap().is_none());
+    }
+
+    #[cfg(feature = "futures")]
+    #[tokio::test]
+    async fn stream() {
+        use futures_util::StreamExt;
+
+        let bytes = include_bytes!("../test/ell.txt");
+        let bytes = [&bytes[..], &bytes[..]].concat();
+        let stream = super::AsyncReader::new(&bytes[..]).into_stream();
+        futures_util::pin_mut!(stream);
+
+        for _ in 0..2 {
+            let readout = stream.next().await.unwrap().unwrap();
+            assert_eq!(readout.to_telegram().unwrap().checksum, 0x9ab5);
+        }
+
+        assert!(stream.next().await.is_none());
+    }
+
+    #[cfg(feature = "futures")]
+    #[tokio::test]
+    async fn borrowed_stream() {
+        use futures_util::StreamExt;
+
+        let bytes = include_bytes!("../test/ell.txt");
+        let bytes = [&bytes[..], &bytes[..], &bytes[..]].concat();
+        let mut reader = super::AsyncReader::new(&bytes[..]);
+
+        let readouts = reader.stream().take(2).collect::<std::vec::Vec<_>>().await;
+        assert_eq!(readouts.len(), 2);
+
+        assert!(reader.next_readout().await.unwrap().is_some());
+        let rest = reader.stream();
+        futures_util::pin_mut!(rest);
+        assert!(rest.next().await.is_none());
+    }
+
+    #[cfg(feature = "embedded-io-async")]
+    #[tokio::test]
+    async fn next_telegram() {
+        use embedded_io_async::{BufRead, ErrorKind, ErrorType, Read};
+
+        /// Fails after yielding `limit` bytes.
+        struct Failing<'a> {
+            bytes: &'a [u8],
+            limit: usize,
+        }
+
+        impl ErrorType for Failing<'_> {
+            type Error = ErrorKind;
+        }
+
+        impl Read for Failing<'_> {
+            async fn read(&mut self, buf: &mut [u8]) -> Result<usize, Self::Error> {
+                let n = self.fill_buf().await?.len().min(buf.len());
+                buf[..n].copy_from_slice(&self.bytes[..n]);
+                self.consume(n);
+                Ok(n)
+            }
+        }
+
+        impl BufRead for Failing<'_> {
+            async fn fill_buf(&mut self) -> Result<&[u8], Self::Error> {
+                match self.limit {
+                    0 => Err(ErrorKind::BrokenPipe),
+                    limit => Ok(&self.bytes[..limit.min(self.bytes.len())]),
+                }
+            }
+
+            fn consume(&mut self, amt: usize) {
+                self.bytes = &self.bytes[amt..];
+                self.limit -= amt;
+            }
+        }
+
+        let bytes = include_bytes!("../test/ell.txt");
+        let mut reader = super::AsyncReader::new(Failing {
+            bytes: &bytes[..],
+            limit: usize::MAX,
+        });
+        let telegram = reader.next_telegram().await.unwrap().unwrap();
+        assert_eq!(telegram.checksum, 0x9ab5);
+        assert_eq!(telegram.objects().count(), 27);
+        assert!(reader.next_telegram().await.unwrap().is_none());
+
+        let mut reader = super::AsyncReader::new(Failing {
+            bytes: &bytes[..],
+            limit: 100,
+        });
+        assert!(matches!(
+            reader.next_telegram().await,
+            Err(super::ReadError::Io(ErrorKind::BrokenPipe))
+        ));
+
+        let bytes = include_bytes!("../test/ell-nobaud.txt");
+        let corrupt = [&bytes[..50], b"X", &bytes[51..]].concat();
+        let mut reader = super::AsyncReader::new(&corrupt[..]);
+        assert!(matches!(
+            reader.next_telegram().await,
+            Err(super::ReadError::Framing(Error::Checksum))
+        ));
+    }
+
+    #[cfg(feature = "embedded-io-async")]
+    #[tokio::test]
+    async fn without_baud_character_async() {
+        let bytes = include_bytes!("../test/ell-nobaud.txt");
+        let mut reader = super::AsyncReader::new(&bytes[..]);
+        let readout = reader.next_readout().await.unwrap().unwrap();
+        let telegram = readout.to_telegram().unwrap();
+
+        assert_eq!(telegram.flag_id, "ELL");
+        assert_eq!(telegram.identification, "\\253833635_A");
+    }
+
+    #[cfg(feature = "embedded-io-async")]
+    #[tokio::test]
+    async fn dlms_async() {
+        use crate::hdlc::tests::aidon_frame;
+        use crate::mbus::tests::frame as mbus_frame;
+
+        let hdlc = aidon_frame();
+        let apdu = crate::hdlc::Frame::parse(&hdlc).unwrap().information[3..].to_vec();
+        let mut corrupted = aidon_frame();
+        corrupted[20] ^= 1;
+
+        let bytes = [
+            &b"noise"[..],
+            &hdlc,
+            &corrupted,
+            &mbus_frame(0x00, &apdu[..10]),
+            &mbus_frame(0x11, &apdu[10..]),
+        ]
+        .concat();
+
+        let mut reader = super::DlmsReader::new(&bytes[..]);
+        for _ in 0..2 {
+            let notification = reader.next_notification().await.unwrap().unwrap();
+            assert_eq!(notification.invoke_id, 0x4000_0000);
+            assert_eq!(notification.objects().count(), 3);
+        }
+        assert!(reader.next_apdu().await.unwrap().is_none());
+
+        // an APDU exceeding the buffer is dropped
+        let mut reader = super::DlmsReader::<_, 64>::new_sized(&bytes[5..]);
+        assert!(reader.next_apdu().await.unwrap().is_none());
     }
 }
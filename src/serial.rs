@@ -0,0 +1,187 @@
+//! Serial settings and readers for the meter standards.
+//!
+//! With the `serialport` feature, [`open_swedish_han`] and [`open_dsmr2`]
+//! open and configure the port:
+//!
+//! ```no_run
+//! # #[cfg(feature = "serialport")] {
+//! let mut reader = han::serial::open_swedish_han("/dev/ttyUSB0")?;
+//! while let Some(readout) = reader.next_readout()? {
+//!     println!("{:?}", readout.to_telegram().map(|t| t.checksum));
+//! }
+//! # }
+//! # Ok::<(), std::io::Error>(())
+//! ```
+//!
+//! Otherwise it is left to the caller, e.g. with `stty`, using the
+//! [`SerialSettings`] of the standard:
+//!
+//! ```no_run
+//! use std::fs::File;
+//!
+//! use han::serial::{self, SerialSettings};
+//!
+//! // configured with `stty -F /dev/ttyUSB0 9600 cs7 parenb -parodd raw`
+//! assert_eq!(SerialSettings::DSMR2.to_string(), "9600 7E1");
+//! let mut reader = serial::dsmr2(File::open("/dev/ttyUSB0")?);
+//! while let Some(readout) = reader.next_readout()? {
+//!     println!("{:?}", readout.to_telegram().map(|t| t.checksum));
+//! }
+//! # Ok::<(), std::io::Error>(())
+//! ```
+
+use std::fmt::{self, Display};
+use std::io::{self, BufReader, Read};
+#[cfg(feature = "serialport")]
+use std::{boxed::Box, time::Duration};
+
+use crate::IoReader;
+
+/// Parity of a serial frame.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Parity {
+    /// No parity bit.
+    None,
+    /// Even parity.
+    Even,
+}
+
+/// Serial frame format and speed of a meter standard.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SerialSettings {
+    /// Bits per second.
+    pub baud_rate: u32,
+    /// Data bits per character.
+    pub data_bits: u8,
+    /// Parity bit.
+    pub parity: Parity,
+    /// Stop bits per character.
+    pub stop_bits: u8,
+}
+
+impl SerialSettings {
+    /// The Swedish and Norwegian HAN port, as well as DSMR 4 and later:
+    /// 115200 8N1.
+    pub const SWEDISH_HAN: Self = Self {
+        baud_rate: 115_200,
+        data_bits: 8,
+        parity: Parity::None,
+        stop_bits: 1,
+    };
+
+    /// DSMR 2.2 and 3: 9600 7E1.
+    pub const DSMR2: Self = Self {
+        baud_rate: 9600,
+        data_bits: 7,
+        parity: Parity::Even,
+        stop_bits: 1,
+    };
+}
+
+impl Display for SerialSettings {
+    /// Write the settings in the usual form, e.g. `115200 8N1`.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let parity = match self.parity {
+            Parity::None => 'N',
+            Parity::Even => 'E',
+        };
+        write!(
+            f,
+            "{} {}{}{}",
+            self.baud_rate, self.data_bits, parity, self.stop_bits
+        )
+    }
+}
+
+/// A serial port opened by [`SerialSettings::open`].
+#[cfg(feature = "serialport")]
+pub type Port = Box<dyn serialport::SerialPort>;
+
+#[cfg(feature = "serialport")]
+impl SerialSettings {
+    /// Open the serial port at `path` with these settings. Reads block until
+    /// the meter sends something.
+    pub fn open(&self, path: &str) -> io::Result<Port> {
+        let data_bits = match self.data_bits {
+            5 => serialport::DataBits::Five,
+            6 => serialport::DataBits::Six,
+            7 => serialport::DataBits::Seven,
+            _ => serialport::DataBits::Eight,
+        };
+        let parity = match self.parity {
+            Parity::None => serialport::Parity::None,
+            Parity::Even => serialport::Parity::Even,
+        };
+        let stop_bits = match self.stop_bits {
+            2 => serialport::StopBits::Two,
+            _ => serialport::StopBits::One,
+        };
+        let port = serialport::new(path, self.baud_rate)
+            .data_bits(data_bits)
+            .parity(parity)
+            .stop_bits(stop_bits)
+            .timeout(Duration::MAX)
+            .open()?;
+        Ok(port)
+    }
+}
+
+/// Clears the most significant bit of every byte, for ports that pass on
+/// the parity bit of 7-bit characters as read at 8N1.
+#[derive(Debug)]
+pub struct StripParity<R>(pub R);
+
+impl<R: Read> Read for StripParity<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let n = self.0.read(buf)?;
+        for b in &mut buf[..n] {
+            *b &= 0x7f;
+        }
+        Ok(n)
+    }
+}
+
+/// A reader for a port configured at [`SerialSettings::SWEDISH_HAN`].
+pub fn swedish_han<R: Read>(port: R) -> IoReader<BufReader<R>> {
+    IoReader::new(BufReader::new(port))
+}
+
+/// A reader for a port configured at [`SerialSettings::DSMR2`], stripping
+/// the parity bit in case the port leaves it in.
+pub fn dsmr2<R: Read>(port: R) -> IoReader<BufReader<StripParity<R>>> {
+    IoReader::new(BufReader::new(StripParity(port)))
+}
+
+/// Open a serial port at [`SerialSettings::SWEDISH_HAN`] and read from it.
+#[cfg(feature = "serialport")]
+pub fn open_swedish_han(path: &str) -> io::Result<IoReader<BufReader<Port>>> {
+    Ok(swedish_han(SerialSettings::SWEDISH_HAN.open(path)?))
+}
+
+/// Open a serial port at [`SerialSettings::DSMR2`] and read from it,
+/// stripping the parity bit in case the port leaves it in.
+#[cfg(feature = "serialport")]
+pub fn open_dsmr2(path: &str) -> io::Result<IoReader<BufReader<StripParity<Port>>>> {
+    Ok(dsmr2(SerialSettings::DSMR2.open(path)?))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::vec::Vec;
+
+    use super::dsmr2;
+
+    #[test]
+    fn strip_parity() {
+        let telegram = include_bytes!("../test/ell.txt");
+        // set the parity bit of every character with an odd number of ones
+        let with_parity = telegram
+            .iter()
+            .map(|&b| b | ((b.count_ones() as u8 & 1) << 7))
+            .collect::<Vec<_>>();
+
+        let mut reader = dsmr2(&with_parity[..]);
+        let readout = reader.next_readout().unwrap().unwrap();
+        assert_eq!(readout.to_telegram().unwrap().checksum, 0x9ab5);
+    }
+}
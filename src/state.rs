@@ -0,0 +1,2260 @@
+//! Aggregated meter state built from the [`Object`]s of a [`Telegram`].
+
+use crate::{
+    Deciamps, Decivolts, Direction, Error, Medium, Obis, ObisFull, Object, Power, Result, Telegram,
+    Timestamp, Unit, Watts,
+};
+
+/// A numeric type that the values of a [`GenericState`] are stored as.
+pub trait Value: Copy + Default + PartialEq + core::fmt::Debug {
+    /// Construct `v` × 10<sup>-`scale`</sup>.
+    fn from_scaled(v: i64, scale: u32) -> Self;
+
+    /// Parse a decimal as written in a telegram, e.g. `001.25`, keeping all
+    /// of its digits. Types returning `None` take the value of the parsed
+    /// [`Object`] instead, which has a fixed number of decimals.
+    fn parse_exact(s: &str) -> Option<Self> {
+        let _ = s;
+        None
+    }
+}
+
+impl Value for f64 {
+    fn from_scaled(v: i64, scale: u32) -> Self {
+        v as f64 / (0..scale).fold(1.0, |d, _| d * 10.0)
+    }
+}
+
+#[cfg(feature = "rust_decimal")]
+impl Value for rust_decimal::Decimal {
+    fn from_scaled(v: i64, scale: u32) -> Self {
+        rust_decimal::Decimal::new(v, scale)
+    }
+
+    fn parse_exact(s: &str) -> Option<Self> {
+        s.parse().ok()
+    }
+}
+
+/// A quantity with active, reactive and apparent components in both
+/// directions, indexed by [`Power`] and [`Direction`].
+///
+/// ```
+/// use han::{state::Measurement, Direction, Power};
+///
+/// let mut energy = Measurement::default();
+/// energy.set(Power::Active, Direction::FromGrid, 6136.936);
+/// assert_eq!(energy.get(Power::Active, Direction::FromGrid), Some(6136.936));
+/// assert_eq!(energy.get(Power::Active, Direction::ToGrid), None);
+/// ```
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "defmt-03", derive(defmt::Format))]
+pub struct Measurement<T = f64> {
+    from_grid: Components<T>,
+    to_grid: Components<T>,
+}
+
+/// The components in one direction, serialized as e.g.
+/// `{"active": 1.0, "reactive": null, "apparent": null}`.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "defmt-03", derive(defmt::Format))]
+struct Components<T> {
+    active: Option<T>,
+    reactive: Option<T>,
+    apparent: Option<T>,
+}
+
+impl<T> Measurement<T> {
+    /// The component of `power` in `dir`, in kW, kvar or kVA for power and
+    /// kWh, kvarh or kVAh for energy.
+    pub fn get(&self, power: Power, dir: Direction) -> Option<T>
+    where
+        T: Copy,
+    {
+        let c = match dir {
+            Direction::FromGrid => &self.from_grid,
+            Direction::ToGrid => &self.to_grid,
+        };
+        match power {
+            Power::Active => c.active,
+            Power::Reactive => c.reactive,
+            Power::Apparent => c.apparent,
+        }
+    }
+
+    /// Set the component of `power` in `dir`, returning the previous value.
+    pub fn set(&mut self, power: Power, dir: Direction, value: T) -> Option<T> {
+        self.get_mut(power, dir).replace(value)
+    }
+
+    /// A mutable reference to the component of `power` in `dir`.
+    pub fn get_mut(&mut self, power: Power, dir: Direction) -> &mut Option<T> {
+        let c = match dir {
+            Direction::FromGrid => &mut self.from_grid,
+            Direction::ToGrid => &mut self.to_grid,
+        };
+        match power {
+            Power::Active => &mut c.active,
+            Power::Reactive => &mut c.reactive,
+            Power::Apparent => &mut c.apparent,
+        }
+    }
+}
+
+/// Measurements of a single [`Line`](crate::Line).
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "defmt-03", derive(defmt::Format))]
+pub struct Line<T = f64> {
+    /// Power (kW or kvar).
+    pub power: Measurement<T>,
+    /// Phase voltage (V).
+    pub voltage: Option<T>,
+    /// Phase current (A).
+    pub current: Option<T>,
+    /// Power factor (-1.0 to 1.0).
+    pub power_factor: Option<T>,
+    /// Number of voltage sags.
+    pub voltage_sags: Option<u32>,
+    /// Number of voltage swells.
+    pub voltage_swells: Option<u32>,
+}
+
+/// Cumulative readings of meters of other [`Medium`]s, such as those
+/// attached to the M-Bus channels.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "defmt-03", derive(defmt::Format))]
+pub struct SubMeters<T = f64> {
+    /// Gas (m³).
+    pub gas: Option<T>,
+    /// Cold water (m³).
+    pub water: Option<T>,
+    /// Hot water (m³).
+    pub hot_water: Option<T>,
+    /// Heat (GJ).
+    pub heat: Option<T>,
+}
+
+impl<T> SubMeters<T> {
+    fn get_mut(&mut self, medium: Medium) -> Option<&mut Option<T>> {
+        match medium {
+            Medium::Electricity => None,
+            Medium::Gas => Some(&mut self.gas),
+            Medium::Water => Some(&mut self.water),
+            Medium::HotWater => Some(&mut self.hot_water),
+            Medium::Heat => Some(&mut self.heat),
+        }
+    }
+}
+
+/// A meter attached to an M-Bus channel.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "defmt-03", derive(defmt::Format))]
+pub struct Channel<T = f64> {
+    /// The medium measured, as given by the device type of the meter.
+    pub medium: Option<Medium>,
+    /// The latest reading, in [`Channel::unit`].
+    pub reading: Option<T>,
+    /// The unit of the reading, such as m³ or GJ.
+    pub unit: Option<Unit>,
+    /// When the reading was captured by the meter.
+    #[cfg_attr(feature = "defmt-03", defmt(Debug2Format))]
+    pub timestamp: Option<Timestamp>,
+}
+
+/// Quarter-hour demand, on which capacity tariffs are based.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "defmt-03", derive(defmt::Format))]
+pub struct Demand<T = f64> {
+    /// Average power received during the current quarter-hour (kW).
+    pub average: Option<T>,
+    /// Highest quarter-hour average of the running month (kW).
+    pub max: Option<T>,
+    /// When the highest quarter-hour average occurred.
+    #[cfg_attr(feature = "defmt-03", defmt(Debug2Format))]
+    pub max_time: Option<Timestamp>,
+}
+
+/// The state of a power meter with values stored as [`f64`].
+///
+/// ```
+/// use han::{Direction, Object, Power, WattHours, state::State};
+///
+/// let mut state = State::default();
+/// state.insert(Object::Energy(Direction::FromGrid, WattHours(6136936).into()));
+/// let energy = state.energy.get(Power::Active, Direction::FromGrid);
+/// assert_eq!(energy, Some(6136.936));
+/// ```
+pub type State = GenericState<f64>;
+
+/// The state of a power meter with values stored as exact
+/// [`Decimal`](rust_decimal::Decimal)s, preserving the precision reported
+/// by the meter when built from a [`Telegram`].
+///
+/// ```
+/// use han::{Direction, Object, Power, WattHours, state::DecimalState};
+/// use rust_decimal::Decimal;
+///
+/// let mut state = DecimalState::default();
+/// state.insert(Object::Energy(Direction::FromGrid, WattHours(6136936).into()));
+/// let energy = state.energy.get(Power::Active, Direction::FromGrid);
+/// assert_eq!(energy, Some(Decimal::new(6136936, 3)));
+/// ```
+#[cfg(feature = "rust_decimal")]
+pub type DecimalState = GenericState<rust_decimal::Decimal>;
+
+/// The state of a power meter with values stored as [`Fixed`](crate::Fixed)
+/// decimals, for targets without an FPU.
+///
+/// When built from a [`Telegram`], the values keep the decimals written by
+/// the meter, such as the hundredths of an ampere sent by eMUCS meters.
+///
+/// ```
+/// use han::{Fixed, Line, Telegram, state::FixedState};
+///
+/// let telegram = Telegram::parse(include_str!("../test/emucs.txt"))?;
+/// let state = FixedState::from_telegram(&telegram)?;
+/// assert_eq!(state.line(Line::L1).current, Some(Fixed::new(121, 2)));
+/// # Ok::<(), han::Error>(())
+/// ```
+#[cfg(feature = "fixed")]
+pub type FixedState = GenericState<crate::Fixed>;
+
+/// The state of a power meter, as reported by one or more telegrams.
+///
+/// See [`State`], `DecimalState` and `FixedState`.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "defmt-03", derive(defmt::Format))]
+pub struct GenericState<T> {
+    /// Time of the measurement.
+    #[cfg_attr(feature = "defmt-03", defmt(Debug2Format))]
+    pub datetime: Option<Timestamp>,
+    /// Cumulative energy (kWh or kvarh).
+    pub energy: Measurement<T>,
+    /// Cumulative energy per tariff, indexed 1 through 4 (kWh or kvarh).
+    pub tariff_energy: [Measurement<T>; 4],
+    /// Power across all lines (kW or kvar).
+    pub power: Measurement<T>,
+    /// Per-line measurements, indexed L1 through L3.
+    pub lines: [Line<T>; 3],
+    /// Power factor across all lines (-1.0 to 1.0).
+    pub power_factor: Option<T>,
+    /// Current in the neutral conductor (A).
+    pub neutral_current: Option<T>,
+    /// Grid frequency (Hz).
+    pub frequency: Option<T>,
+    /// Quarter-hour demand.
+    pub demand: Demand<T>,
+    /// Readings of meters of other media.
+    pub sub_meters: SubMeters<T>,
+    /// The meters on the M-Bus channels, indexed 1 through 4.
+    pub channels: [Channel<T>; 4],
+    /// Number of power failures in any phase.
+    pub power_failures: Option<u32>,
+    /// Number of long power failures in any phase.
+    pub long_power_failures: Option<u32>,
+}
+
+/// When the values of an [`Obis`] reference were last updated, kept
+/// alongside a [`GenericState`] to tell how fresh each of its values is when
+/// merging partial telegrams.
+///
+/// Only the 32 most recently updated references are tracked.
+///
+/// ```
+/// # #[cfg(feature = "time")] {
+/// use han::{Obis, Telegram, state::{State, Updated}};
+/// use time::macros::datetime;
+///
+/// let telegram = Telegram::parse(include_str!("../test/ell.txt"))?;
+/// let mut state = State::default();
+/// let mut updated = Updated::default();
+/// state.update_from_telegram(&telegram)?;
+/// updated.stamp_telegram(&telegram);
+///
+/// let age = updated.age_of(Obis(1, 0, 32, 7, 0), datetime!(2022-10-22 16:28:54 +1));
+/// assert_eq!(age, Some(time::Duration::seconds(10)));
+/// assert_eq!(updated.age_of(Obis(0, 1, 24, 2, 1), datetime!(2022-10-22 16:28:54 +1)), None);
+/// # }
+/// # Ok::<(), han::Error>(())
+/// ```
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "defmt-03", derive(defmt::Format))]
+pub struct Updated {
+    #[cfg_attr(feature = "defmt-03", defmt(Debug2Format))]
+    entries: [Option<(Obis, Timestamp)>; 32],
+}
+
+impl Updated {
+    /// Stamp the references of the objects in a [`Telegram`] as updated at
+    /// its time (0-0:1.0.0). Telegrams without a time are ignored, as are
+    /// objects that cannot be parsed.
+    pub fn stamp_telegram(&mut self, telegram: &Telegram) {
+        let time = telegram.objects().find_map(|o| match o {
+            Ok(Object::DateTime(time)) => Some(time),
+            _ => None,
+        });
+        let Some(time) = time else {
+            return;
+        };
+        for o in telegram.objects().flatten() {
+            self.stamp(o.obis(), time);
+        }
+    }
+
+    /// When the value of an [`Obis`] reference was last updated.
+    pub fn get(&self, obis: Obis) -> Option<Timestamp> {
+        self.entries
+            .iter()
+            .flatten()
+            .find(|(o, _)| *o == obis)
+            .map(|&(_, time)| time)
+    }
+
+    /// Stamp a reference as updated at `time`, e.g. from a clock of one's
+    /// own for meters that don't send their time. Evicts the least recently
+    /// updated reference if full.
+    pub fn stamp(&mut self, obis: Obis, time: Timestamp) {
+        let slot = match self
+            .entries
+            .iter()
+            .position(|e| e.is_some_and(|(o, _)| o == obis))
+        {
+            Some(i) => i,
+            None => match self.entries.iter().position(Option::is_none) {
+                Some(i) => i,
+                None => (0..self.entries.len())
+                    .min_by_key(|&i| self.entries[i].map(|(_, time)| time))
+                    .unwrap_or(0),
+            },
+        };
+        self.entries[slot] = Some((obis, time));
+    }
+
+    /// The time since the value of an [`Obis`] reference was last updated.
+    #[cfg(feature = "time")]
+    pub fn age_of(&self, obis: Obis, now: Timestamp) -> Option<time::Duration> {
+        Some(now - self.get(obis)?)
+    }
+
+    /// Overwrite the stamps with those in `other` that are newer.
+    pub fn merge(&mut self, other: &Self) {
+        Merge::merge(self, other);
+    }
+}
+
+impl<T: Value> GenericState<T> {
+    /// Build a state from every object in a [`Telegram`], failing on the
+    /// first object that cannot be parsed.
+    pub fn from_telegram(telegram: &Telegram) -> Result<Self> {
+        let mut s = Self::default();
+        s.update_from_telegram(telegram)?;
+        Ok(s)
+    }
+
+    /// Like [`GenericState::from_telegram`], but fails with
+    /// [`Error::DuplicateReference`] if a reference occurs more than once in
+    /// the telegram instead of letting the last one win, whether or not the
+    /// state stores its object.
+    pub fn from_telegram_strict(telegram: &Telegram) -> Result<Self> {
+        let mut s = Self::default();
+        for (i, (o, line)) in telegram.objects_with_lines().enumerate() {
+            let o = o?;
+            if let Some(full) = reference(line) {
+                if telegram.lines().take(i).any(|l| reference(l) == Some(full)) {
+                    return Err(Error::DuplicateReference(full.obis));
+                }
+            }
+            s.replace_parsed(o, line);
+        }
+        Ok(s)
+    }
+
+    /// Update the state with the objects in a [`Telegram`], keeping the
+    /// values of those that are not included, such as when a meter
+    /// alternates between short and full lists.
+    ///
+    /// Fails on the first object that cannot be parsed, leaving the objects
+    /// before it applied.
+    pub fn update_from_telegram(&mut self, telegram: &Telegram) -> Result<()> {
+        for (o, line) in telegram.objects_with_lines() {
+            self.replace_parsed(o?, line);
+        }
+        Ok(())
+    }
+
+    /// Overwrite the fields of this state with those set in `other`, which
+    /// is assumed to be newer.
+    ///
+    /// ```
+    /// use han::{Decivolts, Direction, Line, Object, Power, WattHours, state::State};
+    ///
+    /// let mut state = State::default();
+    /// state.insert(Object::Energy(Direction::FromGrid, WattHours(1000).into()));
+    /// state.insert(Object::Voltage(Line::L1, Decivolts(2301)));
+    ///
+    /// let mut newer = State::default();
+    /// newer.insert(Object::Voltage(Line::L1, Decivolts(2299)));
+    ///
+    /// state.merge(&newer);
+    /// assert_eq!(state.energy.get(Power::Active, Direction::FromGrid), Some(1.0));
+    /// assert_eq!(state.line(Line::L1).voltage, Some(229.9));
+    /// ```
+    pub fn merge(&mut self, other: &Self) {
+        Merge::merge(self, other);
+    }
+
+    /// Build a state from the objects in a [`Telegram`] that could be parsed,
+    /// skipping any that could not.
+    ///
+    /// Returns the state along with the number of skipped objects.
+    pub fn from_telegram_lossy(telegram: &Telegram) -> (Self, usize) {
+        let mut s = Self::default();
+        let mut skipped = 0;
+        for (o, line) in telegram.objects_with_lines() {
+            match o {
+                Ok(o) => {
+                    s.replace_parsed(o, line);
+                }
+                Err(_) => skipped += 1,
+            }
+        }
+        (s, skipped)
+    }
+
+    /// Measurements of a single line.
+    pub fn line(&self, line: crate::Line) -> &Line<T> {
+        &self.lines[line_index(line)]
+    }
+
+    /// Update the state with a parsed [`Object`].
+    ///
+    /// Objects without a corresponding field are ignored, as are M-Bus
+    /// readings in units other than m³ (gas and water) or GJ (heat). The
+    /// medium of an M-Bus reading is given by the device type of its channel,
+    /// which meters send before the reading.
+    pub fn insert(&mut self, obj: Object) {
+        self.replace(obj);
+    }
+
+    /// The value of a [`Field`], with counts converted to `T`.
+    ///
+    /// ```
+    /// use han::{Decivolts, Line, Object, state::{Field, State}};
+    ///
+    /// let mut state = State::default();
+    /// state.insert(Object::Voltage(Line::L1, Decivolts(2301)));
+    /// assert_eq!(state.field(Field::Voltage(Line::L1)), Some(230.1));
+    /// ```
+    pub fn field(&self, field: Field) -> Option<T> {
+        let count = |n: Option<u32>| n.map(|n| T::from_scaled(n.into(), 0));
+        match field {
+            Field::Energy(pow, dir) => self.energy.get(pow, dir),
+            Field::TariffEnergy(tariff, pow, dir) => {
+                self.tariff_energy[tariff_index(tariff)?].get(pow, dir)
+            }
+            Field::TotalPower(pow, dir) => self.power.get(pow, dir),
+            Field::Power(line, pow, dir) => self.line(line).power.get(pow, dir),
+            Field::Voltage(line) => self.line(line).voltage,
+            Field::Current(line) => self.line(line).current,
+            Field::LinePowerFactor(line) => self.line(line).power_factor,
+            Field::VoltageSags(line) => count(self.line(line).voltage_sags),
+            Field::VoltageSwells(line) => count(self.line(line).voltage_swells),
+            Field::PowerFactor => self.power_factor,
+            Field::AverageDemand => self.demand.average,
+            Field::MaxDemand => self.demand.max,
+            Field::NeutralCurrent => self.neutral_current,
+            Field::Frequency => self.frequency,
+            Field::SubMeter(Medium::Electricity) => None,
+            Field::SubMeter(Medium::Gas) => self.sub_meters.gas,
+            Field::SubMeter(Medium::Water) => self.sub_meters.water,
+            Field::SubMeter(Medium::HotWater) => self.sub_meters.hot_water,
+            Field::SubMeter(Medium::Heat) => self.sub_meters.heat,
+            Field::ChannelReading(channel) => self.channels[channel_index(channel)?].reading,
+            Field::PowerFailures => count(self.power_failures),
+            Field::LongPowerFailures => count(self.long_power_failures),
+        }
+    }
+
+    /// Update the state, returning whether the value was already set, or
+    /// `None` if the object was ignored.
+    fn apply(&mut self, obj: Object) -> Option<bool> {
+        match obj {
+            Object::DateTime(dt) => self.datetime.replace(dt).is_some().into(),
+            Object::Energy(dir, e) => self
+                .energy
+                .set(e.kind(), dir, kilo(e.raw()))
+                .is_some()
+                .into(),
+            Object::EnergyTariff(dir, tariff, e) => match tariff_index(tariff) {
+                Some(i) => self.tariff_energy[i]
+                    .set(e.kind(), dir, kilo(e.raw()))
+                    .is_some()
+                    .into(),
+                None => None,
+            },
+            Object::TotalPower(dir, p) => self
+                .power
+                .set(p.kind(), dir, kilo(p.raw()))
+                .is_some()
+                .into(),
+            Object::Power(line, dir, p) => self.lines[line_index(line)]
+                .power
+                .set(p.kind(), dir, kilo(p.raw()))
+                .is_some()
+                .into(),
+            Object::Voltage(line, Decivolts(v)) => self.lines[line_index(line)]
+                .voltage
+                .replace(deci(v))
+                .is_some()
+                .into(),
+            Object::Current(line, Deciamps(v)) => self.lines[line_index(line)]
+                .current
+                .replace(deci(v))
+                .is_some()
+                .into(),
+            Object::LinePowerFactor(line, v) => self.lines[line_index(line)]
+                .power_factor
+                .replace(T::from_scaled(v.into(), 3))
+                .is_some()
+                .into(),
+            Object::Volume(medium, v) => match (medium, self.sub_meters.get_mut(medium)) {
+                (Medium::Heat, _) | (_, None) => None,
+                (_, Some(field)) => field.replace(kilo(v)).is_some().into(),
+            },
+            Object::PowerFactor(v) => self
+                .power_factor
+                .replace(T::from_scaled(v.into(), 3))
+                .is_some()
+                .into(),
+            Object::AverageDemand(Watts(v)) => {
+                self.demand.average.replace(kilo(v)).is_some().into()
+            }
+            Object::MaxDemand(dt, Watts(v)) => {
+                self.demand.max_time = Some(dt);
+                self.demand.max.replace(kilo(v)).is_some().into()
+            }
+            Object::NeutralCurrent(Deciamps(v)) => {
+                self.neutral_current.replace(deci(v)).is_some().into()
+            }
+            Object::Frequency(v) => self
+                .frequency
+                .replace(T::from_scaled(v.into(), 2))
+                .is_some()
+                .into(),
+            Object::HeatEnergy(v) => self.sub_meters.heat.replace(kilo(v)).is_some().into(),
+            Object::MBusDeviceType(channel, device_type) => {
+                match channel_index(channel).zip(Medium::from_device_type(device_type)) {
+                    Some((i, medium)) => self.channels[i].medium.replace(medium).is_some().into(),
+                    None => None,
+                }
+            }
+            Object::MBusReading {
+                channel,
+                timestamp,
+                value,
+                unit,
+            } => {
+                let channel = &mut self.channels[channel_index(channel)?];
+                let replaced = channel.reading.replace(kilo(value)).is_some();
+                channel.unit = Some(unit);
+                channel.timestamp = Some(timestamp);
+
+                let medium = sub_meter(channel.medium, unit);
+                if let Some(field) = medium.and_then(|m| self.sub_meters.get_mut(m)) {
+                    *field = Some(kilo(value));
+                }
+                Some(replaced)
+            }
+            Object::VoltageSags(line, n) => self.lines[line_index(line)]
+                .voltage_sags
+                .replace(n)
+                .is_some()
+                .into(),
+            Object::VoltageSwells(line, n) => self.lines[line_index(line)]
+                .voltage_swells
+                .replace(n)
+                .is_some()
+                .into(),
+            Object::PowerFailures(n) => self.power_failures.replace(n).is_some().into(),
+            Object::LongPowerFailures(n) => self.long_power_failures.replace(n).is_some().into(),
+            Object::Version(_)
+            | Object::EquipmentId(_)
+            | Object::TextMessage(_)
+            | Object::MaxDemandHistory(_)
+            | Object::PowerFailureLog(_)
+            | Object::SwitchPosition(_)
+            | Object::ValvePosition(..)
+            | Object::LimiterThreshold(_)
+            | Object::FuseThreshold(_)
+            | Object::TariffIndicator(_)
+            | Object::MBusEquipmentId(..) => None,
+        }
+    }
+
+    /// Update the state, returning `true` if the value was already set.
+    fn replace(&mut self, obj: Object) -> bool {
+        self.apply(obj).unwrap_or(false)
+    }
+
+    /// Like [`GenericState::replace`], but with the value taken from the text
+    /// of the `line` that `obj` was parsed from if `T` can keep all of its
+    /// decimals.
+    fn replace_parsed(&mut self, obj: Object, line: &str) -> bool {
+        let replaced = self.replace(obj);
+        let exact = value_text(line).and_then(T::parse_exact);
+        if let Some((field, v)) = self.exact_field(&obj).zip(exact) {
+            *field = Some(v);
+        }
+        replaced
+    }
+
+    /// The field storing the value of `obj` in the unit the meter writes it
+    /// in, e.g. kWh or A.
+    fn exact_field(&mut self, obj: &Object) -> Option<&mut Option<T>> {
+        Some(match *obj {
+            Object::Energy(dir, e) => self.energy.get_mut(e.kind(), dir),
+            Object::EnergyTariff(dir, tariff, e) => {
+                self.tariff_energy[tariff_index(tariff)?].get_mut(e.kind(), dir)
+            }
+            Object::TotalPower(dir, p) => self.power.get_mut(p.kind(), dir),
+            Object::Power(line, dir, p) => {
+                self.lines[line_index(line)].power.get_mut(p.kind(), dir)
+            }
+            Object::Voltage(line, _) => &mut self.lines[line_index(line)].voltage,
+            Object::Current(line, _) => &mut self.lines[line_index(line)].current,
+            Object::LinePowerFactor(line, _) => &mut self.lines[line_index(line)].power_factor,
+            Object::NeutralCurrent(_) => &mut self.neutral_current,
+            Object::PowerFactor(_) => &mut self.power_factor,
+            Object::Frequency(_) => &mut self.frequency,
+            _ => return None,
+        })
+    }
+}
+
+/// The reference of an object line, e.g. `1-0:32.7.0` in
+/// `1-0:32.7.0(230.1*V)`.
+fn reference(line: &str) -> Option<ObisFull> {
+    let (reference, _) = line.split_once('(')?;
+    reference.parse().ok()
+}
+
+/// The scalar of a line with a single value, e.g. `001.25` in
+/// `1-0:31.7.0(001.25*A)`.
+fn value_text(line: &str) -> Option<&str> {
+    let (_, value) = line.split_once('(')?;
+    let value = value.strip_suffix(')')?;
+    if value.contains('(') {
+        return None;
+    }
+    Some(value.split_once('*').map_or(value, |(scalar, _)| scalar))
+}
+
+impl State {
+    /// Like [`GenericState::insert`], but notifies `observer` of the field
+    /// that was updated, if any, with its previous and new value.
+    ///
+    /// ```
+    /// use han::{Decivolts, Line, Object, state::{Field, State}};
+    ///
+    /// let mut state = State::default();
+    /// let mut high = false;
+    /// let mut observer = |field, _old, new| {
+    ///     high |= field == Field::Voltage(Line::L1) && new > 253.0;
+    /// };
+    /// state.insert_observed(Object::Voltage(Line::L1, Decivolts(2301)), &mut observer);
+    /// state.insert_observed(Object::Voltage(Line::L1, Decivolts(2541)), &mut observer);
+    /// assert!(high);
+    /// ```
+    pub fn insert_observed(&mut self, obj: Object, observer: &mut impl Observer) {
+        // M-Bus readings also update the sub-meter of the channel's medium
+        let sub_meter = match obj {
+            Object::MBusReading { channel, unit, .. } => channel_index(channel)
+                .and_then(|i| sub_meter(self.channels[i].medium, unit))
+                .map(Field::SubMeter),
+            _ => None,
+        };
+        let fields = [Field::of(&obj), sub_meter];
+        let old = fields.map(|f| f.and_then(|f| self.field(f)));
+        self.insert(obj);
+        for (field, old) in fields.into_iter().zip(old) {
+            if let Some((field, new)) = field.and_then(|f| Some((f, self.field(f)?))) {
+                observer.on_update(field, old, new);
+            }
+        }
+    }
+
+    /// Like [`GenericState::update_from_telegram`], but notifies `observer`
+    /// of every field that was updated, as by [`State::insert_observed`].
+    ///
+    /// ```
+    /// use han::{Line, Telegram, state::{Field, State}};
+    ///
+    /// let telegram = Telegram::parse(include_str!("../test/ell.txt"))?;
+    /// let mut state = State::default();
+    /// let mut voltages = Vec::new();
+    /// state.update_from_telegram_observed(&telegram, &mut |field, _old, new| {
+    ///     if let Field::Voltage(line) = field {
+    ///         voltages.push((line, new));
+    ///     }
+    /// })?;
+    /// assert_eq!(voltages[2], (Line::L3, 235.5));
+    /// # Ok::<(), han::Error>(())
+    /// ```
+    pub fn update_from_telegram_observed(
+        &mut self,
+        telegram: &Telegram,
+        observer: &mut impl Observer,
+    ) -> Result<()> {
+        for o in telegram.objects() {
+            self.insert_observed(o?, observer);
+        }
+        Ok(())
+    }
+
+    /// Apparent power across all lines (kVA), as reported by the meter or
+    /// otherwise derived from the active and reactive power.
+    ///
+    /// ```
+    /// use han::{Direction, Object, VoltAmperesReactive, Watts, state::State};
+    ///
+    /// let mut state = State::default();
+    /// state.insert(Object::TotalPower(Direction::FromGrid, Watts(3000).into()));
+    /// state.insert(Object::TotalPower(Direction::ToGrid, VoltAmperesReactive(4000).into()));
+    /// assert_eq!(state.apparent_power(), Some(5.0));
+    /// assert_eq!(state.power_factor(), Some(0.6));
+    /// ```
+    pub fn apparent_power(&self) -> Option<f64> {
+        self.power.apparent()
+    }
+
+    /// Power factor across all lines (-1.0 to 1.0), as reported by the meter
+    /// or otherwise derived from the active and apparent power.
+    pub fn power_factor(&self) -> Option<f64> {
+        self.power_factor.or_else(|| self.power.power_factor())
+    }
+
+    /// Average power (kW, kvar or kVA) since an `earlier` state, computed
+    /// from the energy registers for meters that don't send the power often
+    /// enough.
+    ///
+    /// The total registers are used if set in both states, otherwise the sum
+    /// of the tariff registers. Returns `None` unless both states are
+    /// timestamped, with `self` being later. Components whose register
+    /// decreased are left out, as a meter may step back by a unit; see
+    /// [`average_power_since_wrapping`](Self::average_power_since_wrapping)
+    /// for registers known to wrap around.
+    ///
+    /// ```
+    /// use han::{Direction, Object, Power, WattHours, state::State};
+    /// use time::macros::datetime;
+    ///
+    /// let state = |dt, wh| {
+    ///     let mut state = State::default();
+    ///     state.insert(Object::DateTime(dt));
+    ///     state.insert(Object::Energy(Direction::FromGrid, WattHours(wh).into()));
+    ///     state
+    /// };
+    /// let a = state(datetime!(2024-01-01 12:00 +1), 1_000_000);
+    /// let b = state(datetime!(2024-01-01 12:15 +1), 1_000_500);
+    ///
+    /// let power = b.average_power_since(&a).unwrap();
+    /// assert_eq!(power.get(Power::Active, Direction::FromGrid), Some(2.0));
+    /// ```
+    #[cfg(feature = "time")]
+    pub fn average_power_since(&self, earlier: &Self) -> Option<Measurement> {
+        self.average_power(earlier, None)
+    }
+
+    /// Like [`average_power_since`](Self::average_power_since), but with
+    /// registers that wrap around to zero at `modulus` (kWh, kvarh or kVAh),
+    /// e.g. `1e6` for a register of six integer digits.
+    ///
+    /// ```
+    /// use han::{Direction, Object, Power, WattHours, state::State};
+    /// use time::macros::datetime;
+    ///
+    /// let state = |dt, wh| {
+    ///     let mut state = State::default();
+    ///     state.insert(Object::DateTime(dt));
+    ///     state.insert(Object::Energy(Direction::FromGrid, WattHours(wh).into()));
+    ///     state
+    /// };
+    /// let a = state(datetime!(2024-01-01 12:00 +1), 999_999_750);
+    /// let b = state(datetime!(2024-01-01 12:15 +1), 250);
+    ///
+    /// let power = b.average_power_since_wrapping(&a, 1e6).unwrap();
+    /// assert_eq!(power.get(Power::Active, Direction::FromGrid), Some(2.0));
+    ///
+    /// let power = b.average_power_since(&a).unwrap();
+    /// assert_eq!(power.get(Power::Active, Direction::FromGrid), None);
+    /// ```
+    #[cfg(feature = "time")]
+    pub fn average_power_since_wrapping(
+        &self,
+        earlier: &Self,
+        modulus: f64,
+    ) -> Option<Measurement> {
+        self.average_power(earlier, Some(modulus))
+    }
+
+    #[cfg(feature = "time")]
+    fn average_power(&self, earlier: &Self, modulus: Option<f64>) -> Option<Measurement> {
+        let hours = (self.datetime? - earlier.datetime?).as_seconds_f64() / 3600.;
+        if hours <= 0. {
+            return None;
+        }
+
+        let mut avg = Measurement::default();
+        for power in [Power::Active, Power::Reactive, Power::Apparent] {
+            for dir in [Direction::FromGrid, Direction::ToGrid] {
+                if let Some(delta) = self.energy_since(earlier, power, dir, modulus) {
+                    avg.set(power, dir, delta / hours);
+                }
+            }
+        }
+        Some(avg)
+    }
+
+    /// Energy (kWh, kvarh or kVAh) since an `earlier` state, from the total
+    /// register or else the tariff registers, which wrap at `modulus` if
+    /// given.
+    #[cfg(feature = "time")]
+    pub(crate) fn energy_since(
+        &self,
+        earlier: &Self,
+        power: Power,
+        dir: Direction,
+        modulus: Option<f64>,
+    ) -> Option<f64> {
+        if let Some(delta) = register_delta(
+            earlier.energy.get(power, dir),
+            self.energy.get(power, dir),
+            modulus,
+        ) {
+            return Some(delta);
+        }
+
+        let mut sum = None;
+        for (a, b) in earlier.tariff_energy.iter().zip(&self.tariff_energy) {
+            match (a.get(power, dir), b.get(power, dir)) {
+                (None, None) => {}
+                (a, b) => *sum.get_or_insert(0.) += register_delta(a, b, modulus)?,
+            }
+        }
+        sum
+    }
+}
+
+/// The increase of an energy register, which may have wrapped around at
+/// `modulus`. `None` if it decreased otherwise.
+#[cfg(feature = "time")]
+fn register_delta(earlier: Option<f64>, later: Option<f64>, modulus: Option<f64>) -> Option<f64> {
+    let (earlier, later) = (earlier?, later?);
+    if later >= earlier {
+        return Some(later - earlier);
+    }
+    let modulus = modulus.filter(|&m| m > earlier)?;
+    Some(modulus - earlier + later)
+}
+
+impl Line<f64> {
+    /// Apparent power of the line (kVA), as reported by the meter or
+    /// otherwise derived from the active and reactive power.
+    pub fn apparent_power(&self) -> Option<f64> {
+        self.power.apparent()
+    }
+
+    /// Power factor of the line (-1.0 to 1.0), as reported by the meter or
+    /// otherwise derived from the active and apparent power.
+    pub fn power_factor(&self) -> Option<f64> {
+        self.power_factor.or_else(|| self.power.power_factor())
+    }
+}
+
+impl Measurement<f64> {
+    /// The net value of `power` in watts, or `None` if neither direction is
+    /// set.
+    fn net_watts(&self, power: Power) -> Option<i64> {
+        // no f64::round in core
+        let watts = |kw: f64| (kw * 1e3 + 0.5f64.copysign(kw)) as i64;
+        match (
+            self.get(power, Direction::FromGrid),
+            self.get(power, Direction::ToGrid),
+        ) {
+            (None, None) => None,
+            (from, to) => Some(watts(from.unwrap_or(0.)) - watts(to.unwrap_or(0.))),
+        }
+    }
+
+    /// Apparent power (kVA), derived as √(P² + Q²) in whole watts if not
+    /// reported.
+    fn apparent(&self) -> Option<f64> {
+        if let Some(s) = self.net_watts(Power::Apparent) {
+            return Some(s.unsigned_abs() as f64 / 1e3);
+        }
+        let p = i128::from(self.net_watts(Power::Active)?);
+        let q = i128::from(self.net_watts(Power::Reactive)?);
+        Some((p * p + q * q).unsigned_abs().isqrt() as f64 / 1e3)
+    }
+
+    /// Power factor, negative when power is returned to the grid.
+    fn power_factor(&self) -> Option<f64> {
+        let p = self.net_watts(Power::Active)? as f64 / 1e3;
+        let s = self.apparent()?;
+        (s > 0.).then(|| (p / s).clamp(-1., 1.))
+    }
+}
+
+/// Active quantities of a [`State`] with their units attached.
+///
+/// `uom` has no notion of reactive power, so reactive quantities are left
+/// out.
+#[cfg(feature = "uom")]
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct Quantities {
+    /// Active energy received from the grid.
+    pub energy_from_grid: Option<uom::si::f64::Energy>,
+    /// Active energy returned to the grid.
+    pub energy_to_grid: Option<uom::si::f64::Energy>,
+    /// Active power received from the grid across all lines.
+    pub power_from_grid: Option<uom::si::f64::Power>,
+    /// Active power returned to the grid across all lines.
+    pub power_to_grid: Option<uom::si::f64::Power>,
+    /// Per-line quantities, indexed L1 through L3.
+    pub lines: [LineQuantities; 3],
+}
+
+/// Active quantities of a single [`Line`] with their units attached.
+#[cfg(feature = "uom")]
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct LineQuantities {
+    /// Active power received from the grid.
+    pub power_from_grid: Option<uom::si::f64::Power>,
+    /// Active power returned to the grid.
+    pub power_to_grid: Option<uom::si::f64::Power>,
+    /// Phase voltage.
+    pub voltage: Option<uom::si::f64::ElectricPotential>,
+    /// Phase current.
+    pub current: Option<uom::si::f64::ElectricCurrent>,
+}
+
+#[cfg(feature = "uom")]
+impl State {
+    /// Convert the active quantities to [`uom`] quantities.
+    pub fn to_quantities(&self) -> Quantities {
+        use uom::si::electric_current::ampere;
+        use uom::si::electric_potential::volt;
+        use uom::si::energy::kilowatt_hour;
+        use uom::si::f64::{ElectricCurrent, ElectricPotential, Energy, Power};
+        use uom::si::power::kilowatt;
+
+        let energy = |v: Option<f64>| v.map(Energy::new::<kilowatt_hour>);
+        let power = |v: Option<f64>| v.map(Power::new::<kilowatt>);
+        let active = |m: &Measurement, dir| m.get(crate::Power::Active, dir);
+
+        Quantities {
+            energy_from_grid: energy(active(&self.energy, Direction::FromGrid)),
+            energy_to_grid: energy(active(&self.energy, Direction::ToGrid)),
+            power_from_grid: power(active(&self.power, Direction::FromGrid)),
+            power_to_grid: power(active(&self.power, Direction::ToGrid)),
+            lines: self.lines.map(|l| LineQuantities {
+                power_from_grid: power(active(&l.power, Direction::FromGrid)),
+                power_to_grid: power(active(&l.power, Direction::ToGrid)),
+                voltage: l.voltage.map(ElectricPotential::new::<volt>),
+                current: l.current.map(ElectricCurrent::new::<ampere>),
+            }),
+        }
+    }
+}
+
+/// Field-wise merging of states, where set values of the newer state win.
+trait Merge {
+    fn merge(&mut self, newer: &Self);
+}
+
+impl<T: Copy> Merge for Option<T> {
+    fn merge(&mut self, newer: &Self) {
+        if newer.is_some() {
+            *self = *newer;
+        }
+    }
+}
+
+impl<M: Merge, const N: usize> Merge for [M; N] {
+    fn merge(&mut self, newer: &Self) {
+        for (a, b) in self.iter_mut().zip(newer) {
+            a.merge(b);
+        }
+    }
+}
+
+impl<T: Copy> Merge for Components<T> {
+    fn merge(&mut self, newer: &Self) {
+        self.active.merge(&newer.active);
+        self.reactive.merge(&newer.reactive);
+        self.apparent.merge(&newer.apparent);
+    }
+}
+
+impl<T: Copy> Merge for Measurement<T> {
+    fn merge(&mut self, newer: &Self) {
+        self.from_grid.merge(&newer.from_grid);
+        self.to_grid.merge(&newer.to_grid);
+    }
+}
+
+impl<T: Copy> Merge for Line<T> {
+    fn merge(&mut self, newer: &Self) {
+        self.power.merge(&newer.power);
+        self.voltage.merge(&newer.voltage);
+        self.current.merge(&newer.current);
+        self.power_factor.merge(&newer.power_factor);
+        self.voltage_sags.merge(&newer.voltage_sags);
+        self.voltage_swells.merge(&newer.voltage_swells);
+    }
+}
+
+impl<T: Copy> Merge for SubMeters<T> {
+    fn merge(&mut self, newer: &Self) {
+        self.gas.merge(&newer.gas);
+        self.water.merge(&newer.water);
+        self.hot_water.merge(&newer.hot_water);
+        self.heat.merge(&newer.heat);
+    }
+}
+
+impl<T: Copy> Merge for Channel<T> {
+    fn merge(&mut self, newer: &Self) {
+        self.medium.merge(&newer.medium);
+        self.reading.merge(&newer.reading);
+        self.unit.merge(&newer.unit);
+        self.timestamp.merge(&newer.timestamp);
+    }
+}
+
+impl Merge for Updated {
+    fn merge(&mut self, newer: &Self) {
+        for &(obis, time) in newer.entries.iter().flatten() {
+            if self.get(obis).is_none_or(|t| t <= time) {
+                self.stamp(obis, time);
+            }
+        }
+    }
+}
+
+impl<T: Copy> Merge for Demand<T> {
+    fn merge(&mut self, newer: &Self) {
+        self.average.merge(&newer.average);
+        self.max.merge(&newer.max);
+        self.max_time.merge(&newer.max_time);
+    }
+}
+
+impl<T: Copy> Merge for GenericState<T> {
+    fn merge(&mut self, newer: &Self) {
+        self.datetime.merge(&newer.datetime);
+        self.energy.merge(&newer.energy);
+        self.tariff_energy.merge(&newer.tariff_energy);
+        self.power.merge(&newer.power);
+        self.lines.merge(&newer.lines);
+        self.power_factor.merge(&newer.power_factor);
+        self.neutral_current.merge(&newer.neutral_current);
+        self.frequency.merge(&newer.frequency);
+        self.demand.merge(&newer.demand);
+        self.sub_meters.merge(&newer.sub_meters);
+        self.channels.merge(&newer.channels);
+        self.power_failures.merge(&newer.power_failures);
+        self.long_power_failures.merge(&newer.long_power_failures);
+    }
+}
+
+/// The sub-meter of an M-Bus reading in `unit` on a channel of `medium`,
+/// if any. Readings in GJ are taken to be heat unless the medium says
+/// otherwise.
+fn sub_meter(medium: Option<Medium>, unit: Unit) -> Option<Medium> {
+    match (medium, unit) {
+        (Some(medium @ (Medium::Gas | Medium::Water | Medium::HotWater)), Unit::M3) => Some(medium),
+        (Some(Medium::Heat) | None, Unit::GJ) => Some(Medium::Heat),
+        _ => None,
+    }
+}
+
+fn line_index(line: crate::Line) -> usize {
+    match line {
+        crate::Line::L1 => 0,
+        crate::Line::L2 => 1,
+        crate::Line::L3 => 2,
+    }
+}
+
+/// The index of a tariff (1–4) in [`GenericState::tariff_energy`].
+fn tariff_index(tariff: u8) -> Option<usize> {
+    usize::from(tariff).checked_sub(1).filter(|&i| i < 4)
+}
+
+/// The index of an M-Bus channel (1–4) in [`GenericState::channels`].
+fn channel_index(channel: u8) -> Option<usize> {
+    usize::from(channel).checked_sub(1).filter(|&i| i < 4)
+}
+
+fn kilo<T: Value>(v: impl Into<u64>) -> T {
+    // saturating, as no meter counts beyond 9.2 EWh
+    T::from_scaled(v.into().try_into().unwrap_or(i64::MAX), 3)
+}
+
+fn deci<T: Value>(v: u16) -> T {
+    T::from_scaled(v.into(), 1)
+}
+
+/// A fixed-capacity ring buffer of the `N` most recent [`State`]s.
+///
+/// ```
+/// use han::state::{State, StateHistory};
+///
+/// let mut history = StateHistory::<2>::new();
+/// history.push(State::default());
+/// history.push(State::default());
+/// assert!(history.push(State::default()).is_some()); // the oldest is evicted
+/// assert_eq!(history.len(), 2);
+/// ```
+#[derive(Debug, Clone)]
+pub struct StateHistory<const N: usize> {
+    buf: [Option<State>; N],
+    /// Index of the oldest state.
+    head: usize,
+    len: usize,
+}
+
+impl<const N: usize> Default for StateHistory<N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<const N: usize> StateHistory<N> {
+    /// Construct an empty history.
+    pub const fn new() -> Self {
+        Self {
+            buf: [None; N],
+            head: 0,
+            len: 0,
+        }
+    }
+
+    /// Push a state, returning the evicted oldest state if the history was
+    /// full.
+    pub fn push(&mut self, state: State) -> Option<State> {
+        if N == 0 {
+            return Some(state);
+        }
+
+        if self.len < N {
+            self.buf[(self.head + self.len) % N] = Some(state);
+            self.len += 1;
+            None
+        } else {
+            let evicted = self.buf[self.head].replace(state);
+            self.head = (self.head + 1) % N;
+            evicted
+        }
+    }
+
+    /// Number of states currently held.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns `true` if no states have been pushed.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// The most recently pushed state.
+    pub fn latest(&self) -> Option<&State> {
+        self.get(self.len.checked_sub(1)?)
+    }
+
+    /// The oldest state still held.
+    pub fn oldest(&self) -> Option<&State> {
+        self.get(0)
+    }
+
+    /// Iterate over the states from oldest to newest.
+    pub fn iter(&self) -> impl DoubleEndedIterator<Item = &State> + '_ {
+        (0..self.len).filter_map(|i| self.get(i))
+    }
+
+    /// Iterate over the states timestamped at or after `time`, from oldest
+    /// to newest.
+    pub fn iter_since(&self, time: Timestamp) -> impl DoubleEndedIterator<Item = &State> + '_ {
+        self.iter()
+            .filter(move |s| s.datetime.is_some_and(|dt| dt >= time))
+    }
+
+    /// Mean power across all lines over the states in which it is set (kW,
+    /// kvar or kVA).
+    ///
+    /// ```
+    /// use han::{Direction, Object, Power, Watts, state::{State, StateHistory}};
+    ///
+    /// let mut history = StateHistory::<4>::new();
+    /// for w in [1000, 2000, 4500] {
+    ///     let mut state = State::default();
+    ///     state.insert(Object::TotalPower(Direction::FromGrid, Watts(w).into()));
+    ///     history.push(state);
+    /// }
+    /// assert_eq!(history.mean_power(Power::Active, Direction::FromGrid), Some(2.5));
+    /// ```
+    pub fn mean_power(&self, power: Power, dir: Direction) -> Option<f64> {
+        let (sum, n) = self
+            .iter()
+            .filter_map(|s| s.power.get(power, dir))
+            .fold((0., 0u32), |(sum, n), v| (sum + v, n + 1));
+        (n > 0).then(|| sum / f64::from(n))
+    }
+
+    /// Active energy received from the grid between the oldest and the
+    /// latest state (kWh).
+    pub fn delta_energy_from_grid_active(&self) -> Option<f64> {
+        let oldest = self
+            .oldest()?
+            .energy
+            .get(Power::Active, Direction::FromGrid)?;
+        let latest = self
+            .latest()?
+            .energy
+            .get(Power::Active, Direction::FromGrid)?;
+        Some(latest - oldest)
+    }
+
+    fn get(&self, i: usize) -> Option<&State> {
+        if i >= self.len {
+            return None;
+        }
+        self.buf[(self.head + i) % N].as_ref()
+    }
+}
+
+/// A value and when it was measured.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "defmt-03", derive(defmt::Format))]
+pub struct Extreme {
+    /// The value.
+    pub value: f64,
+    /// The time of the state it was taken from, if known.
+    #[cfg_attr(feature = "defmt-03", defmt(Debug2Format))]
+    pub time: Option<Timestamp>,
+}
+
+/// The lowest and highest value seen.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "defmt-03", derive(defmt::Format))]
+pub struct MinMax {
+    /// The lowest value.
+    pub min: Option<Extreme>,
+    /// The highest value.
+    pub max: Option<Extreme>,
+}
+
+impl MinMax {
+    fn update(&mut self, value: Option<f64>, time: Option<Timestamp>) {
+        let Some(value) = value else {
+            return;
+        };
+        let extreme = Some(Extreme { value, time });
+        if self.min.is_none_or(|min| value < min.value) {
+            self.min = extreme;
+        }
+        if self.max.is_none_or(|max| value > max.value) {
+            self.max = extreme;
+        }
+    }
+}
+
+/// Extremes of the measurements of a single [`Line`](crate::Line).
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "defmt-03", derive(defmt::Format))]
+pub struct LineExtremes {
+    /// Active power received from the grid (kW).
+    pub power_from_grid: MinMax,
+    /// Active power returned to the grid (kW).
+    pub power_to_grid: MinMax,
+    /// Phase voltage (V).
+    pub voltage: MinMax,
+    /// Phase current (A).
+    pub current: MinMax,
+}
+
+/// Minimum and maximum values, with timestamps, of the [`State`]s seen
+/// since the last reset, e.g. for sizing fuses or detecting brownouts.
+///
+/// ```
+/// use han::{Decivolts, Line, Object, state::{Extremes, State}};
+///
+/// let mut extremes = Extremes::default();
+/// for v in [2301, 2154, 2387] {
+///     let mut state = State::default();
+///     state.insert(Object::Voltage(Line::L1, Decivolts(v)));
+///     extremes.update(&state);
+/// }
+///
+/// let voltage = extremes.line(Line::L1).voltage;
+/// assert_eq!(voltage.min.unwrap().value, 215.4);
+/// assert_eq!(voltage.max.unwrap().value, 238.7);
+/// ```
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "defmt-03", derive(defmt::Format))]
+pub struct Extremes {
+    /// Active power received from the grid across all lines (kW).
+    pub power_from_grid: MinMax,
+    /// Active power returned to the grid across all lines (kW).
+    pub power_to_grid: MinMax,
+    /// Per-line extremes, indexed L1 through L3.
+    pub lines: [LineExtremes; 3],
+}
+
+impl Extremes {
+    /// Track the values of a state.
+    pub fn update(&mut self, state: &State) {
+        let time = state.datetime;
+        let active = |m: &Measurement, dir| m.get(Power::Active, dir);
+
+        self.power_from_grid
+            .update(active(&state.power, Direction::FromGrid), time);
+        self.power_to_grid
+            .update(active(&state.power, Direction::ToGrid), time);
+        for (e, l) in self.lines.iter_mut().zip(&state.lines) {
+            e.power_from_grid
+                .update(active(&l.power, Direction::FromGrid), time);
+            e.power_to_grid
+                .update(active(&l.power, Direction::ToGrid), time);
+            e.voltage.update(l.voltage, time);
+            e.current.update(l.current, time);
+        }
+    }
+
+    /// Extremes of a single line.
+    pub fn line(&self, line: crate::Line) -> &LineExtremes {
+        &self.lines[line_index(line)]
+    }
+
+    /// Forget all values seen.
+    pub fn reset(&mut self) {
+        *self = Self::default();
+    }
+}
+
+/// How a [`SmoothedState`] averages the values of its window.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Smoothing {
+    /// The arithmetic mean.
+    Mean,
+    /// An exponential moving average where each newer value has the weight
+    /// `alpha` (0.0 to 1.0), seeded with the oldest value.
+    Exponential {
+        /// The weight of each newer value.
+        alpha: f64,
+    },
+}
+
+/// Smoothed power, voltage and current over the `N` most recent [`State`]s,
+/// for display purposes.
+///
+/// States in which a value is not set are left out of its average.
+///
+/// ```
+/// use han::{Decivolts, Line, Object, state::{SmoothedState, State}};
+///
+/// let mut smoothed = SmoothedState::<4>::new();
+/// for v in [2300, 2310, 2290] {
+///     let mut state = State::default();
+///     state.insert(Object::Voltage(Line::L1, Decivolts(v)));
+///     smoothed.push(state);
+/// }
+/// assert_eq!(smoothed.voltage(Line::L1), Some(230.0));
+/// ```
+#[derive(Debug, Clone)]
+pub struct SmoothedState<const N: usize> {
+    history: StateHistory<N>,
+    smoothing: Smoothing,
+}
+
+impl<const N: usize> Default for SmoothedState<N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<const N: usize> SmoothedState<N> {
+    /// Smooth by the mean of the window.
+    pub const fn new() -> Self {
+        Self::with_smoothing(Smoothing::Mean)
+    }
+
+    /// Smooth as given by `smoothing`.
+    pub const fn with_smoothing(smoothing: Smoothing) -> Self {
+        Self {
+            history: StateHistory::new(),
+            smoothing,
+        }
+    }
+
+    /// Add a state to the window, evicting the oldest if it is full.
+    pub fn push(&mut self, state: State) {
+        self.history.push(state);
+    }
+
+    /// The states in the window.
+    pub fn history(&self) -> &StateHistory<N> {
+        &self.history
+    }
+
+    /// Smoothed power across all lines (kW, kvar or kVA).
+    pub fn power(&self, power: Power, dir: Direction) -> Option<f64> {
+        self.smooth(|s| s.power.get(power, dir))
+    }
+
+    /// Smoothed power of a line (kW, kvar or kVA).
+    pub fn line_power(&self, line: crate::Line, power: Power, dir: Direction) -> Option<f64> {
+        self.smooth(|s| s.line(line).power.get(power, dir))
+    }
+
+    /// Smoothed phase voltage (V).
+    pub fn voltage(&self, line: crate::Line) -> Option<f64> {
+        self.smooth(|s| s.line(line).voltage)
+    }
+
+    /// Smoothed phase current (A).
+    pub fn current(&self, line: crate::Line) -> Option<f64> {
+        self.smooth(|s| s.line(line).current)
+    }
+
+    fn smooth(&self, f: impl Fn(&State) -> Option<f64>) -> Option<f64> {
+        let mut values = self.history.iter().filter_map(f);
+        match self.smoothing {
+            Smoothing::Mean => {
+                let (sum, n) = values.fold((0., 0u32), |(sum, n), v| (sum + v, n + 1));
+                (n > 0).then(|| sum / f64::from(n))
+            }
+            Smoothing::Exponential { alpha } => {
+                let first = values.next()?;
+                Some(values.fold(first, |avg, v| avg + alpha * (v - avg)))
+            }
+        }
+    }
+}
+
+/// A numeric field of a [`GenericState`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt-03", derive(defmt::Format))]
+pub enum Field {
+    /// [`GenericState::energy`].
+    Energy(Power, Direction),
+    /// [`GenericState::tariff_energy`] of a tariff (1–4).
+    TariffEnergy(u8, Power, Direction),
+    /// [`GenericState::power`].
+    TotalPower(Power, Direction),
+    /// [`Line::power`].
+    Power(crate::Line, Power, Direction),
+    /// [`Line::voltage`].
+    Voltage(crate::Line),
+    /// [`Line::current`].
+    Current(crate::Line),
+    /// [`Line::power_factor`].
+    LinePowerFactor(crate::Line),
+    /// [`Line::voltage_sags`].
+    VoltageSags(crate::Line),
+    /// [`Line::voltage_swells`].
+    VoltageSwells(crate::Line),
+    /// [`GenericState::power_factor`].
+    PowerFactor,
+    /// [`Demand::average`].
+    AverageDemand,
+    /// [`Demand::max`].
+    MaxDemand,
+    /// [`GenericState::neutral_current`].
+    NeutralCurrent,
+    /// [`GenericState::frequency`].
+    Frequency,
+    /// The [`SubMeters`] field of a medium.
+    SubMeter(Medium),
+    /// [`Channel::reading`] of an M-Bus channel (1–4).
+    ChannelReading(u8),
+    /// [`GenericState::power_failures`].
+    PowerFailures,
+    /// [`GenericState::long_power_failures`].
+    LongPowerFailures,
+}
+
+impl Field {
+    /// The field updated by an object, if any.
+    ///
+    /// M-Bus readings update [`Field::ChannelReading`], and also the
+    /// [`Field::SubMeter`] of the channel's medium if known, which depends on
+    /// the state.
+    pub fn of(obj: &Object) -> Option<Self> {
+        Some(match *obj {
+            Object::Energy(dir, e) => Field::Energy(e.kind(), dir),
+            Object::EnergyTariff(dir, tariff, e) => Field::TariffEnergy(tariff, e.kind(), dir),
+            Object::TotalPower(dir, p) => Field::TotalPower(p.kind(), dir),
+            Object::Power(line, dir, p) => Field::Power(line, p.kind(), dir),
+            Object::Voltage(line, _) => Field::Voltage(line),
+            Object::Current(line, _) => Field::Current(line),
+            Object::LinePowerFactor(line, _) => Field::LinePowerFactor(line),
+            Object::VoltageSags(line, _) => Field::VoltageSags(line),
+            Object::VoltageSwells(line, _) => Field::VoltageSwells(line),
+            Object::PowerFactor(_) => Field::PowerFactor,
+            Object::AverageDemand(_) => Field::AverageDemand,
+            Object::MaxDemand(..) => Field::MaxDemand,
+            Object::NeutralCurrent(_) => Field::NeutralCurrent,
+            Object::Frequency(_) => Field::Frequency,
+            Object::Volume(Medium::Heat, _) => return None,
+            Object::Volume(medium, _) => Field::SubMeter(medium),
+            Object::HeatEnergy(_) => Field::SubMeter(Medium::Heat),
+            Object::MBusReading { channel, .. } => Field::ChannelReading(channel),
+            Object::PowerFailures(_) => Field::PowerFailures,
+            Object::LongPowerFailures(_) => Field::LongPowerFailures,
+            _ => return None,
+        })
+    }
+
+    /// The field that objects with an [`Obis`] reference update, if any.
+    ///
+    /// ```
+    /// use han::{Line, Obis, state::Field};
+    ///
+    /// assert_eq!(Field::of_obis(Obis(1, 0, 52, 7, 0)), Some(Field::Voltage(Line::L2)));
+    /// assert_eq!(Field::of_obis(Obis(0, 0, 96, 1, 0)), None);
+    /// ```
+    pub fn of_obis(obis: Obis) -> Option<Self> {
+        let pow_dir = |c| crate::pow_dir(c).ok();
+        let lines = [crate::Line::L1, crate::Line::L2, crate::Line::L3];
+        Some(match obis {
+            Obis(1, 0, 13, 7, 0) => Field::PowerFactor,
+            Obis(1, 0, 14, 7, 0) => Field::Frequency,
+            Obis(1, 0, 91, 7, 0) => Field::NeutralCurrent,
+            Obis(1, 0, c @ ..=20, 7, 0) => {
+                let (pow, dir) = pow_dir(c)?;
+                Field::TotalPower(pow, dir)
+            }
+            Obis(1, 0, c @ 21..=80, 7, 0) => {
+                // 21–40 for L1, 41–60 for L2 and 61–80 for L3
+                let line = lines[(c as usize - 21) / 20];
+                match (c - 1) % 20 + 1 {
+                    11 => Field::Current(line),
+                    12 => Field::Voltage(line),
+                    13 => Field::LinePowerFactor(line),
+                    c => {
+                        let (pow, dir) = pow_dir(c)?;
+                        Field::Power(line, pow, dir)
+                    }
+                }
+            }
+            Obis(1, 0, c, 8, tariff) => {
+                let (pow, dir) = pow_dir(c)?;
+                match tariff {
+                    0 => Field::Energy(pow, dir),
+                    1..=4 => Field::TariffEnergy(tariff, pow, dir),
+                    _ => return None,
+                }
+            }
+            Obis(1, 0, c @ (32 | 52 | 72), d @ (32 | 36), 0) => {
+                let line = lines[c as usize / 20 - 1];
+                match d {
+                    32 => Field::VoltageSags(line),
+                    _ => Field::VoltageSwells(line),
+                }
+            }
+            Obis(1, 0, 1, 4, 0) => Field::AverageDemand,
+            Obis(1, 0, 1, 6, 0) => Field::MaxDemand,
+            Obis(0, 0, 96, 7, 21) => Field::PowerFailures,
+            Obis(0, 0, 96, 7, 9) => Field::LongPowerFailures,
+            Obis(0, channel @ 1..=4, 24, 2, 1) => Field::ChannelReading(channel),
+            Obis(6, 0, 1, 0, 0) => Field::SubMeter(Medium::Heat),
+            Obis(a, 0, c, 0, 0) => {
+                let medium = Medium::from_code(a).filter(|m| m.volume_c() == Some(c))?;
+                Field::SubMeter(medium)
+            }
+            _ => return None,
+        })
+    }
+}
+
+/// Notified of updates to the fields of a [`State`] by
+/// [`State::insert_observed`], e.g. to react to thresholds being crossed
+/// without comparing whole states.
+///
+/// Implemented for closures taking the same arguments as
+/// [`Observer::on_update`].
+pub trait Observer {
+    /// A field was set to `new`, from `old` if it was set before. The value
+    /// may be unchanged.
+    fn on_update(&mut self, field: Field, old: Option<f64>, new: f64);
+}
+
+impl<F: FnMut(Field, Option<f64>, f64)> Observer for F {
+    fn on_update(&mut self, field: Field, old: Option<f64>, new: f64) {
+        self(field, old, new)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #[cfg(feature = "time")]
+    use time::macros::datetime;
+
+    #[cfg(feature = "time")]
+    use crate::Unit;
+    use crate::{
+        Deciamps, Direction, Error, Medium, Obis, Object, Power, Reader, Telegram, VoltAmpereHours,
+        VoltAmperes, VoltAmperesReactive, WattHours, Watts,
+    };
+
+    #[cfg(feature = "time")]
+    use super::Updated;
+    use super::{Field, State, StateHistory};
+
+    fn with_energy(wh: u64) -> State {
+        let mut s = State::default();
+        s.insert(Object::Energy(Direction::FromGrid, WattHours(wh).into()));
+        s
+    }
+
+    #[test]
+    fn from_telegram() {
+        let bytes = include_bytes!("../test/ell.txt");
+        let readout = Reader::new(bytes.iter().cloned()).next().unwrap();
+        let state = State::from_telegram(&readout.to_telegram().unwrap()).unwrap();
+
+        assert_eq!(
+            state.energy.get(Power::Active, Direction::FromGrid),
+            Some(6136.936)
+        );
+        assert_eq!(
+            state.power.get(Power::Reactive, Direction::ToGrid),
+            Some(0.206)
+        );
+        assert_eq!(state.line(crate::Line::L3).voltage, Some(235.5));
+        assert_eq!(state.line(crate::Line::L1).current, Some(2.2));
+        assert_eq!(state.line(crate::Line::L1).power_factor, None);
+    }
+
+    #[test]
+    fn dsmr5() {
+        let telegram = Telegram::parse(include_str!("../test/dsmr5.txt")).unwrap();
+        let state = State::from_telegram_lossy(&telegram).0;
+        assert_eq!(state.power_failures, Some(4));
+        assert_eq!(state.long_power_failures, Some(2));
+        assert_eq!(state.line(crate::Line::L1).voltage_sags, Some(2));
+        assert_eq!(state.line(crate::Line::L2).voltage_swells, Some(3));
+    }
+
+    #[cfg(feature = "time")]
+    #[test]
+    fn sub_meters() {
+        let telegram = Telegram::parse(include_str!("../test/dsmr5.txt")).unwrap();
+        let state = State::from_telegram_lossy(&telegram).0;
+        let media = state.channels.map(|c| c.medium);
+        assert_eq!(media, [Some(Medium::Gas), None, None, None]);
+        assert_eq!(state.sub_meters.gas, Some(12785.123));
+        assert_eq!(state.channels[0].reading, Some(12785.123));
+        assert_eq!(state.channels[0].unit, Some(Unit::M3));
+        assert_eq!(
+            state.channels[0].timestamp,
+            Some(datetime!(2010-12-09 11:25:00 +1))
+        );
+
+        let reading = |channel, value, unit| Object::MBusReading {
+            channel,
+            timestamp: datetime!(2024-10-27 15:00 +1),
+            value,
+            unit,
+        };
+        let mut state = State::default();
+        state.insert(Object::MBusDeviceType(2, 7));
+        state.insert(reading(2, 123456, Unit::M3));
+        state.insert(Object::MBusDeviceType(3, 4));
+        state.insert(reading(3, 42000, Unit::GJ));
+        // the medium of a volume is unknown without the device type
+        state.insert(reading(4, 1000, Unit::M3));
+        // wrong unit
+        state.insert(Object::MBusDeviceType(1, 3));
+        state.insert(reading(1, 1000, Unit::GJ));
+
+        assert_eq!(state.sub_meters.water, Some(123.456));
+        assert_eq!(state.sub_meters.heat, Some(42.0));
+        assert_eq!(state.sub_meters.gas, None);
+        assert_eq!(state.channels[3].medium, None);
+        assert_eq!(state.channels[3].reading, Some(1.0));
+    }
+
+    #[test]
+    fn frequency() {
+        let mut state = State::default();
+        state.insert(Object::Frequency(4998));
+        assert_eq!(state.frequency, Some(49.98));
+    }
+
+    #[cfg(feature = "time")]
+    #[test]
+    fn demand() {
+        let telegram = Telegram::parse(include_str!("../test/emucs.txt")).unwrap();
+        let state = State::from_telegram_lossy(&telegram).0;
+        assert_eq!(state.demand.average, Some(2.351));
+        assert_eq!(state.demand.max, Some(4.329));
+        assert_eq!(state.demand.max_time, Some(datetime!(2023-03-02 18:30 +1)));
+    }
+
+    #[test]
+    fn neutral_current() {
+        let mut state = State::default();
+        state.insert(Object::NeutralCurrent(Deciamps(17)));
+        assert_eq!(state.neutral_current, Some(1.7));
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde_field_names() {
+        let state = with_energy(1000);
+        let json = serde_json::to_value(state).unwrap();
+        assert_eq!(json["energy"]["from_grid"]["active"], 1.0);
+        assert!(json["power"]["to_grid"]["apparent"].is_null());
+    }
+
+    #[test]
+    fn tariffs() {
+        let bytes = include_bytes!("../test/dsmr5.txt");
+        let readout = Reader::new(bytes.iter().cloned()).next().unwrap();
+        let state = State::from_telegram(&readout.to_telegram().unwrap()).unwrap();
+
+        let energy = |tariff: usize, dir| state.tariff_energy[tariff - 1].get(Power::Active, dir);
+        assert_eq!(energy(1, Direction::FromGrid), Some(123456.789));
+        assert_eq!(energy(2, Direction::ToGrid), Some(123456.789));
+        assert_eq!(energy(3, Direction::FromGrid), None);
+
+        let mut state = State::default();
+        state.insert(Object::EnergyTariff(
+            Direction::FromGrid,
+            5,
+            WattHours(1).into(),
+        ));
+        assert_eq!(state, State::default());
+    }
+
+    #[test]
+    fn update_from_telegram() {
+        let full = Telegram::parse(include_str!("../test/ell.txt")).unwrap();
+        let mut state = full.to_state().unwrap();
+
+        let short = Telegram::parse_without_checksum(
+            "/ELL5\\253833635_A\r\n\r\n1-0:1.7.0(0001.234*kW)\r\n!\r\n",
+        )
+        .unwrap();
+        state.update_from_telegram(&short).unwrap();
+        assert_eq!(
+            state.power.get(Power::Active, Direction::FromGrid),
+            Some(1.234)
+        );
+        assert_eq!(
+            state.energy.get(Power::Active, Direction::FromGrid),
+            Some(6136.936)
+        );
+
+        let mut merged = full.to_state().unwrap();
+        merged.merge(&short.to_state().unwrap());
+        assert_eq!(merged, state);
+    }
+
+    #[test]
+    fn derived_power() {
+        let mut state = State::default();
+        assert_eq!(state.apparent_power(), None);
+        assert_eq!(state.power_factor(), None);
+
+        state.insert(Object::Power(
+            crate::Line::L1,
+            Direction::ToGrid,
+            Watts(1200).into(),
+        ));
+        state.insert(Object::Power(
+            crate::Line::L1,
+            Direction::FromGrid,
+            VoltAmperesReactive(500).into(),
+        ));
+        let line = state.line(crate::Line::L1);
+        assert_eq!(line.apparent_power(), Some(1.3));
+        assert_eq!(line.power_factor(), Some(-1.2 / 1.3));
+
+        // reported values take precedence
+        state.insert(Object::LinePowerFactor(crate::Line::L1, -900));
+        state.insert(Object::Power(
+            crate::Line::L1,
+            Direction::ToGrid,
+            VoltAmperes(1400).into(),
+        ));
+        let line = state.line(crate::Line::L1);
+        assert_eq!(line.apparent_power(), Some(1.4));
+        assert_eq!(line.power_factor(), Some(-0.9));
+    }
+
+    #[cfg(feature = "time")]
+    #[test]
+    fn average_power() {
+        let state = |dt, objects: &[Object]| {
+            let mut state = State::default();
+            state.insert(Object::DateTime(dt));
+            for &o in objects {
+                state.insert(o);
+            }
+            state
+        };
+        let tariff =
+            |tariff, wh| Object::EnergyTariff(Direction::FromGrid, tariff, WattHours(wh).into());
+
+        // split across tariffs, with the second one wrapping around
+        let a = state(
+            datetime!(2024-01-01 12:00 +1),
+            &[tariff(1, 500_000), tariff(2, 99_999_000)],
+        );
+        let b = state(
+            datetime!(2024-01-01 12:30 +1),
+            &[tariff(1, 500_250), tariff(2, 250)],
+        );
+        let power = b.average_power_since_wrapping(&a, 100_000.).unwrap();
+        assert_eq!(power.get(Power::Active, Direction::FromGrid), Some(3.0));
+        assert_eq!(power.get(Power::Active, Direction::ToGrid), None);
+        let power = b.average_power_since(&a).unwrap();
+        assert_eq!(power.get(Power::Active, Direction::FromGrid), None);
+
+        // a small step back is a glitch, not a wrap
+        let c = state(
+            datetime!(2024-01-01 13:00 +1),
+            &[tariff(1, 500_249), tariff(2, 250)],
+        );
+        let power = c.average_power_since(&b).unwrap();
+        assert_eq!(power.get(Power::Active, Direction::FromGrid), None);
+
+        assert_eq!(a.average_power_since(&b), None);
+        assert_eq!(a.average_power_since(&State::default()), None);
+    }
+
+    #[cfg(feature = "time")]
+    #[test]
+    fn updated() {
+        let voltage = Obis(1, 0, 32, 7, 0);
+        let mut updated = Updated::default();
+
+        // short telegrams without a time leave the stamps alone
+        let short =
+            Telegram::parse_without_checksum("/XYZ5\r\n\r\n1-0:32.7.0(230.1*V)\r\n!").unwrap();
+        updated.stamp_telegram(&short);
+        assert_eq!(updated.get(voltage), None);
+
+        let full = Telegram::parse(include_str!("../test/ell.txt")).unwrap();
+        updated.stamp_telegram(&full);
+        assert_eq!(
+            updated.get(voltage),
+            Some(datetime!(2022-10-22 16:28:44 +1))
+        );
+        updated.stamp_telegram(&short);
+        assert_eq!(
+            updated.get(voltage),
+            Some(datetime!(2022-10-22 16:28:44 +1))
+        );
+
+        let mut newer = Updated::default();
+        newer.stamp(voltage, datetime!(2022-10-22 16:30 +1));
+        newer.stamp(Obis(0, 0, 1, 0, 0), datetime!(2022-10-22 16:00 +1));
+        updated.merge(&newer);
+        assert_eq!(updated.get(voltage), Some(datetime!(2022-10-22 16:30 +1)));
+        assert_eq!(
+            updated.get(Obis(0, 0, 1, 0, 0)),
+            Some(datetime!(2022-10-22 16:28:44 +1))
+        );
+
+        // full, so the least recently updated reference is evicted
+        for i in 0..31 {
+            updated.stamp(Obis(0, 0, 96, 13, i), datetime!(2022-10-22 17:00 +1));
+        }
+        assert_eq!(
+            updated.get(Obis(0, 0, 96, 13, 30)),
+            Some(datetime!(2022-10-22 17:00 +1))
+        );
+    }
+
+    #[test]
+    fn apparent() {
+        let mut state = State::default();
+        state.insert(Object::TotalPower(
+            Direction::FromGrid,
+            VoltAmperes(2790).into(),
+        ));
+        state.insert(Object::Energy(
+            Direction::ToGrid,
+            VoltAmpereHours(1234).into(),
+        ));
+        assert_eq!(
+            state.power.get(Power::Apparent, Direction::FromGrid),
+            Some(2.79)
+        );
+        assert_eq!(
+            state.energy.get(Power::Apparent, Direction::ToGrid),
+            Some(1.234)
+        );
+    }
+
+    #[test]
+    fn power_factor() {
+        let mut state = State::default();
+        state.insert(Object::LinePowerFactor(crate::Line::L2, -870));
+        state.insert(Object::PowerFactor(-912));
+        assert_eq!(state.power_factor, Some(-0.912));
+        assert_eq!(state.line(crate::Line::L2).power_factor, Some(-0.87));
+    }
+
+    #[test]
+    fn from_telegram_lossy() {
+        let bytes = include_bytes!("../test/ell-badline.txt");
+        let readout = Reader::new(bytes.iter().cloned()).next().unwrap();
+        let telegram = readout.to_telegram().unwrap();
+        assert!(State::from_telegram(&telegram).is_err());
+
+        let (state, skipped) = State::from_telegram_lossy(&telegram);
+        assert_eq!(skipped, 1);
+        assert_eq!(
+            state.energy.get(Power::Active, Direction::FromGrid),
+            Some(6136.936)
+        );
+        assert_eq!(state.line(crate::Line::L1).voltage, None);
+        assert_eq!(state.line(crate::Line::L2).voltage, Some(230.6));
+    }
+
+    #[cfg(feature = "uom")]
+    #[test]
+    fn to_quantities() {
+        use uom::si::electric_potential::volt;
+        use uom::si::energy::{joule, kilowatt_hour};
+
+        let bytes = include_bytes!("../test/ell.txt");
+        let readout = Reader::new(bytes.iter().cloned()).next().unwrap();
+        let state = State::from_telegram(&readout.to_telegram().unwrap()).unwrap();
+        let q = state.to_quantities();
+
+        let energy = q.energy_from_grid.unwrap();
+        assert_eq!(energy.get::<kilowatt_hour>(), 6136.936);
+        assert!((energy.get::<joule>() - 6136.936 * 3.6e6).abs() < 1e-3);
+        assert_eq!(q.lines[2].voltage.unwrap().get::<volt>(), 235.5);
+        assert!(q.lines[0].power_to_grid.is_some());
+    }
+
+    #[test]
+    fn duplicates() {
+        let bytes = include_bytes!("../test/ell-duplicate.txt");
+        let readout = Reader::new(bytes.iter().cloned()).next().unwrap();
+        let telegram = readout.to_telegram().unwrap();
+
+        // last one wins
+        let state = State::from_telegram(&telegram).unwrap();
+        assert_eq!(state.line(crate::Line::L1).voltage, Some(240.1));
+
+        assert!(matches!(
+            State::from_telegram_strict(&telegram),
+            Err(Error::DuplicateReference(Obis(1, 0, 32, 7, 0)))
+        ));
+
+        let bytes = include_bytes!("../test/ell.txt");
+        let readout = Reader::new(bytes.iter().cloned()).next().unwrap();
+        State::from_telegram_strict(&readout.to_telegram().unwrap()).unwrap();
+
+        // objects that the state does not store count too
+        let telegram = Telegram::parse_without_checksum(
+            "/ELL5\\253833635_A\r\n\r\n0-0:96.14.0(0001)\r\n0-0:96.14.0(0002)\r\n!",
+        )
+        .unwrap();
+        assert!(matches!(
+            State::from_telegram_strict(&telegram),
+            Err(Error::DuplicateReference(Obis(0, 0, 96, 14, 0)))
+        ));
+    }
+
+    #[cfg(feature = "rust_decimal")]
+    #[test]
+    fn decimal() {
+        use rust_decimal::Decimal;
+
+        use super::DecimalState;
+
+        let readings = [100, 200, 300]; // Wh
+
+        let states = readings.map(with_energy);
+        let delta = |a: &State, b: &State| {
+            b.energy.get(Power::Active, Direction::FromGrid).unwrap()
+                - a.energy.get(Power::Active, Direction::FromGrid).unwrap()
+        };
+        let sum = delta(&states[0], &states[1]) + delta(&states[1], &states[2]);
+        assert_ne!(sum, 0.2);
+
+        let states = readings.map(|wh| {
+            let mut s = DecimalState::default();
+            s.insert(Object::Energy(Direction::FromGrid, WattHours(wh).into()));
+            s
+        });
+        let delta = |a: &DecimalState, b: &DecimalState| {
+            b.energy.get(Power::Active, Direction::FromGrid).unwrap()
+                - a.energy.get(Power::Active, Direction::FromGrid).unwrap()
+        };
+        let sum = delta(&states[0], &states[1]) + delta(&states[1], &states[2]);
+        assert_eq!(sum, Decimal::new(2, 1));
+
+        let bytes = include_bytes!("../test/ell.txt");
+        let readout = Reader::new(bytes.iter().cloned()).next().unwrap();
+        let state = DecimalState::from_telegram(&readout.to_telegram().unwrap()).unwrap();
+        assert_eq!(
+            state.line(crate::Line::L3).voltage,
+            Some(Decimal::new(2355, 1))
+        );
+
+        let telegram = Telegram::parse(include_str!("../test/emucs.txt")).unwrap();
+        let state = DecimalState::from_telegram(&telegram).unwrap();
+        assert_eq!(
+            state.line(crate::Line::L1).current,
+            Some(Decimal::new(121, 2))
+        );
+    }
+
+    #[cfg(feature = "fixed")]
+    #[test]
+    fn fixed() {
+        use crate::Fixed;
+
+        use super::{value_text, FixedState};
+
+        let telegram = Telegram::parse(include_str!("../test/emucs.txt")).unwrap();
+        let state = FixedState::from_telegram(&telegram).unwrap();
+        let line = state.line(crate::Line::L1);
+        assert_eq!(line.current, Some(Fixed::new(121, 2)));
+        assert_eq!(line.current.unwrap().scale, 2);
+        assert_eq!(line.voltage.unwrap().to_string(), "232.1");
+        assert_eq!(
+            state.tariff_energy[0]
+                .get(Power::Active, Direction::FromGrid)
+                .map(|v| v.to_string())
+                .as_deref(),
+            Some("123.456")
+        );
+
+        // values written by hand keep the precision of the object
+        let mut state = FixedState::default();
+        state.insert(Object::Current(crate::Line::L1, Deciamps(12)));
+        assert_eq!(state.line(crate::Line::L1).current, Some(Fixed::new(12, 1)));
+
+        assert_eq!(value_text("1-0:31.7.0(001.25*A)"), Some("001.25"));
+        assert_eq!(value_text("0-0:96.7.21(00004)"), Some("00004"));
+        assert_eq!(value_text("0-1:24.2.1(101209112500W)(12785.123*m3)"), None);
+    }
+
+    #[test]
+    fn history_wraparound() {
+        let mut history = StateHistory::<3>::new();
+        assert!(history.is_empty());
+        assert!(history.latest().is_none());
+
+        for wh in [1000, 2000, 3000] {
+            assert!(history.push(with_energy(wh)).is_none());
+        }
+
+        let evicted = history.push(with_energy(4000)).unwrap();
+        assert_eq!(
+            evicted.energy.get(Power::Active, Direction::FromGrid),
+            Some(1.0)
+        );
+        history.push(with_energy(5000));
+
+        assert_eq!(history.len(), 3);
+        assert_eq!(
+            history
+                .oldest()
+                .unwrap()
+                .energy
+                .get(Power::Active, Direction::FromGrid),
+            Some(3.0)
+        );
+        assert_eq!(
+            history
+                .latest()
+                .unwrap()
+                .energy
+                .get(Power::Active, Direction::FromGrid),
+            Some(5.0)
+        );
+
+        assert!(history
+            .iter()
+            .map(|s| s.energy.get(Power::Active, Direction::FromGrid).unwrap())
+            .eq([3.0, 4.0, 5.0]));
+    }
+
+    #[test]
+    fn history_energy_delta() {
+        let mut history = StateHistory::<4>::new();
+        assert_eq!(history.delta_energy_from_grid_active(), None);
+
+        for wh in [6136000, 6137500, 6138000] {
+            history.push(with_energy(wh));
+        }
+
+        assert_eq!(history.delta_energy_from_grid_active(), Some(2.0));
+    }
+
+    #[test]
+    fn smoothed() {
+        use super::{SmoothedState, Smoothing};
+
+        let power = |w| {
+            let mut s = State::default();
+            s.insert(Object::TotalPower(Direction::FromGrid, Watts(w).into()));
+            s
+        };
+
+        let mut mean = SmoothedState::<3>::new();
+        let mut ema = SmoothedState::<3>::with_smoothing(Smoothing::Exponential { alpha: 0.5 });
+        assert_eq!(mean.power(Power::Active, Direction::FromGrid), None);
+
+        for w in [9000, 1000, 2000, 3000] {
+            mean.push(power(w));
+            ema.push(power(w));
+        }
+        mean.push(State::default());
+
+        // 9 kW was evicted and the empty state is left out
+        assert_eq!(mean.power(Power::Active, Direction::FromGrid), Some(2.5));
+        assert_eq!(ema.power(Power::Active, Direction::FromGrid), Some(2.25));
+        assert_eq!(mean.current(crate::Line::L1), None);
+    }
+
+    #[cfg(feature = "time")]
+    #[test]
+    fn extremes() {
+        use super::Extremes;
+
+        let mut extremes = Extremes::default();
+        for (dt, da) in [
+            (datetime!(2024-01-01 12:00 +1), 120),
+            (datetime!(2024-01-01 12:01 +1), 254),
+            (datetime!(2024-01-01 12:02 +1), 98),
+        ] {
+            let mut state = State::default();
+            state.insert(Object::DateTime(dt));
+            state.insert(Object::Current(crate::Line::L2, Deciamps(da)));
+            extremes.update(&state);
+        }
+
+        let current = extremes.line(crate::Line::L2).current;
+        assert_eq!(current.max.unwrap().value, 25.4);
+        assert_eq!(
+            current.max.unwrap().time,
+            Some(datetime!(2024-01-01 12:01 +1))
+        );
+        assert_eq!(current.min.unwrap().value, 9.8);
+        assert_eq!(extremes.line(crate::Line::L1).current.max, None);
+
+        extremes.reset();
+        assert_eq!(extremes, Extremes::default());
+    }
+
+    #[cfg(feature = "time")]
+    #[test]
+    fn history_since() {
+        let mut history = StateHistory::<4>::new();
+        for minute in 0..4 {
+            let mut s = with_energy(1000);
+            s.insert(Object::DateTime(
+                datetime!(2024-01-01 12:00 +1) + time::Duration::minutes(minute),
+            ));
+            history.push(s);
+        }
+        history.push(State::default());
+
+        let since = history.iter_since(datetime!(2024-01-01 12:02 +1));
+        assert!(since.map(|s| s.datetime.unwrap().minute()).eq([2, 3]));
+        assert_eq!(history.mean_power(Power::Active, Direction::FromGrid), None);
+    }
+
+    #[test]
+    fn history_zero_capacity() {
+        let mut history = StateHistory::<0>::new();
+        assert!(history.push(State::default()).is_some());
+        assert!(history.latest().is_none());
+    }
+
+    #[test]
+    fn field_of_obis() {
+        for text in [
+            include_str!("../test/ell.txt"),
+            include_str!("../test/dsmr5.txt"),
+            include_str!("../test/emucs.txt"),
+        ] {
+            let telegram = Telegram::parse(text).unwrap();
+            for obj in telegram.objects().flatten() {
+                if let Some(field) = Field::of(&obj) {
+                    assert_eq!(Field::of_obis(obj.obis()), Some(field), "{:?}", obj.obis());
+                }
+            }
+        }
+        assert_eq!(Field::of_obis(Obis(1, 0, 40, 7, 0)), None);
+        assert_eq!(Field::of_obis(Obis(1, 0, 1, 8, 5)), None);
+    }
+
+    #[test]
+    fn observer() {
+        let mut state = State::default();
+        let mut updates = std::vec::Vec::new();
+        let mut observer = |field, old, new| updates.push((field, old, new));
+
+        state.insert_observed(Object::PowerFailures(3), &mut observer);
+        state.insert_observed(Object::PowerFailures(4), &mut observer);
+        state.insert_observed(Object::TariffIndicator(1), &mut observer);
+        state.insert_observed(
+            Object::EnergyTariff(Direction::FromGrid, 9, WattHours(1).into()),
+            &mut observer,
+        );
+        assert_eq!(
+            updates,
+            [
+                (Field::PowerFailures, None, 3.),
+                (Field::PowerFailures, Some(3.), 4.)
+            ]
+        );
+
+        // both the channel and the sub-meter of a gas reading
+        updates.clear();
+        let mut observer = |field, old, new| updates.push((field, old, new));
+        let telegram = Telegram::parse(include_str!("../test/dsmr5.txt")).unwrap();
+        state
+            .update_from_telegram_observed(&telegram, &mut observer)
+            .unwrap();
+        let gas = updates
+            .iter()
+            .filter(|(f, ..)| matches!(f, Field::ChannelReading(_) | Field::SubMeter(_)))
+            .collect::<std::vec::Vec<_>>();
+        assert_eq!(
+            gas,
+            [
+                &(Field::ChannelReading(1), None, 12785.123),
+                &(Field::SubMeter(Medium::Gas), None, 12785.123)
+            ]
+        );
+    }
+}
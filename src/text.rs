@@ -0,0 +1,190 @@
+use core::fmt::{self, Debug, Write};
+
+use crate::{Error, Result};
+
+/// Capacity of a [`Text`] in bytes.
+pub const TEXT_CAPACITY: usize = 64;
+
+/// A short string stored inline, such as an equipment identifier or a text
+/// message, decoded from the hexadecimal form used in telegrams.
+///
+/// Strings longer than [`TEXT_CAPACITY`] bytes are truncated, which is
+/// reported by [`Text::is_truncated`].
+///
+/// ```
+/// use han::Text;
+///
+/// let text = Text::from_hex("4B384547303034303436333935353037")?;
+/// assert_eq!(text.as_str(), Some("K8EG004046395507"));
+/// # Ok::<(), han::Error>(())
+/// ```
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub struct Text {
+    bytes: [u8; TEXT_CAPACITY],
+    len: u8,
+    truncated: bool,
+}
+
+impl Text {
+    /// Store a string as is, truncating it to [`TEXT_CAPACITY`] bytes.
+    pub fn new(s: &str) -> Self {
+        Self::from_bytes(s.as_bytes())
+    }
+
+    fn from_bytes(b: &[u8]) -> Self {
+        let len = b.len().min(TEXT_CAPACITY);
+        let mut bytes = [0; TEXT_CAPACITY];
+        bytes[..len].copy_from_slice(&b[..len]);
+
+        Self {
+            bytes,
+            len: len as u8,
+            truncated: len < b.len(),
+        }
+    }
+
+    /// Decode a string of hexadecimal digit pairs.
+    pub fn from_hex(hex: &str) -> Result<Self> {
+        if !hex.len().is_multiple_of(2) {
+            return Err(Error::InvalidFormat);
+        }
+
+        let mut text = Self {
+            bytes: [0; TEXT_CAPACITY],
+            len: 0,
+            truncated: hex.len() / 2 > TEXT_CAPACITY,
+        };
+
+        for (i, pair) in hex.as_bytes().chunks(2).enumerate() {
+            let pair = core::str::from_utf8(pair).map_err(|_| Error::InvalidFormat)?;
+            let b = u8::from_str_radix(pair, 16).map_err(|_| Error::InvalidFormat)?;
+            if let Some(slot) = text.bytes.get_mut(i) {
+                *slot = b;
+                text.len += 1;
+            }
+        }
+
+        Ok(text)
+    }
+
+    /// The decoded bytes.
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.bytes[..usize::from(self.len)]
+    }
+
+    /// The decoded bytes as a string, if they are valid UTF-8.
+    pub fn as_str(&self) -> Option<&str> {
+        core::str::from_utf8(self.as_bytes()).ok()
+    }
+
+    /// Returns `true` if the string did not fit and was truncated.
+    pub fn is_truncated(&self) -> bool {
+        self.truncated
+    }
+
+    /// Returns `true` if the string is empty.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Write the bytes in the hexadecimal form used in telegrams.
+    pub(crate) fn write_hex(&self, f: &mut impl Write) -> fmt::Result {
+        for b in self.as_bytes() {
+            write!(f, "{:02X}", b)?;
+        }
+        Ok(())
+    }
+}
+
+impl Debug for Text {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.as_str() {
+            Some(s) => Debug::fmt(s, f),
+            None => Debug::fmt(self.as_bytes(), f),
+        }
+    }
+}
+
+#[cfg(feature = "defmt-03")]
+impl defmt::Format for Text {
+    fn format(&self, f: defmt::Formatter) {
+        match self.as_str() {
+            Some(s) => defmt::write!(f, "{=str}", s),
+            None => defmt::write!(f, "{=[u8]}", self.as_bytes()),
+        }
+    }
+}
+
+/// Serialized as a string, or as bytes if not valid UTF-8.
+#[cfg(feature = "serde")]
+impl serde::Serialize for Text {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self.as_str() {
+            Some(s) => serializer.serialize_str(s),
+            None => serializer.serialize_bytes(self.as_bytes()),
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for Text {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct Visitor;
+
+        impl serde::de::Visitor<'_> for Visitor {
+            type Value = Text;
+
+            fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                f.write_str("a string or bytes")
+            }
+
+            fn visit_str<E>(self, v: &str) -> Result<Text, E> {
+                Ok(Text::new(v))
+            }
+
+            fn visit_bytes<E>(self, v: &[u8]) -> Result<Text, E> {
+                Ok(Text::from_bytes(v))
+            }
+        }
+
+        deserializer.deserialize_str(Visitor)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::string::String;
+
+    use crate::Error;
+
+    use super::{Text, TEXT_CAPACITY};
+
+    #[test]
+    fn from_hex() {
+        let text = Text::from_hex("3232323241424344").unwrap();
+        assert_eq!(text.as_str(), Some("2222ABCD"));
+        assert!(!text.is_truncated());
+
+        let mut hex = String::new();
+        text.write_hex(&mut hex).unwrap();
+        assert_eq!(hex, "3232323241424344");
+
+        assert!(Text::from_hex("").unwrap().is_empty());
+        assert!(matches!(Text::from_hex("323"), Err(Error::InvalidFormat)));
+        assert!(matches!(Text::from_hex("3G"), Err(Error::InvalidFormat)));
+        assert_eq!(Text::from_hex("FF").unwrap().as_str(), None);
+    }
+
+    #[test]
+    fn truncated() {
+        let hex = "41".repeat(TEXT_CAPACITY + 1);
+        let text = Text::from_hex(&hex).unwrap();
+        assert!(text.is_truncated());
+        assert_eq!(text.as_bytes().len(), TEXT_CAPACITY);
+
+        let text = Text::new(&"A".repeat(TEXT_CAPACITY + 1));
+        assert!(text.is_truncated());
+        assert_eq!(text.as_bytes().len(), TEXT_CAPACITY);
+        assert!(!Text::new("ABC").is_truncated());
+    }
+}
@@ -0,0 +1,169 @@
+use core::fmt::Display;
+use core::str::FromStr;
+
+use crate::Error;
+
+/// Timestamp of an [`Object`](crate::Object): an [`OffsetDateTime`] with the
+/// `time` feature (enabled by default), otherwise a [`RawTimestamp`].
+///
+/// [`OffsetDateTime`]: time::OffsetDateTime
+#[cfg(feature = "time")]
+pub type Timestamp = time::OffsetDateTime;
+
+/// Timestamp of an [`Object`](crate::Object): an `OffsetDateTime` with the
+/// `time` feature (enabled by default), otherwise a [`RawTimestamp`].
+#[cfg(not(feature = "time"))]
+pub type Timestamp = RawTimestamp;
+
+/// Local date and time as written by the meter, for builds without the
+/// `time` feature that do their own calendar handling.
+///
+/// ```
+/// use han::RawTimestamp;
+///
+/// let ts = "220717231648S".parse::<RawTimestamp>()?;
+/// assert_eq!((ts.year, ts.month, ts.day), (2022, 7, 17));
+/// assert_eq!((ts.hour, ts.min, ts.sec), (23, 16, 48));
+/// assert_eq!(ts.dst, Some(true));
+/// assert_eq!(ts.to_string(), "220717231648S");
+/// # Ok::<(), han::Error>(())
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "defmt-03", derive(defmt::Format))]
+pub struct RawTimestamp {
+    /// Full year, e.g. 2022.
+    pub year: u16,
+    /// Month of the year (1–12).
+    pub month: u8,
+    /// Day of the month (1–31).
+    pub day: u8,
+    /// Hour (0–23).
+    pub hour: u8,
+    /// Minute (0–59).
+    pub min: u8,
+    /// Second (0–59).
+    pub sec: u8,
+    /// Whether daylight saving time is in effect (`S`) or not (`W`), if
+    /// indicated by the meter.
+    pub dst: Option<bool>,
+}
+
+impl RawTimestamp {
+    /// Check that the date and time exist.
+    pub(crate) fn validate(self) -> Result<Self, Error> {
+        let leap = self.year.is_multiple_of(4)
+            && (!self.year.is_multiple_of(100) || self.year.is_multiple_of(400));
+        let days = match self.month {
+            2 if leap => 29,
+            2 => 28,
+            4 | 6 | 9 | 11 => 30,
+            1..=12 => 31,
+            _ => return Err(Error::InvalidFormat),
+        };
+        if !(1..=days).contains(&self.day) || self.hour > 23 || self.min > 59 || self.sec > 59 {
+            return Err(Error::InvalidFormat);
+        }
+        Ok(self)
+    }
+
+    /// The timestamp at `offset`.
+    #[cfg(feature = "time")]
+    pub(crate) fn assume_offset(
+        self,
+        offset: time::UtcOffset,
+    ) -> Result<time::OffsetDateTime, Error> {
+        let month = time::Month::try_from(self.month).map_err(|_| Error::InvalidFormat)?;
+        let date = time::Date::from_calendar_date(self.year.into(), month, self.day)
+            .map_err(|_| Error::InvalidFormat)?;
+        let time = time::Time::from_hms(self.hour, self.min, self.sec)
+            .map_err(|_| Error::InvalidFormat)?;
+        Ok(time::PrimitiveDateTime::new(date, time).assume_offset(offset))
+    }
+}
+
+impl FromStr for RawTimestamp {
+    type Err = Error;
+
+    /// Parse a `YYMMDDhhmmssX` timestamp, where the DST indicator `X` is
+    /// optional.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let two = |i: usize| match s.as_bytes().get(i..i + 2) {
+            Some(&[a @ b'0'..=b'9', b @ b'0'..=b'9']) => Ok((a - b'0') * 10 + b - b'0'),
+            _ => Err(Error::InvalidFormat),
+        };
+        let dst = match s.get(12..) {
+            Some("W") => Some(false),
+            Some("S") => Some(true),
+            Some("") => None,
+            _ => return Err(Error::InvalidFormat),
+        };
+
+        Self {
+            year: 2000 + u16::from(two(0)?),
+            month: two(2)?,
+            day: two(4)?,
+            hour: two(6)?,
+            min: two(8)?,
+            sec: two(10)?,
+            dst,
+        }
+        .validate()
+    }
+}
+
+impl Display for RawTimestamp {
+    /// Write the timestamp in the `YYMMDDhhmmssX` form used by telegrams.
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(
+            f,
+            "{:02}{:02}{:02}{:02}{:02}{:02}",
+            self.year % 100,
+            self.month,
+            self.day,
+            self.hour,
+            self.min,
+            self.sec
+        )?;
+        match self.dst {
+            Some(true) => f.write_str("S"),
+            Some(false) => f.write_str("W"),
+            None => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::string::ToString;
+
+    use super::RawTimestamp;
+
+    #[test]
+    fn parse() {
+        let ts = "240229000000W".parse::<RawTimestamp>().unwrap();
+        assert_eq!((ts.year, ts.month, ts.day), (2024, 2, 29));
+        assert_eq!(ts.dst, Some(false));
+        assert_eq!("240229000000".parse::<RawTimestamp>().unwrap().dst, None);
+
+        for s in [
+            "230229000000W", // not a leap year
+            "221301000000W",
+            "220732000000W",
+            "220717240000W",
+            "220717236000W",
+            "22071723164W",
+            "220717231648X",
+            "2207172316+8W",
+        ] {
+            assert!(s.parse::<RawTimestamp>().is_err(), "{s}");
+        }
+    }
+
+    #[test]
+    fn round_trip() {
+        for s in ["220717231648S", "221022162844W", "220101000000"] {
+            assert_eq!(s.parse::<RawTimestamp>().unwrap().to_string(), s);
+        }
+    }
+}
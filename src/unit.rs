@@ -0,0 +1,150 @@
+use core::fmt::Display;
+use core::str::FromStr;
+
+use crate::Error;
+
+/// The unit of a value, as written after the `*` in a telegram.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "defmt-03", derive(defmt::Format))]
+pub enum Unit {
+    /// Watt-hour.
+    Wh,
+    /// Kilowatt-hour.
+    KWh,
+    /// Volt-ampere reactive hour.
+    VArh,
+    /// Kilovolt-ampere reactive hour.
+    KVArh,
+    /// Volt-ampere hour.
+    VAh,
+    /// Kilovolt-ampere hour.
+    KVAh,
+    /// Watt.
+    W,
+    /// Kilowatt.
+    KW,
+    /// Volt-ampere reactive.
+    VAr,
+    /// Kilovolt-ampere reactive.
+    KVAr,
+    /// Volt-ampere.
+    VA,
+    /// Kilovolt-ampere.
+    KVA,
+    /// Volt.
+    V,
+    /// Ampere.
+    A,
+    /// Hertz.
+    Hz,
+    /// Cubic metre.
+    M3,
+    /// Gigajoule.
+    GJ,
+}
+
+impl Unit {
+    /// The unit as written in a telegram.
+    pub const fn as_str(&self) -> &'static str {
+        match self {
+            Unit::Wh => "Wh",
+            Unit::KWh => "kWh",
+            Unit::VArh => "varh",
+            Unit::KVArh => "kvarh",
+            Unit::VAh => "VAh",
+            Unit::KVAh => "kVAh",
+            Unit::W => "W",
+            Unit::KW => "kW",
+            Unit::VAr => "var",
+            Unit::KVAr => "kvar",
+            Unit::VA => "VA",
+            Unit::KVA => "kVA",
+            Unit::V => "V",
+            Unit::A => "A",
+            Unit::Hz => "Hz",
+            Unit::M3 => "m3",
+            Unit::GJ => "GJ",
+        }
+    }
+
+    /// The unit with the given DLMS/COSEM unit enumeration, as sent by meters
+    /// pushing binary data. Only base units have such codes.
+    ///
+    /// ```
+    /// use han::Unit;
+    ///
+    /// assert_eq!(Unit::from_cosem(27), Some(Unit::W));
+    /// ```
+    pub fn from_cosem(code: u8) -> Option<Self> {
+        match code {
+            13 => Some(Unit::M3),
+            27 => Some(Unit::W),
+            28 => Some(Unit::VA),
+            29 => Some(Unit::VAr),
+            30 => Some(Unit::Wh),
+            31 => Some(Unit::VAh),
+            32 => Some(Unit::VArh),
+            33 => Some(Unit::A),
+            35 => Some(Unit::V),
+            44 => Some(Unit::Hz),
+            _ => None,
+        }
+    }
+}
+
+impl Display for Unit {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for Unit {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "Wh" => Ok(Unit::Wh),
+            "kWh" => Ok(Unit::KWh),
+            "varh" => Ok(Unit::VArh),
+            "kvarh" => Ok(Unit::KVArh),
+            "VAh" => Ok(Unit::VAh),
+            "kVAh" => Ok(Unit::KVAh),
+            "W" => Ok(Unit::W),
+            "kW" => Ok(Unit::KW),
+            "var" => Ok(Unit::VAr),
+            "kvar" => Ok(Unit::KVAr),
+            "VA" => Ok(Unit::VA),
+            "kVA" => Ok(Unit::KVA),
+            "V" => Ok(Unit::V),
+            "A" => Ok(Unit::A),
+            "Hz" => Ok(Unit::Hz),
+            "m3" => Ok(Unit::M3),
+            "GJ" => Ok(Unit::GJ),
+            _ => Err(Error::InvalidFormat),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Unit;
+
+    #[test]
+    fn round_trip() {
+        for unit in [
+            Unit::KWh,
+            Unit::KVArh,
+            Unit::KW,
+            Unit::KVAr,
+            Unit::V,
+            Unit::A,
+            Unit::M3,
+        ] {
+            assert_eq!(unit.as_str().parse::<Unit>().unwrap(), unit);
+        }
+
+        assert!("kwh".parse::<Unit>().is_err());
+        assert!("".parse::<Unit>().is_err());
+    }
+}
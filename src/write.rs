@@ -0,0 +1,246 @@
+use core::fmt::{self, Write};
+
+#[cfg(feature = "alloc")]
+use alloc::{string::ToString, vec::Vec};
+
+use crate::{obis::Object, Telegram};
+
+/// Forwards writes while updating the CRC.
+struct CrcWriter<W> {
+    w: W,
+    crc: crc16::State<crc16::ARC>,
+}
+
+impl<W: Write> Write for CrcWriter<W> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        self.crc.update(s.as_bytes());
+        self.w.write_str(s)
+    }
+}
+
+/// An encoder writing a telegram, including its checksum, to a
+/// [`fmt::Write`].
+///
+/// ```
+/// use han::{Direction, Object, Power, TelegramWriter, WattHours};
+///
+/// let mut w = TelegramWriter::new(String::new(), "ELL", Some('5'), "\\253833635_A")?;
+/// w.object(&Object::Energy(Direction::FromGrid, WattHours(6136936).into()))?;
+/// let s = w.finish()?;
+///
+/// let telegram = han::Telegram::parse(&s).unwrap();
+/// assert_eq!(telegram.flag_id, "ELL");
+/// assert_eq!(telegram.objects().count(), 1);
+/// # Ok::<(), core::fmt::Error>(())
+/// ```
+pub struct TelegramWriter<W> {
+    inner: CrcWriter<W>,
+}
+
+impl<W: Write> TelegramWriter<W> {
+    /// Start a telegram by writing its header.
+    pub fn new(
+        w: W,
+        flag_id: &str,
+        baud_indicator: Option<char>,
+        identification: &str,
+    ) -> Result<Self, fmt::Error> {
+        let mut inner = CrcWriter {
+            w,
+            crc: crc16::State::new(),
+        };
+
+        write!(inner, "/{}", flag_id)?;
+        if let Some(baud) = baud_indicator {
+            inner.write_char(baud)?;
+        }
+        write!(inner, "{}\r\n\r\n", identification)?;
+
+        Ok(Self { inner })
+    }
+
+    /// Write an object on a line of its own.
+    pub fn object(&mut self, obj: &Object) -> fmt::Result {
+        write!(self.inner, "{}\r\n", obj)
+    }
+
+    /// Write a raw line, e.g. an object not covered by [`Object`]. The line
+    /// ending is added.
+    pub fn line(&mut self, line: &str) -> fmt::Result {
+        write!(self.inner, "{}\r\n", line)
+    }
+
+    /// End the telegram by writing the `!` and the checksum, returning the
+    /// underlying writer.
+    pub fn finish(mut self) -> Result<W, fmt::Error> {
+        self.inner.write_char('!')?;
+        let crc = self.inner.crc.get();
+        write!(self.inner.w, "{:04X}\r\n", crc)?;
+        Ok(self.inner.w)
+    }
+}
+
+impl Telegram<'_> {
+    /// Encode the telegram, with a freshly computed checksum.
+    ///
+    /// The object lines are written as received, so parsing the output
+    /// yields an identical telegram.
+    pub fn write_to<W: Write>(&self, w: W) -> Result<W, fmt::Error> {
+        let mut writer =
+            TelegramWriter::new(w, self.flag_id, self.baud_indicator, self.identification)?;
+        for line in self.lines() {
+            writer.line(line)?;
+        }
+        writer.finish()
+    }
+}
+
+/// A builder for complete telegrams, e.g. for tests of downstream crates.
+///
+/// The telegram is written, including its checksum, through
+/// [`Display`](fmt::Display) or [`TelegramBuilder::to_bytes`].
+///
+/// ```
+/// use han::{Decivolts, Line, Object, TelegramBuilder};
+///
+/// let builder = TelegramBuilder::new("ELL", "xyz").object(Object::Voltage(Line::L1, Decivolts(2301)));
+///
+/// let bytes = builder.to_bytes();
+/// let readout = han::Reader::new(bytes.into_iter()).next().unwrap();
+/// assert_eq!(readout.to_telegram()?.identification, "xyz");
+///
+/// let state = builder.to_state();
+/// assert_eq!(state.lines[0].voltage, Some(230.1));
+/// # Ok::<(), han::Error>(())
+/// ```
+#[cfg(feature = "alloc")]
+#[derive(Debug, Clone)]
+pub struct TelegramBuilder<'a> {
+    flag_id: &'a str,
+    baud_indicator: Option<char>,
+    identification: &'a str,
+    objects: Vec<Object>,
+}
+
+#[cfg(feature = "alloc")]
+impl<'a> TelegramBuilder<'a> {
+    /// Start building a telegram without objects.
+    pub fn new(flag_id: &'a str, identification: &'a str) -> Self {
+        Self {
+            flag_id,
+            baud_indicator: None,
+            identification,
+            objects: Vec::new(),
+        }
+    }
+
+    /// Set the baud rate character following the flag id.
+    pub fn baud_indicator(mut self, baud_indicator: char) -> Self {
+        self.baud_indicator = Some(baud_indicator);
+        self
+    }
+
+    /// Add an object.
+    pub fn object(mut self, obj: Object) -> Self {
+        self.objects.push(obj);
+        self
+    }
+
+    /// Add several objects.
+    pub fn objects(mut self, objects: impl IntoIterator<Item = Object>) -> Self {
+        self.objects.extend(objects);
+        self
+    }
+
+    /// Encode the telegram as raw bytes, as sent by a meter.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        self.to_string().into_bytes()
+    }
+
+    /// The [`State`](crate::state::State) that the telegram describes.
+    pub fn to_state(&self) -> crate::state::State {
+        let mut state = crate::state::State::default();
+        for obj in &self.objects {
+            state.insert(*obj);
+        }
+        state
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl fmt::Display for TelegramBuilder<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut writer =
+            TelegramWriter::new(f, self.flag_id, self.baud_indicator, self.identification)?;
+        for obj in &self.objects {
+            writer.object(obj)?;
+        }
+        writer.finish().map(|_| ())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::string::String;
+
+    use crate::{Deciamps, Decivolts, Line, Object, Telegram};
+
+    use super::TelegramWriter;
+
+    #[test]
+    fn round_trip() {
+        let s = include_str!("../test/ell.txt");
+        let telegram = Telegram::parse(s).unwrap();
+
+        let encoded = telegram.write_to(String::new()).unwrap();
+        assert_eq!(encoded, s);
+
+        let nobaud = include_str!("../test/ell-nobaud.txt");
+        let encoded = Telegram::parse(nobaud)
+            .unwrap()
+            .write_to(String::new())
+            .unwrap();
+        assert_eq!(encoded, nobaud);
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn builder() {
+        use std::string::ToString;
+
+        let telegram = Telegram::parse(include_str!("../test/ell.txt")).unwrap();
+        let builder = super::TelegramBuilder::new("ELL", "\\253833635_A")
+            .baud_indicator('5')
+            .objects(telegram.objects().map(Result::unwrap));
+
+        assert_eq!(builder.to_string(), include_str!("../test/ell.txt"));
+        assert_eq!(
+            builder.to_state(),
+            crate::state::State::from_telegram(&telegram).unwrap()
+        );
+    }
+
+    #[test]
+    fn objects() {
+        let objects = [
+            Object::Voltage(Line::L1, Decivolts(2301)),
+            Object::Current(Line::L2, Deciamps(15)),
+        ];
+
+        let mut w = TelegramWriter::new(String::new(), "ABC", None, "meter").unwrap();
+        for obj in &objects {
+            w.object(obj).unwrap();
+        }
+        let s = w.finish().unwrap();
+
+        let telegram = Telegram::parse(&s).unwrap();
+        assert_eq!(telegram.identification, "meter");
+        assert!(telegram.objects().map(Result::unwrap).eq(objects));
+
+        let empty = TelegramWriter::new(String::new(), "ABC", None, "")
+            .unwrap()
+            .finish()
+            .unwrap();
+        assert!(Telegram::parse(&empty).unwrap().is_empty());
+    }
+}